@@ -10,6 +10,7 @@ fn show(c: &mut Criterion) {
                 &gitu::cli::Args {
                     command: Some(Commands::Show {
                         reference: "f4de01c0a12794d7b42a77b2138aa64119b90ea5".into(),
+                        json: false,
                     }),
                     print: true,
                     ..Default::default()
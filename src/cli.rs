@@ -1,4 +1,6 @@
+use crate::config::InitialScreen;
 use clap::{Parser, Subcommand};
+use std::path::PathBuf;
 
 #[derive(Default, Debug, Parser)]
 #[command(name = "gitu")]
@@ -12,9 +14,24 @@ pub struct Args {
     ///     - single char-keys: a, b, c, ...
     ///     - special keys: <backspace>, <enter>, <up>, <tab>, <delete>, <esc>, ...
     ///     - modifiers: <ctrl+a>, <ctrl+shift+alt+a>, <shift+delete>
+    ///     - waiting for a command to finish: <wait-cmd>
+    ///     - waiting a while: <sleep:500> (milliseconds)
     #[clap(short, long, verbatim_doc_comment)]
     pub keys: Option<String>,
 
+    /// Open the repository at this path rather than the one in the current directory.
+    #[clap(short = 'C', long = "repo")]
+    pub repo: Option<PathBuf>,
+
+    /// List the repositories in these directories, or in `workspace_dirs` if none are given,
+    /// with their branch and state. Each is opened with <enter>.
+    #[clap(long, num_args = 0.., value_name = "DIR")]
+    pub workspace: Option<Vec<PathBuf>>,
+
+    /// Open this screen instead of the configured `initial_screen`.
+    #[clap(long, value_enum)]
+    pub screen: Option<InitialScreen>,
+
     /// Print one frame and exit. Useful for debugging.
     #[clap(long, action)]
     pub print: bool,
@@ -30,5 +47,54 @@ pub struct Args {
 
 #[derive(Debug, Subcommand)]
 pub enum Commands {
-    Show { reference: String },
+    /// Show a commit, or the combined changes of a range like A..B
+    Show {
+        reference: String,
+        /// Print the diff as JSON instead
+        #[clap(long, action)]
+        json: bool,
+    },
+    /// Show the unstaged changes, on the status screen
+    Diff {
+        /// The staged changes instead
+        #[clap(long, alias = "cached", action)]
+        staged: bool,
+        /// Print the diff as JSON instead
+        #[clap(long, action)]
+        json: bool,
+    },
+    /// Open the status screen
+    Status {
+        /// Print the status as JSON instead: branch, files, stashes and recent commits
+        #[clap(long, action)]
+        porcelain_json: bool,
+    },
+    /// Open the log of the current branch
+    Log,
+    /// Open the list of branches, remotes and tags
+    Refs,
+    /// Create a repository, here or in `dir`, then open its status screen
+    Init { dir: Option<PathBuf> },
+    /// Clone a repository, showing its progress, then open its status screen
+    Clone {
+        url: String,
+        /// Defaults to a directory named after the repository, like `git clone`
+        dir: Option<PathBuf>,
+    },
+}
+
+impl Args {
+    /// Whether to print a diff or the status as JSON rather than run the ui.
+    pub fn json(&self) -> bool {
+        matches!(
+            self.command,
+            Some(
+                Commands::Show { json: true, .. }
+                    | Commands::Diff { json: true, .. }
+                    | Commands::Status {
+                        porcelain_json: true
+                    }
+            )
+        )
+    }
 }
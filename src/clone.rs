@@ -0,0 +1,172 @@
+use crate::{term::Term, Res};
+use crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::{
+    text::Line,
+    widgets::{Block, Borders, Paragraph},
+};
+use std::{
+    env, fs,
+    io::Read,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::mpsc::{self, RecvTimeoutError},
+    thread,
+    time::Duration,
+};
+
+/// Runs `git clone` into `dir`, or a directory named after the repository, drawing its progress
+/// (counting, receiving and resolving objects) as it goes. Returns where it was cloned to.
+/// Pressing `q` or Ctrl-C, as given by `poll_event`, stops it.
+pub(crate) fn clone(
+    term: &mut Term,
+    url: &str,
+    dir: Option<&Path>,
+    mut poll_event: impl FnMut() -> Res<Option<Event>>,
+) -> Res<PathBuf> {
+    let dir = match dir {
+        Some(dir) => dir.to_path_buf(),
+        None => PathBuf::from(default_dir(url).ok_or("Couldn't name a directory to clone into")?),
+    };
+    let existed = dir.exists();
+
+    let mut cmd = Command::new("git");
+    cmd.args(["clone", "--progress", url])
+        .arg(&dir)
+        // A prompt for credentials would be drawn over, and wait on a terminal git can't read from
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped());
+
+    // The same goes for ssh asking about host keys or passphrases, unless told how to run it
+    if env::var_os("GIT_SSH_COMMAND").is_none()
+        && env::var_os("GIT_SSH").is_none()
+        && git2::Config::open_default()
+            .and_then(|config| config.get_string("core.sshCommand"))
+            .is_err()
+    {
+        cmd.env("GIT_SSH_COMMAND", "ssh -o BatchMode=yes");
+    }
+
+    let mut child = cmd.spawn()?;
+    let mut stderr = child
+        .stderr
+        .take()
+        .ok_or("Couldn't read the progress of git")?;
+
+    // Read on another thread, so that keys are handled while git is quiet
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut buffer = [0; 1024];
+        while let Ok(read @ 1..) = stderr.read(&mut buffer) {
+            if sender.send(buffer[..read].to_vec()).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut progress = Progress::default();
+    draw(term, url, &progress)?;
+
+    loop {
+        if let Some(Event::Key(key)) = poll_event()? {
+            let ctrl_c = key.code == KeyCode::Char('c') && key.modifiers == KeyModifiers::CONTROL;
+            if key.kind == KeyEventKind::Press && (key.code == KeyCode::Char('q') || ctrl_c) {
+                child.kill()?;
+                child.wait()?;
+                if !existed {
+                    let _ = fs::remove_dir_all(&dir);
+                }
+
+                return Err("Clone stopped".into());
+            }
+        }
+
+        match receiver.recv_timeout(Duration::from_millis(50)) {
+            Ok(output) => {
+                progress.push(&String::from_utf8_lossy(&output));
+                draw(term, url, &progress)?;
+            }
+            Err(RecvTimeoutError::Timeout) => (),
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+
+    if !child.wait()?.success() {
+        return Err(progress.lines.join("\n").into());
+    }
+
+    Ok(dir)
+}
+
+/// Like git, the last part of the url without any ".git", e.g. "gitu" for "https://github.com/altsem/gitu.git".
+pub(crate) fn default_dir(url: &str) -> Option<&str> {
+    let name = url
+        .trim_end_matches('/')
+        .trim_end_matches(".git")
+        .trim_end_matches('/')
+        .rsplit(['/', ':'])
+        .next()?;
+
+    (!name.is_empty()).then_some(name)
+}
+
+/// What git printed so far. Progress is printed over and over on the same line, ended by `\r`,
+/// so a line updates the last one if they're of the same phase, like "Receiving objects".
+#[derive(Default)]
+struct Progress {
+    lines: Vec<String>,
+    partial: String,
+}
+
+impl Progress {
+    fn push(&mut self, output: &str) {
+        for char in output.chars() {
+            match char {
+                '\r' | '\n' => {
+                    let line = std::mem::take(&mut self.partial);
+                    if !line.is_empty() {
+                        self.push_line(line);
+                    }
+                }
+                _ => self.partial.push(char),
+            }
+        }
+    }
+
+    fn push_line(&mut self, line: String) {
+        let phase = |line: &str| line.split_once(':').map(|(phase, _)| phase.to_string());
+
+        match self.lines.last_mut() {
+            Some(last) if phase(last).is_some() && phase(last) == phase(&line) => *last = line,
+            _ => self.lines.push(line),
+        }
+    }
+}
+
+fn draw(term: &mut Term, url: &str, progress: &Progress) -> Res<()> {
+    term.draw(|frame| {
+        let area = frame.area();
+        let lines = progress
+            .lines
+            .iter()
+            .chain(Some(&progress.partial).filter(|partial| !partial.is_empty()))
+            .map(|line| Line::raw(line.as_str()))
+            .collect::<Vec<_>>();
+
+        // Keeps the latest lines in view
+        let visible = area.height.saturating_sub(2) as usize;
+        let skipped = lines.len().saturating_sub(visible);
+
+        frame.render_widget(
+            Paragraph::new(lines[skipped..].to_vec()).block(
+                Block::new()
+                    .borders(Borders::TOP)
+                    .title(format!("Cloning {url}")),
+            ),
+            area,
+        );
+    })?;
+
+    Ok(())
+}
@@ -1,22 +1,50 @@
+use crate::config::CmdNotification;
 use crate::config::Config;
+use ansi_to_tui::IntoText;
 use itertools::Itertools;
 use ratatui::style::Style;
 use ratatui::style::Stylize;
 use ratatui::text::Line;
 use ratatui::text::Text;
 use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::VecDeque;
 use std::iter;
 use std::process::Command;
+use std::rc::Rc;
 use std::sync::Arc;
 use std::sync::RwLock;
 
+pub(crate) type CmdLogHistory = Rc<RefCell<VecDeque<(usize, Arc<RwLock<CmdLogEntry>>)>>>;
+
 pub(crate) struct CmdLog {
     pub(crate) entries: Vec<Arc<RwLock<CmdLogEntry>>>,
+    /// Everything logged this session (up to `general.cmd_log_limit`), numbered in order.
+    /// Unlike `entries`, this isn't cleared as keys are pressed.
+    pub(crate) history: CmdLogHistory,
+    logged: usize,
+    limit: usize,
 }
 
 impl CmdLog {
-    pub fn new() -> Self {
-        Self { entries: vec![] }
+    pub fn new(limit: usize) -> Self {
+        Self {
+            entries: vec![],
+            history: Rc::new(RefCell::new(VecDeque::new())),
+            logged: 0,
+            limit,
+        }
+    }
+
+    fn add(&mut self, entry: Arc<RwLock<CmdLogEntry>>) {
+        let mut history = self.history.borrow_mut();
+        history.push_back((self.logged, Arc::clone(&entry)));
+        if history.len() > self.limit {
+            history.pop_front();
+        }
+
+        self.logged += 1;
+        self.entries.push(entry);
     }
 
     pub fn push_cmd(&mut self, cmd: &Command) -> Arc<RwLock<CmdLogEntry>> {
@@ -25,7 +53,7 @@ impl CmdLog {
             out: None,
         }));
 
-        self.entries.push(Arc::clone(&value));
+        self.add(Arc::clone(&value));
         value
     }
 
@@ -39,12 +67,12 @@ impl CmdLog {
             out: Some(out),
         }));
 
-        self.entries.push(Arc::clone(&value));
+        self.add(Arc::clone(&value));
         value
     }
 
     pub fn push(&mut self, entry: CmdLogEntry) {
-        self.entries.push(Arc::new(RwLock::new(entry)));
+        self.add(Arc::new(RwLock::new(entry)));
     }
 
     pub fn clear(&mut self) {
@@ -86,7 +114,7 @@ pub(crate) fn format_log_entry<'a>(
             if out.is_empty() {
                 vec![]
             } else {
-                Text::raw(out.to_string()).lines
+                output_lines(out)
             }
         }))
         .collect::<Vec<_>>(),
@@ -105,6 +133,26 @@ pub(crate) fn format_log_entry<'a>(
     }
 }
 
+/// Keeps the colors of the output, such as of hooks or the remote.
+fn output_lines<'a>(out: &str) -> Vec<Line<'a>> {
+    out.into_text()
+        .map(|text| text.lines)
+        .unwrap_or_else(|_| Text::raw(out.to_string()).lines)
+}
+
+/// What to write to the terminal to notify that a command is done, if anything.
+pub(crate) fn notification(mode: CmdNotification, message: &str) -> Option<String> {
+    match mode {
+        CmdNotification::Off => None,
+        CmdNotification::Bell => Some("\x07".into()),
+        // Control characters would end the sequence early
+        CmdNotification::Desktop => Some(format!(
+            "\x1b]9;{}\x07",
+            message.replace(|c: char| c.is_control(), " ")
+        )),
+    }
+}
+
 pub(crate) enum CmdLogEntry {
     Cmd {
         args: Cow<'static, str>,
@@ -113,3 +161,22 @@ pub(crate) enum CmdLogEntry {
     Error(String),
     Info(String),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::notification;
+    use crate::config::CmdNotification;
+
+    #[test]
+    fn notifications() {
+        assert_eq!(notification(CmdNotification::Off, "done"), None);
+        assert_eq!(
+            notification(CmdNotification::Bell, "done").as_deref(),
+            Some("\x07")
+        );
+        assert_eq!(
+            notification(CmdNotification::Desktop, "git fetch\x07 done").as_deref(),
+            Some("\x1b]9;git fetch  done\x07")
+        );
+    }
+}
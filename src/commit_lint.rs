@@ -0,0 +1,81 @@
+use crate::config::CommitLintConfig;
+use regex::Regex;
+use std::sync::LazyLock;
+
+static CONVENTIONAL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[a-z]+(\([^()]+\))?!?: \S").unwrap());
+
+/// Checks a commit message (without comment lines) against the configured rules,
+/// returning a description of each one it breaks.
+pub(crate) fn lint(config: &CommitLintConfig, message: &str) -> Vec<String> {
+    let mut problems = vec![];
+    if !config.enabled {
+        return problems;
+    }
+
+    let mut lines = message.lines();
+    let subject = lines.next().unwrap_or("");
+
+    let subject_length = subject.chars().count();
+    if config.max_subject_length > 0 && subject_length > config.max_subject_length {
+        problems.push(format!(
+            "Subject is {} characters long, max is {}",
+            subject_length, config.max_subject_length
+        ));
+    }
+
+    if config.blank_second_line && lines.next().is_some_and(|line| !line.trim().is_empty()) {
+        problems.push("Second line is not blank".to_string());
+    }
+
+    if config.conventional_prefix && !CONVENTIONAL.is_match(subject) {
+        problems.push("Subject lacks a conventional commit prefix, e.g. \"feat: \"".to_string());
+    }
+
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::lint;
+    use crate::config::CommitLintConfig;
+
+    fn config() -> CommitLintConfig {
+        CommitLintConfig {
+            enabled: true,
+            max_subject_length: 20,
+            blank_second_line: true,
+            conventional_prefix: true,
+        }
+    }
+
+    #[test]
+    fn valid() {
+        assert!(lint(&config(), "feat(ui): add x\n\nBody").is_empty());
+        assert!(lint(&config(), "fix!: y").is_empty());
+    }
+
+    #[test]
+    fn disabled() {
+        let config = CommitLintConfig {
+            enabled: false,
+            ..config()
+        };
+        assert!(lint(&config, "no prefix, and way too long of a subject").is_empty());
+    }
+
+    #[test]
+    fn all_problems() {
+        assert_eq!(
+            lint(&config(), "Added the new thing!\nbody"),
+            [
+                "Second line is not blank",
+                "Subject lacks a conventional commit prefix, e.g. \"feat: \""
+            ]
+        );
+        assert_eq!(
+            lint(&config(), "feat: a subject that is too long"),
+            ["Subject is 32 characters long, max is 20"]
+        );
+    }
+}
@@ -1,9 +1,14 @@
-use std::{collections::BTreeMap, path::PathBuf};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
 
-use crate::{menu::Menu, ops::Op, Res};
+use crate::{locale, menu::Menu, ops::Op, Res};
+use chrono::format::{Item, StrftimeItems};
 use etcetera::{choose_base_strategy, BaseStrategy};
 use figment::{
-    providers::{Format, Toml},
+    providers::{Format, Serialized, Toml},
+    value::Value,
     Figment,
 };
 use ratatui::style::{Color, Modifier, Style};
@@ -11,11 +16,46 @@ use serde::Deserialize;
 
 const DEFAULT_CONFIG: &str = include_str!("default_config.toml");
 
+/// Per-project config, read from the root of the repository.
+const PROJECT_CONFIG_FILE: &str = ".gitu.toml";
+
+/// Settings that end up in a command line, so are never taken from a project's config.
+const USER_ONLY_KEYS: [&str; 3] = [
+    "general.external_diff",
+    "editor.line_arg",
+    "editor.mergetool",
+];
+
 #[derive(Default, Debug, Deserialize)]
 pub(crate) struct Config {
     pub general: GeneralConfig,
     pub style: StyleConfig,
-    pub bindings: BTreeMap<Menu, BTreeMap<Op, Vec<String>>>,
+    pub bindings: BindingsConfig,
+    pub editor: EditorConfig,
+    pub push: PushConfig,
+    /// Where Gitu was launched from, relative to the root of the worktree.
+    #[serde(skip)]
+    pub launch_dir: PathBuf,
+}
+
+#[derive(Default, Debug, Deserialize)]
+pub(crate) struct BindingsConfig {
+    /// Already layered in by `layered_config`, only here to not be taken for a menu.
+    #[allow(dead_code)]
+    pub preset: String,
+    #[serde(flatten)]
+    pub menus: BTreeMap<Menu, BTreeMap<Op, Vec<String>>>,
+}
+
+#[derive(Default, Debug, Deserialize)]
+pub struct PushConfig {
+    pub confirm: BoolConfigEntry,
+}
+
+#[derive(Default, Debug, Deserialize)]
+pub struct EditorConfig {
+    pub line_arg: String,
+    pub mergetool: String,
 }
 
 #[derive(Default, Debug, Deserialize)]
@@ -23,6 +63,133 @@ pub struct GeneralConfig {
     pub always_show_help: BoolConfigEntry,
     pub confirm_quit: BoolConfigEntry,
     pub collapsed_sections: Vec<String>,
+    pub stash_list_limit: usize,
+    pub recent_commits_limit: usize,
+    pub cmd_log_limit: usize,
+    pub persist_list_limits: BoolConfigEntry,
+    pub fold_unchanged_lines: usize,
+    pub breadcrumbs: BoolConfigEntry,
+    pub commit_prefixes: Vec<String>,
+    pub discard_to_trash: DiscardToTrash,
+    pub commit_template: String,
+    pub commit_lint: CommitLintConfig,
+    pub base_branch: String,
+    pub refs_sort: RefsSort,
+    pub relative_paths: BoolConfigEntry,
+    pub initial_screen: InitialScreen,
+    pub date_format: String,
+    pub locale: String,
+    pub status_sections: Vec<StatusSection>,
+    pub external_diff: String,
+    pub cmd_notification: CmdNotificationConfig,
+    pub mouse_support: BoolConfigEntry,
+    pub log_signatures: BoolConfigEntry,
+    pub refresh_on_file_change: RefreshOnFileChangeConfig,
+    pub restore_session: BoolConfigEntry,
+    pub persist_menu_args: BoolConfigEntry,
+    pub key_hints: KeyHintsConfig,
+    pub workspace_dirs: Vec<String>,
+}
+
+#[derive(Default, Debug, Deserialize, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum InitialScreen {
+    #[default]
+    Status,
+    Log,
+    Refs,
+}
+
+/// How `show_refs` orders the branches and tags it lists.
+#[derive(Default, Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RefsSort {
+    #[default]
+    Name,
+    CommitterDate,
+    Ahead,
+}
+
+impl RefsSort {
+    pub(crate) fn next(self) -> Self {
+        match self {
+            RefsSort::Name => RefsSort::CommitterDate,
+            RefsSort::CommitterDate => RefsSort::Ahead,
+            RefsSort::Ahead => RefsSort::Name,
+        }
+    }
+
+    /// As it's written in the config.
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            RefsSort::Name => "name",
+            RefsSort::CommitterDate => "committer_date",
+            RefsSort::Ahead => "ahead",
+        }
+    }
+}
+
+/// Sections of the status screen, named like their ids in `collapsed_sections`.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StatusSection {
+    BranchStatus,
+    Untracked,
+    Unmerged,
+    UnstagedChanges,
+    StagedChanges,
+    Fixups,
+    Stashes,
+    RecentCommits,
+}
+
+#[derive(Default, Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiscardToTrash {
+    #[default]
+    Off,
+    Os,
+    Backup,
+}
+
+#[derive(Default, Debug, Deserialize)]
+pub struct CommitLintConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub max_subject_length: usize,
+    #[serde(default)]
+    pub blank_second_line: bool,
+    #[serde(default)]
+    pub conventional_prefix: bool,
+}
+
+#[derive(Default, Debug, Deserialize)]
+pub struct RefreshOnFileChangeConfig {
+    pub enabled: bool,
+    pub pause_while_editing: bool,
+}
+
+#[derive(Default, Debug, Deserialize)]
+pub struct KeyHintsConfig {
+    pub enabled: bool,
+    pub delay_ms: u64,
+}
+
+#[derive(Default, Debug, Deserialize)]
+pub struct CmdNotificationConfig {
+    pub mode: CmdNotification,
+    pub after_seconds: u64,
+}
+
+#[derive(Default, Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CmdNotification {
+    #[default]
+    Off,
+    Bell,
+    /// An OSC 9 desktop notification.
+    Desktop,
 }
 
 #[derive(Default, Debug, Deserialize)]
@@ -33,6 +200,8 @@ pub struct BoolConfigEntry {
 
 #[derive(Default, Debug, Deserialize)]
 pub struct StyleConfig {
+    pub theme: String,
+
     pub section_header: StyleConfigEntry,
     pub file_header: StyleConfigEntry,
     pub hunk_header: StyleConfigEntry,
@@ -47,11 +216,16 @@ pub struct StyleConfig {
     pub selection_line: StyleConfigEntry,
     pub selection_bar: SymbolStyleConfigEntry,
     pub selection_area: StyleConfigEntry,
+    pub mouse_hover: StyleConfigEntry,
+    pub mouse_selection: StyleConfigEntry,
 
     pub hash: StyleConfigEntry,
     pub branch: StyleConfigEntry,
     pub remote: StyleConfigEntry,
     pub tag: StyleConfigEntry,
+    pub signature_good: StyleConfigEntry,
+    pub signature_bad: StyleConfigEntry,
+    pub signature_unknown: StyleConfigEntry,
 
     pub command: StyleConfigEntry,
     pub active_arg: StyleConfigEntry,
@@ -171,23 +345,152 @@ impl From<&SymbolStyleConfigEntry> for Style {
     }
 }
 
-pub(crate) fn init_config() -> Res<Config> {
+pub(crate) fn init_config(repo_dir: &Path) -> Res<Config> {
     let config_path = config_path();
+    let project_config_path = repo_dir.join(PROJECT_CONFIG_FILE);
 
-    if config_path.exists() {
-        log::info!("Loading config file at {:?}", config_path);
-    } else {
-        log::info!("No config file at {:?}", config_path);
+    for path in [&config_path, &project_config_path] {
+        if path.exists() {
+            log::info!("Loading config file at {:?}", path);
+        } else {
+            log::info!("No config file at {:?}", path);
+        }
     }
 
-    let config = Figment::new()
-        .merge(Toml::string(DEFAULT_CONFIG))
-        .merge(Toml::file(config_path))
-        .extract()?;
+    let figment = layered_config(&config_path, &project_config_path)?;
+    let mut value = figment.extract::<Value>()?;
 
+    if !supports_truecolor() {
+        log::info!("No truecolor support detected, approximating hex colors");
+        if let Value::Dict(_, dict) = &mut value {
+            if let Some(style) = dict.get_mut("style") {
+                approximate_colors(style);
+            }
+        }
+    }
+
+    let config: Config = value.deserialize()?;
+    validate_date_format(&config.general.date_format)?;
+    validate_locale(&config.general.locale)?;
     Ok(config)
 }
 
+fn validate_locale(name: &str) -> Res<()> {
+    if !name.is_empty() && locale::parse(name).is_none() {
+        return Err(format!("Unknown general.locale: {:?}", name).into());
+    }
+
+    Ok(())
+}
+
+/// Formatting a date with an invalid format would panic.
+fn validate_date_format(format: &str) -> Res<()> {
+    if StrftimeItems::new(format).any(|item| item == Item::Error) {
+        return Err(format!("Invalid general.date_format: {:?}", format).into());
+    }
+
+    Ok(())
+}
+
+/// Defaults, overridden by the chosen theme and bindings preset, the user's config and in turn by the project's.
+fn layered_config(config_path: &Path, project_config_path: &Path) -> Res<Figment> {
+    let user_config = || {
+        Figment::new()
+            .merge(Toml::file(config_path))
+            .merge(Toml::file(project_config_path))
+    };
+
+    let chosen = Figment::new()
+        .merge(Toml::string(DEFAULT_CONFIG))
+        .merge(user_config());
+    let theme: String = chosen.extract_inner("style.theme")?;
+    let preset: String = chosen.extract_inner("bindings.preset")?;
+
+    let mut figment = Figment::new()
+        .merge(Toml::string(DEFAULT_CONFIG))
+        .merge(Toml::string(theme_config(&theme)?))
+        .merge(Toml::string(bindings_preset_config(&preset)?))
+        .merge(user_config());
+
+    // A cloned repository's config mustn't get to name a command to run, or its arguments
+    let user_only = Figment::new()
+        .merge(Toml::string(DEFAULT_CONFIG))
+        .merge(Toml::file(config_path));
+    for key in USER_ONLY_KEYS {
+        let value: String = user_only.extract_inner(key)?;
+        figment = figment.merge(Serialized::default(key, value));
+    }
+
+    Ok(figment)
+}
+
+fn theme_config(theme: &str) -> Res<&'static str> {
+    match theme {
+        "dark" => Ok(""),
+        "light" => Ok(include_str!("themes/light.toml")),
+        "solarized" => Ok(include_str!("themes/solarized.toml")),
+        _ => Err(format!("Unknown theme: {:?}", theme).into()),
+    }
+}
+
+fn bindings_preset_config(preset: &str) -> Res<&'static str> {
+    match preset {
+        "magit" => Ok(""),
+        "vim" => Ok(include_str!("presets/vim.toml")),
+        "emacs" => Ok(include_str!("presets/emacs.toml")),
+        _ => Err(format!("Unknown bindings preset: {:?}", preset).into()),
+    }
+}
+
+fn supports_truecolor() -> bool {
+    std::env::var("COLORTERM").is_ok_and(|value| value == "truecolor" || value == "24bit")
+}
+
+/// Replaces hex colors with the closest color of the 256-color palette.
+fn approximate_colors(value: &mut Value) {
+    match value {
+        Value::Dict(_, dict) => dict.values_mut().for_each(approximate_colors),
+        Value::String(_, string) if string.starts_with('#') => {
+            if let Ok(Color::Rgb(r, g, b)) = string.parse::<Color>() {
+                *string = rgb_to_ansi256(r, g, b).to_string();
+            }
+        }
+        _ => (),
+    }
+}
+
+fn rgb_to_ansi256(r: u8, g: u8, b: u8) -> u8 {
+    // Levels of the 6x6x6 color cube (16-231), and the grayscale ramp (232-255)
+    const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+    let closest_level = |c: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|&i| CUBE_LEVELS[i].abs_diff(c))
+            .unwrap()
+    };
+
+    let distance = |(r2, g2, b2): (u8, u8, u8)| {
+        [(r, r2), (g, g2), (b, b2)]
+            .iter()
+            .map(|&(a, b)| (a.abs_diff(b) as u32).pow(2))
+            .sum::<u32>()
+    };
+
+    let (ri, gi, bi) = (closest_level(r), closest_level(g), closest_level(b));
+    let cube = (CUBE_LEVELS[ri], CUBE_LEVELS[gi], CUBE_LEVELS[bi]);
+
+    let average = (r as u32 + g as u32 + b as u32) / 3;
+    let gray_i = (average.saturating_sub(3) / 10).min(23) as u8;
+    let gray_level = 8 + gray_i * 10;
+    let gray = (gray_level, gray_level, gray_level);
+
+    if distance(gray) < distance(cube) {
+        232 + gray_i
+    } else {
+        16 + 36 * ri as u8 + 6 * gi as u8 + bi as u8
+    }
+}
+
 pub fn config_path() -> PathBuf {
     choose_base_strategy()
         .expect("Unable to find the config directory!")
@@ -212,8 +515,14 @@ mod tests {
         Figment,
     };
     use ratatui::style::Color;
+    use temp_dir::TempDir;
 
-    use super::{Config, DEFAULT_CONFIG};
+    use crate::{key_parser, menu::Menu, ops::Op};
+
+    use super::{
+        approximate_colors, bindings_preset_config, layered_config, rgb_to_ansi256,
+        validate_date_format, Config, DEFAULT_CONFIG, PROJECT_CONFIG_FILE,
+    };
 
     #[test]
     fn config_merges() {
@@ -231,4 +540,220 @@ mod tests {
         assert_eq!(config.style.hunk_header.bg, Some(Color::LightGreen));
         assert_eq!(config.style.hunk_header.fg, Some(Color::Blue));
     }
+
+    #[test]
+    fn project_config_overrides_user_config() {
+        let dir = TempDir::new().unwrap();
+        let config_path = dir.path().join("config.toml");
+        let project_config_path = dir.path().join(PROJECT_CONFIG_FILE);
+
+        std::fs::write(
+            &config_path,
+            r#"
+            general.collapsed_sections = ["untracked"]
+            style.hunk_header.bg = "light green"
+            bindings.root.quit = ["Q"]
+            "#,
+        )
+        .unwrap();
+
+        std::fs::write(
+            &project_config_path,
+            r#"
+            general.collapsed_sections = ["recent_commits"]
+            bindings.root.quit = ["x"]
+            "#,
+        )
+        .unwrap();
+
+        let config: Config = layered_config(&config_path, &project_config_path)
+            .unwrap()
+            .extract()
+            .unwrap();
+
+        assert_eq!(config.general.collapsed_sections, ["recent_commits"]);
+        assert_eq!(config.style.hunk_header.bg, Some(Color::LightGreen));
+        assert_eq!(config.bindings.menus[&Menu::Root][&Op::Quit], ["x"]);
+        assert_eq!(config.bindings.menus[&Menu::Root][&Op::Refresh], ["g"]);
+    }
+
+    #[test]
+    fn external_diff_only_from_user_config() {
+        let dir = TempDir::new().unwrap();
+        let config_path = dir.path().join("config.toml");
+        let project_config_path = dir.path().join(PROJECT_CONFIG_FILE);
+        std::fs::write(&project_config_path, "general.external_diff = \"rm -rf\"").unwrap();
+
+        let config: Config = layered_config(&config_path, &project_config_path)
+            .unwrap()
+            .extract()
+            .unwrap();
+        assert_eq!(config.general.external_diff, "");
+
+        std::fs::write(&config_path, "general.external_diff = \"difft\"").unwrap();
+        let config: Config = layered_config(&config_path, &project_config_path)
+            .unwrap()
+            .extract()
+            .unwrap();
+        assert_eq!(config.general.external_diff, "difft");
+    }
+
+    #[test]
+    fn editor_commands_only_from_user_config() {
+        let dir = TempDir::new().unwrap();
+        let config_path = dir.path().join("config.toml");
+        let project_config_path = dir.path().join(PROJECT_CONFIG_FILE);
+        std::fs::write(
+            &project_config_path,
+            "editor.line_arg = \"-c :!touch${IFS}pwned {file}\"\neditor.mergetool = \"sh\"",
+        )
+        .unwrap();
+
+        let config: Config = layered_config(&config_path, &project_config_path)
+            .unwrap()
+            .extract()
+            .unwrap();
+        assert_eq!(config.editor.line_arg, "");
+        assert_eq!(config.editor.mergetool, "");
+
+        std::fs::write(&config_path, "editor.line_arg = \"--goto {file}:{line}\"").unwrap();
+        let config: Config = layered_config(&config_path, &project_config_path)
+            .unwrap()
+            .extract()
+            .unwrap();
+        assert_eq!(config.editor.line_arg, "--goto {file}:{line}");
+    }
+
+    #[test]
+    fn theme_between_defaults_and_user_config() {
+        let dir = TempDir::new().unwrap();
+        let config_path = dir.path().join("config.toml");
+
+        std::fs::write(
+            &config_path,
+            r#"
+            style.theme = "solarized"
+            style.hash.fg = "light green"
+            "#,
+        )
+        .unwrap();
+
+        let config: Config = layered_config(&config_path, &dir.path().join(PROJECT_CONFIG_FILE))
+            .unwrap()
+            .extract()
+            .unwrap();
+
+        assert_eq!(
+            config.style.section_header.fg,
+            Some(Color::Rgb(0xb5, 0x89, 0x00))
+        );
+        assert_eq!(config.style.hash.fg, Some(Color::LightGreen));
+    }
+
+    #[test]
+    fn unknown_theme() {
+        let dir = TempDir::new().unwrap();
+        let config_path = dir.path().join("config.toml");
+        std::fs::write(&config_path, r#"style.theme = "plaid""#).unwrap();
+
+        let result = layered_config(&config_path, &dir.path().join(PROJECT_CONFIG_FILE));
+        assert_eq!(result.unwrap_err().to_string(), r#"Unknown theme: "plaid""#);
+    }
+
+    #[test]
+    fn bindings_preset_between_defaults_and_user_config() {
+        let dir = TempDir::new().unwrap();
+        let config_path = dir.path().join("config.toml");
+
+        std::fs::write(
+            &config_path,
+            r#"
+            bindings.preset = "emacs"
+            bindings.root.discard = ["D"]
+            "#,
+        )
+        .unwrap();
+
+        let config: Config = layered_config(&config_path, &dir.path().join(PROJECT_CONFIG_FILE))
+            .unwrap()
+            .extract()
+            .unwrap();
+
+        let root = &config.bindings.menus[&Menu::Root];
+        assert_eq!(root[&Op::MoveDown], ["n", "<ctrl+n>", "<down>"]);
+        assert_eq!(root[&Op::Discard], ["D"]);
+        assert_eq!(root[&Op::Refresh], ["g"]);
+    }
+
+    #[test]
+    fn bindings_presets_without_shadowed_keys() {
+        for preset in ["magit", "vim", "emacs"] {
+            let config: Config = Figment::new()
+                .merge(Toml::string(DEFAULT_CONFIG))
+                .merge(Toml::string(bindings_preset_config(preset).unwrap()))
+                .extract()
+                .unwrap();
+
+            for (menu, ops) in &config.bindings.menus {
+                let bindings = ops
+                    .iter()
+                    .flat_map(|(op, keys)| keys.iter().map(move |keys| (op, keys)))
+                    .map(|(op, keys)| (op, key_parser::parse_keys(keys).unwrap().1))
+                    .collect::<Vec<_>>();
+
+                for (op, keys) in &bindings {
+                    for (other_op, other_keys) in &bindings {
+                        assert!(
+                            op == other_op || !other_keys.starts_with(keys),
+                            "{} preset: {:?} in {:?} shadows {:?}",
+                            preset,
+                            op,
+                            menu,
+                            other_op
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn approximates_hex_colors() {
+        let mut value = Figment::new()
+            .merge(Toml::string(
+                r##"
+                hunk_header = { fg = "#268bd2", bg = "blue" }
+                cursor = { symbol = "#", fg = "#ffffff" }
+                "##,
+            ))
+            .extract()
+            .unwrap();
+
+        approximate_colors(&mut value);
+
+        assert_eq!(
+            value.find_ref("hunk_header.fg").unwrap().as_str(),
+            Some("32")
+        );
+        assert_eq!(
+            value.find_ref("hunk_header.bg").unwrap().as_str(),
+            Some("blue")
+        );
+        assert_eq!(value.find_ref("cursor.symbol").unwrap().as_str(), Some("#"));
+        assert_eq!(value.find_ref("cursor.fg").unwrap().as_str(), Some("231"));
+    }
+
+    #[test]
+    fn ansi256_grayscale() {
+        assert_eq!(rgb_to_ansi256(0, 0, 0), 16);
+        assert_eq!(rgb_to_ansi256(0x80, 0x80, 0x80), 244);
+        assert_eq!(rgb_to_ansi256(0xff, 0, 0), 196);
+    }
+
+    #[test]
+    fn date_format_validation() {
+        assert!(validate_date_format("").is_ok());
+        assert!(validate_date_format("%d.%m.%Y %H:%M").is_ok());
+        assert!(validate_date_format("%Q").is_err());
+    }
 }
@@ -0,0 +1,116 @@
+use crate::{
+    git::diff::{Delta, FileContents},
+    items::{Item, TargetData},
+    Res,
+};
+use ansi_to_tui::IntoText;
+use ratatui::text::Line;
+use std::{io::Write, path::Path, process::Command};
+use tempfile::NamedTempFile;
+
+/// Replaces the hunks of each file with what `command` outputs for it. The file items are kept,
+/// and the rendered lines act on the whole file, as the hunks they replace can't be told apart.
+pub(crate) fn replace_hunks(command: &str, items: Vec<Item>) -> Res<Vec<Item>> {
+    let mut replaced = vec![];
+    let mut hidden_depth = None;
+
+    for item in items {
+        if hidden_depth.is_some_and(|depth| item.depth > depth) {
+            continue;
+        }
+
+        hidden_depth = None;
+        let Some(TargetData::Delta(delta)) = &item.target_data else {
+            replaced.push(item);
+            continue;
+        };
+
+        let Some(contents) = &delta.contents else {
+            replaced.push(item);
+            continue;
+        };
+
+        let rendered = render(command, delta, contents)?
+            .into_iter()
+            .enumerate()
+            .map(|(i, line)| Item {
+                id: format!("{}_external_{}", item.id, i).into(),
+                display: line,
+                depth: item.depth + 1,
+                target_data: item.target_data.clone(),
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+
+        hidden_depth = Some(item.depth);
+        replaced.push(item);
+        replaced.extend(rendered);
+    }
+
+    Ok(replaced)
+}
+
+/// Runs `command` like git runs a `GIT_EXTERNAL_DIFF`, that is with the arguments
+/// `path old-file old-hex old-mode new-file new-hex new-mode`, and parses the colors it outputs.
+fn render(command: &str, delta: &Delta, contents: &FileContents) -> Res<Vec<Line<'static>>> {
+    let old_file = temp_file(&delta.old_file, &contents.old)?;
+    let new_file = temp_file(&delta.new_file, &contents.new)?;
+    let side = |file: &Path, id: git2::Oid, mode: git2::FileMode, exists: bool| {
+        if exists {
+            [
+                file.to_string_lossy().into_owned(),
+                if id.is_zero() {
+                    ".".to_string()
+                } else {
+                    id.to_string()
+                },
+                format!("{:o}", u32::from(mode)),
+            ]
+        } else {
+            ["/dev/null".to_string(), ".".to_string(), ".".to_string()]
+        }
+    };
+
+    let args = command.split_whitespace().collect::<Vec<_>>();
+    let output = Command::new(args[0])
+        .args(&args[1..])
+        .arg(&delta.new_file)
+        .args(side(
+            old_file.path(),
+            delta.old_id,
+            delta.old_mode,
+            delta.status != git2::Delta::Added && delta.status != git2::Delta::Untracked,
+        ))
+        .args(side(
+            new_file.path(),
+            delta.new_id,
+            delta.new_mode,
+            delta.status != git2::Delta::Deleted,
+        ))
+        .output()
+        .map_err(|err| format!("Couldn't run {}: {}", args[0], err))?;
+
+    // Like `diff`, tools exit with 1 when there are differences
+    if !matches!(output.status.code(), Some(0 | 1)) {
+        return Err(format!(
+            "{} failed: {}",
+            args[0],
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+        .into());
+    }
+
+    Ok(output.stdout.into_text()?.lines)
+}
+
+/// Removed once dropped. Named like the file, so that tools can tell its language by the extension.
+fn temp_file(path: &Path, content: &str) -> Res<NamedTempFile> {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let mut file = tempfile::Builder::new()
+        .prefix("gitu-")
+        .suffix(&format!("-{}", name))
+        .tempfile()?;
+
+    file.write_all(content.as_bytes())?;
+    Ok(file)
+}
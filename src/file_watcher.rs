@@ -0,0 +1,216 @@
+use crate::Res;
+use git2::Repository;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::{
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver},
+    time::{Duration, Instant},
+};
+
+/// How long it has to be quiet before changes are acted on, as git touches many files at once.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// What was changed on disk, telling which screens are out of date.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Changes {
+    /// Branches, tags, HEAD, or an ongoing merge / rebase.
+    pub refs: bool,
+    /// What's staged.
+    pub index: bool,
+    /// Files in the worktree, other than ignored ones.
+    pub worktree: bool,
+}
+
+impl Changes {
+    pub(crate) const ALL: Self = Self {
+        refs: true,
+        index: true,
+        worktree: true,
+    };
+
+    pub(crate) fn any(&self) -> bool {
+        self.refs || self.index || self.worktree
+    }
+
+    /// Whether something in `other` is also in here.
+    pub(crate) fn intersects(&self, other: &Changes) -> bool {
+        (self.refs && other.refs)
+            || (self.index && other.index)
+            || (self.worktree && other.worktree)
+    }
+}
+
+/// Watches the worktree and the git directory for changes made outside of Gitu, like by an editor.
+pub(crate) struct FileWatcher {
+    // Stops watching once dropped
+    _watcher: RecommendedWatcher,
+    events: Receiver<PathBuf>,
+    git_dir: PathBuf,
+    workdir: Option<PathBuf>,
+    pending: Changes,
+    last_event: Option<Instant>,
+    /// Changes seen before this are left out, see `discard`.
+    discard_until: Option<Instant>,
+}
+
+impl FileWatcher {
+    pub(crate) fn new(repo: &Repository) -> Res<Self> {
+        let (sender, events) = mpsc::channel();
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                if let Ok(event) = event {
+                    for path in event.paths {
+                        let _ = sender.send(path);
+                    }
+                }
+            })?;
+
+        let git_dir = repo.path().canonicalize()?;
+        let workdir = repo.workdir().map(Path::canonicalize).transpose()?;
+
+        if let Some(workdir) = &workdir {
+            watcher.watch(workdir, RecursiveMode::Recursive)?;
+        }
+        if !workdir.as_ref().is_some_and(|dir| git_dir.starts_with(dir)) {
+            watcher.watch(&git_dir, RecursiveMode::Recursive)?;
+        }
+
+        Ok(Self {
+            _watcher: watcher,
+            events,
+            git_dir,
+            workdir,
+            pending: Changes::default(),
+            last_event: None,
+            discard_until: None,
+        })
+    }
+
+    /// What changed, once things have been quiet for a bit. Each change is only told about once.
+    pub(crate) fn take_changes(&mut self, repo: &Repository) -> Option<Changes> {
+        for path in self.events.try_iter() {
+            if self
+                .discard_until
+                .is_some_and(|until| Instant::now() < until)
+            {
+                continue;
+            }
+
+            let Some(changes) = classify(&self.git_dir, self.workdir.as_deref(), &path) else {
+                continue;
+            };
+
+            if changes.worktree && self.is_ignored(repo, &path) {
+                continue;
+            }
+
+            self.pending.refs |= changes.refs;
+            self.pending.index |= changes.index;
+            self.pending.worktree |= changes.worktree;
+            self.last_event = Some(Instant::now());
+        }
+
+        if !self.pending.any()
+            || self
+                .last_event
+                .is_some_and(|last| last.elapsed() < DEBOUNCE)
+        {
+            return None;
+        }
+
+        Some(std::mem::take(&mut self.pending))
+    }
+
+    /// Leaves out the changes seen so far and those still on their way in, when the screen is refreshed anyway.
+    pub(crate) fn discard(&mut self) {
+        self.events.try_iter().for_each(drop);
+        self.pending = Changes::default();
+        self.last_event = None;
+        self.discard_until = Some(Instant::now() + DEBOUNCE);
+    }
+
+    fn is_ignored(&self, repo: &Repository, path: &Path) -> bool {
+        let Some(relative) = self
+            .workdir
+            .as_ref()
+            .and_then(|workdir| path.strip_prefix(workdir).ok())
+        else {
+            return true;
+        };
+
+        repo.is_path_ignored(relative).unwrap_or(false)
+    }
+}
+
+/// Tells what a changed path affects, if anything that's shown.
+fn classify(git_dir: &Path, workdir: Option<&Path>, path: &Path) -> Option<Changes> {
+    let Ok(in_git_dir) = path.strip_prefix(git_dir) else {
+        return workdir
+            .is_some_and(|workdir| path.starts_with(workdir))
+            .then_some(Changes {
+                worktree: true,
+                ..Default::default()
+            });
+    };
+
+    // Written to and then renamed into place, which is seen by itself
+    if in_git_dir.extension().is_some_and(|ext| ext == "lock") {
+        return None;
+    }
+
+    let first = in_git_dir.components().next()?.as_os_str().to_str()?;
+    match first {
+        "index" => Some(Changes {
+            index: true,
+            ..Default::default()
+        }),
+        "HEAD" | "refs" | "packed-refs" | "MERGE_HEAD" | "REVERT_HEAD" | "CHERRY_PICK_HEAD"
+        | "rebase-merge" | "rebase-apply" => Some(Changes {
+            refs: true,
+            ..Default::default()
+        }),
+        // Objects and logs come along with a change of refs, other files aren't shown
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{classify, Changes};
+    use std::path::Path;
+
+    fn classify_path(path: &str) -> Option<Changes> {
+        classify(
+            Path::new("/repo/.git"),
+            Some(Path::new("/repo")),
+            Path::new(path),
+        )
+    }
+
+    #[test]
+    fn classify_git_dir() {
+        assert_eq!(
+            classify_path("/repo/.git/index").map(|c| c.index),
+            Some(true)
+        );
+        assert_eq!(
+            classify_path("/repo/.git/refs/heads/main").map(|c| c.refs),
+            Some(true)
+        );
+        assert_eq!(classify_path("/repo/.git/HEAD").map(|c| c.refs), Some(true));
+        assert_eq!(classify_path("/repo/.git/index.lock"), None);
+        assert_eq!(classify_path("/repo/.git/objects/ab/cdef"), None);
+    }
+
+    #[test]
+    fn classify_worktree() {
+        assert_eq!(
+            classify_path("/repo/src/main.rs"),
+            Some(Changes {
+                worktree: true,
+                ..Default::default()
+            })
+        );
+        assert_eq!(classify_path("/elsewhere/file"), None);
+    }
+}
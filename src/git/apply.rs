@@ -0,0 +1,264 @@
+use crate::Res;
+use git2::{IndexEntry, IndexTime, Repository};
+use std::path::Path;
+
+/// Applies a single-hunk patch to the staged content of `path`, like `git apply --cached --recount`
+/// (with `--reverse` if `reverse`), but without leaving the process.
+pub(crate) fn apply_to_index(
+    repo: &Repository,
+    path: &Path,
+    patch: &[u8],
+    reverse: bool,
+) -> Res<()> {
+    let mut index = repo.index()?;
+    // Picks up changes to the index made outside of Gitu
+    index.read(false)?;
+    let (mut entry, staged) = match index.get_path(path, 0) {
+        Some(entry) => {
+            let staged = repo.find_blob(entry.id)?.content().to_vec();
+            (entry, staged)
+        }
+        // A staged deletion leaves nothing in the index to unstage onto, but what HEAD has
+        None if reverse => (head_entry(repo, path)?, vec![]),
+        None => return Err(format!("{} is not in the index", path.to_string_lossy()).into()),
+    };
+
+    let header = patch_header(patch);
+    if let Some((old_id, new_id)) = blob_ids(&header) {
+        // The side of the patch that is expected to be staged
+        let expected = if reverse { new_id } else { old_id };
+        let is_hashed = expected.chars().any(|c| c != '0');
+
+        if is_hashed && !entry.id.to_string().starts_with(expected) {
+            return Err(format!(
+                "{} was staged elsewhere since it was shown, refresh and try again",
+                path.to_string_lossy()
+            )
+            .into());
+        }
+    }
+
+    let content = apply_patch(&staged, patch, reverse)
+        .ok_or_else(|| format!("Patch does not apply to {}", path.to_string_lossy()))?;
+
+    let removes_file = if reverse {
+        header.contains("\nnew file mode")
+    } else {
+        header.contains("\ndeleted file mode")
+    };
+
+    if removes_file && content.is_empty() {
+        index.remove_path(path)?;
+    } else {
+        // Makes git compare the content with the worktree, as the file's stat info no longer matches
+        entry.mtime = IndexTime::new(0, 0);
+        entry.ctime = IndexTime::new(0, 0);
+        index.add_frombuffer(&entry, &content)?;
+    }
+
+    index.write()?;
+    Ok(())
+}
+
+/// An index entry of `path` with the mode and blob it has in HEAD.
+fn head_entry(repo: &Repository, path: &Path) -> Res<IndexEntry> {
+    let tree_entry = repo
+        .head()?
+        .peel_to_tree()?
+        .get_path(path)
+        .map_err(|_| format!("{} is not in the index", path.to_string_lossy()))?;
+    let path = path.as_os_str().as_encoded_bytes().to_vec();
+
+    Ok(IndexEntry {
+        ctime: IndexTime::new(0, 0),
+        mtime: IndexTime::new(0, 0),
+        dev: 0,
+        ino: 0,
+        mode: tree_entry.filemode() as u32,
+        uid: 0,
+        gid: 0,
+        file_size: 0,
+        id: tree_entry.id(),
+        // The lower bits hold the length of the path, saturated
+        flags: path.len().min(0xfff) as u16,
+        flags_extended: 0,
+        path,
+    })
+}
+
+/// Everything before the hunk, starting with a newline so lines can be matched by their start.
+fn patch_header(patch: &[u8]) -> String {
+    let header = patch
+        .split_inclusive(|&b| b == b'\n')
+        .take_while(|line| !line.starts_with(b"@@"))
+        .flatten()
+        .copied()
+        .collect::<Vec<_>>();
+
+    format!("\n{}", String::from_utf8_lossy(&header))
+}
+
+/// The abbreviated blob ids of an `index <old>..<new> <mode>` line, if the header has one.
+fn blob_ids(header: &str) -> Option<(&str, &str)> {
+    let ids = header
+        .lines()
+        .find_map(|line| line.strip_prefix("index "))?
+        .split(' ')
+        .next()?;
+
+    ids.split_once("..")
+}
+
+/// Replaces the lines the hunk removes with the ones it adds (the other way around if `reverse`).
+/// The hunk's line counts aren't used, and it's located by its context if its start line is off.
+fn apply_patch(content: &[u8], patch: &[u8], reverse: bool) -> Option<Vec<u8>> {
+    let mut patch_lines = patch
+        .split_inclusive(|&b| b == b'\n')
+        .skip_while(|line| !line.starts_with(b"@@"));
+
+    let header = String::from_utf8_lossy(patch_lines.next()?).to_string();
+    let (old_start, new_start) = parse_hunk_header(&header)?;
+
+    let (remove, add) = if reverse { (b'+', b'-') } else { (b'-', b'+') };
+    let mut before = vec![];
+    let mut after = vec![];
+    let mut last_side = None;
+
+    for line in patch_lines {
+        let Some((&prefix, text)) = line.split_first() else {
+            continue;
+        };
+
+        if prefix == b'\\' {
+            // "\ No newline at end of file" applies to the line before it
+            let strip = |lines: &mut Vec<Vec<u8>>| {
+                if let Some(last) = lines.last_mut() {
+                    if last.ends_with(b"\n") {
+                        last.pop();
+                    }
+                }
+            };
+
+            match last_side {
+                Some(side) if side == remove => strip(&mut before),
+                Some(side) if side == add => strip(&mut after),
+                _ => {
+                    strip(&mut before);
+                    strip(&mut after);
+                }
+            }
+            continue;
+        }
+
+        let text = text.to_vec();
+        match prefix {
+            b' ' => {
+                before.push(text.clone());
+                after.push(text);
+            }
+            p if p == remove => before.push(text),
+            p if p == add => after.push(text),
+            _ => continue,
+        }
+        last_side = Some(prefix);
+    }
+
+    let lines = content.split_inclusive(|&b| b == b'\n').collect::<Vec<_>>();
+
+    let start = if reverse { new_start } else { old_start };
+    // Hunks of empty files start at line 0, others count from 1
+    let expected = start.saturating_sub(1).min(lines.len());
+    let matches_at = |pos: usize| {
+        pos + before.len() <= lines.len()
+            && before
+                .iter()
+                .zip(&lines[pos..])
+                .all(|(expected, actual)| expected.as_slice() == *actual)
+    };
+
+    let pos = (0..=lines.len())
+        .flat_map(|offset| [expected.checked_sub(offset), Some(expected + offset)])
+        .flatten()
+        .filter(|&pos| pos <= lines.len())
+        .find(|&pos| matches_at(pos))?;
+
+    Some(
+        lines[..pos]
+            .iter()
+            .copied()
+            .chain(after.iter().map(Vec::as_slice))
+            .chain(lines[(pos + before.len())..].iter().copied())
+            .flatten()
+            .copied()
+            .collect(),
+    )
+}
+
+/// The start lines of the old and new side of a header like `@@ -1,3 +1,4 @@`.
+fn parse_hunk_header(header: &str) -> Option<(usize, usize)> {
+    let mut ranges = header.strip_prefix("@@ ")?.split_whitespace();
+    let start = |range: &str| range.split(',').next()?.parse().ok();
+
+    let old_start = start(ranges.next()?.strip_prefix('-')?)?;
+    let new_start = start(ranges.next()?.strip_prefix('+')?)?;
+    Some((old_start, new_start))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{apply_patch, blob_ids};
+
+    const PATCH: &str =
+        "diff --git a/file b/file\n--- a/file\n+++ b/file\n@@ -2,3 +2,3 @@\n b\n-c\n+C\n d\n";
+
+    fn apply(content: &str, patch: &str, reverse: bool) -> Option<String> {
+        apply_patch(content.as_bytes(), patch.as_bytes(), reverse)
+            .map(|applied| String::from_utf8(applied).unwrap())
+    }
+
+    #[test]
+    fn apply_forward() {
+        assert_eq!(
+            apply("a\nb\nc\nd\ne\n", PATCH, false).unwrap(),
+            "a\nb\nC\nd\ne\n"
+        );
+    }
+
+    #[test]
+    fn apply_reverse() {
+        assert_eq!(
+            apply("a\nb\nC\nd\ne\n", PATCH, true).unwrap(),
+            "a\nb\nc\nd\ne\n"
+        );
+    }
+
+    #[test]
+    fn apply_at_offset() {
+        assert_eq!(apply("b\nc\nd\ne\n", PATCH, false).unwrap(), "b\nC\nd\ne\n");
+    }
+
+    #[test]
+    fn apply_mismatch() {
+        assert_eq!(apply("a\nb\nx\nd\ne\n", PATCH, false), None);
+    }
+
+    #[test]
+    fn apply_no_newline_at_end() {
+        let patch =
+            "@@ -1 +1 @@\n-old\n\\ No newline at end of file\n+new\n\\ No newline at end of file\n";
+        assert_eq!(apply("old", patch, false).unwrap(), "new");
+        assert_eq!(apply("new", patch, true).unwrap(), "old");
+    }
+
+    #[test]
+    fn header_blob_ids() {
+        let header = "\ndiff --git a/file b/file\nindex de98044..7be73ce 100644\n--- a/file\n";
+        assert_eq!(blob_ids(header), Some(("de98044", "7be73ce")));
+        assert_eq!(blob_ids("\ndiff --git a/file b/file\n"), None);
+    }
+
+    #[test]
+    fn apply_to_empty() {
+        assert_eq!(apply("", "@@ -0,0 +1 @@\n+new\n", false).unwrap(), "new\n");
+    }
+}
@@ -0,0 +1,122 @@
+use super::diff::Hunk;
+use crate::Res;
+use git2::{BlameOptions, Repository};
+
+/// The commit and author that last changed a line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct LineBlame {
+    pub short_id: String,
+    pub author: String,
+}
+
+/// Who last changed each of the hunk's unchanged lines as of `rev`, by content line.
+/// Changed lines are left out, being `None`. `new_side` tells whether `rev` has the file as it
+/// is after the hunk, like the commit on a show screen, rather than as it was before.
+/// If the file before the hunk isn't as of `rev`, like the index with staged changes, its lines are
+/// blamed as they are there, ones not in `rev` being not committed yet.
+pub(crate) fn blame_hunk(
+    repo: &Repository,
+    rev: &str,
+    hunk: &Hunk,
+    new_side: bool,
+) -> Res<Vec<Option<LineBlame>>> {
+    let (old_start, new_start) =
+        hunk_starts(&hunk.header).ok_or("Can't blame this kind of hunk")?;
+
+    let (mut old_line, mut new_line) = (old_start, new_start);
+    let mut line_numbers = vec![];
+    for line in &hunk.content.lines {
+        let prefix = line.spans.first().map(|span| &*span.content);
+        match prefix {
+            Some(" ") => {
+                line_numbers.push(Some(if new_side { new_line } else { old_line }));
+                old_line += 1;
+                new_line += 1;
+            }
+            Some("-") => {
+                line_numbers.push(None);
+                old_line += 1;
+            }
+            Some("+") => {
+                line_numbers.push(None);
+                new_line += 1;
+            }
+            // "\ No newline at end of file"
+            Some(text) if text.starts_with('\\') => line_numbers.push(None),
+            // Combined diffs of merges have a column per parent
+            _ => return Err("Can't blame the hunks of a merge".into()),
+        }
+    }
+
+    let (Some(&min), Some(&max)) = (
+        line_numbers.iter().flatten().min(),
+        line_numbers.iter().flatten().max(),
+    ) else {
+        return Ok(line_numbers.iter().map(|_| None).collect());
+    };
+
+    let commit = repo.revparse_single(rev)?.peel_to_commit()?;
+    let committed = commit
+        .tree()?
+        .get_path(&hunk.new_file)
+        .ok()
+        .map(|entry| entry.id());
+    let uncommitted = (!new_side && !hunk.old_id.is_zero() && Some(hunk.old_id) != committed)
+        .then(|| repo.find_blob(hunk.old_id))
+        .transpose()?;
+
+    let mut options = BlameOptions::new();
+    options.newest_commit(commit.id());
+    // Lines move around in the uncommitted content, so all of the file is needed for it
+    if uncommitted.is_none() {
+        options.min_line(min).max_line(max);
+    }
+
+    let committed_blame = repo.blame_file(&hunk.new_file, Some(&mut options))?;
+    let uncommitted_blame;
+    let blame = match &uncommitted {
+        Some(blob) => {
+            uncommitted_blame = committed_blame.blame_buffer(blob.content())?;
+            &uncommitted_blame
+        }
+        None => &committed_blame,
+    };
+
+    line_numbers
+        .into_iter()
+        .map(|line_number| {
+            let Some(blame_hunk) = line_number.and_then(|n| blame.get_line(n)) else {
+                return Ok(None);
+            };
+
+            let id = blame_hunk.final_commit_id();
+            if id.is_zero() {
+                return Ok(Some(LineBlame {
+                    short_id: "0000000".into(),
+                    author: "Not Committed Yet".into(),
+                }));
+            }
+
+            let signature = blame_hunk.final_signature();
+            Ok(Some(LineBlame {
+                short_id: repo
+                    .find_object(id, None)?
+                    .short_id()?
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+                author: signature.name().unwrap_or_default().to_string(),
+            }))
+        })
+        .collect()
+}
+
+/// The old and new start lines of a hunk header like `@@ -12,7 +12,8 @@`.
+fn hunk_starts(header: &str) -> Option<(usize, usize)> {
+    let mut ranges = header.strip_prefix("@@ ")?.split_whitespace();
+    let start = |range: &str| range.split(',').next()?.parse().ok();
+
+    let old_start = start(ranges.next()?.strip_prefix('-')?)?;
+    let new_start = start(ranges.next()?.strip_prefix('+')?)?;
+    Some((old_start, new_start))
+}
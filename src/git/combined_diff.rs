@@ -0,0 +1,215 @@
+use super::diff::{Delta, Diff, Hunk};
+use crate::{config::Config, Res};
+use encoding_rs::UTF_8;
+use ratatui::text::{Line, Span, Text};
+use std::{path::Path, process::Command, rc::Rc, str};
+
+/// How a merge commit differs from all of its parents at once, like `git show --cc`.
+/// Only the files and hunks that differ from every parent are included, typically conflict resolutions.
+pub(crate) fn combined_diff(config: &Config, dir: &Path, hash: &str) -> Res<Diff> {
+    let out = Command::new("git")
+        .args([
+            "show",
+            "--cc",
+            "--format=",
+            "--no-color",
+            "--no-ext-diff",
+            hash,
+        ])
+        .current_dir(dir)
+        .output()?;
+
+    if !out.status.success() {
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        return Err(stderr.lines().next().unwrap_or("git show failed").into());
+    }
+
+    Ok(parse(config, &String::from_utf8_lossy(&out.stdout)))
+}
+
+/// Parses combined diff output. Each line of a hunk starts with a column per parent,
+/// telling whether the line was added (`+`) or removed (`-`) compared to that parent.
+/// A file that can't be parsed gets a warning, and parsing carries on with the next one.
+pub(crate) fn parse(config: &Config, output: &str) -> Diff {
+    let style = &config.style.diff_highlight;
+    let mut deltas: Vec<Delta> = vec![];
+    let mut hunk: Option<(String, Vec<Line<'static>>)> = None;
+
+    let finish_hunk = |delta: Option<&mut Delta>, hunk: Option<(String, Vec<Line<'static>>)>| {
+        if let (Some(delta), Some((header, lines))) = (delta, hunk) {
+            delta.hunks.push(Rc::new(Hunk {
+                file_header: delta.file_header.clone(),
+                new_file: delta.new_file.clone(),
+                old_id: delta.old_id,
+                new_start: new_start(&header).unwrap_or_default(),
+                header,
+                definitions: Default::default(),
+                content: Text::from(lines),
+                encodings: (UTF_8, UTF_8),
+            }));
+        }
+    };
+
+    for line in output.lines() {
+        if let Some(path) = line
+            .strip_prefix("diff --cc ")
+            .or_else(|| line.strip_prefix("diff --combined "))
+        {
+            finish_hunk(deltas.last_mut(), hunk.take());
+            deltas.push(Delta {
+                file_header: format!("{line}\n"),
+                old_file: path.into(),
+                new_file: path.into(),
+                hunks: vec![],
+                status: git2::Delta::Modified,
+                old_mode: git2::FileMode::Blob,
+                new_mode: git2::FileMode::Blob,
+                old_id: git2::Oid::zero(),
+                new_id: git2::Oid::zero(),
+                contents: None,
+                binary: None,
+                warning: None,
+            });
+            continue;
+        }
+
+        let Some(delta) = deltas.last_mut() else {
+            continue;
+        };
+
+        if delta.warning.is_some() {
+            // Skipping the rest of a file that couldn't be parsed
+            continue;
+        }
+
+        if line.starts_with("@@@") {
+            finish_hunk(Some(delta), hunk.take());
+            if new_start(line).is_none() {
+                delta.warning = Some(format!("Couldn't parse hunk header {line}").into());
+                continue;
+            }
+
+            hunk = Some((line.to_string(), vec![]));
+            continue;
+        }
+
+        let Some((header, lines)) = &mut hunk else {
+            if line.starts_with("new file mode") {
+                delta.status = git2::Delta::Added;
+            } else if line.starts_with("deleted file mode") {
+                delta.status = git2::Delta::Deleted;
+            }
+
+            delta.file_header.push_str(line);
+            delta.file_header.push('\n');
+            continue;
+        };
+
+        if line.starts_with('\\') {
+            lines.push(Line::raw(line.to_string()));
+            continue;
+        }
+
+        // A hunk header like `@@@ -1,3 -1,3 +1,3 @@@` has one more `@` than there are parents
+        let parent_count = header.chars().take_while(|&c| c == '@').count() - 1;
+        let split_at = line
+            .char_indices()
+            .nth(parent_count)
+            .map_or(line.len(), |(i, _)| i);
+        let (origins, text) = line.split_at(split_at);
+
+        let origin_style = if origins.contains('+') {
+            &style.tag_new
+        } else if origins.contains('-') {
+            &style.tag_old
+        } else {
+            &style.unchanged_new
+        };
+
+        lines.push(Line::from(vec![
+            Span::styled(origins.to_string(), origin_style),
+            Span::raw(text.to_string()),
+        ]));
+    }
+
+    finish_hunk(deltas.last_mut(), hunk);
+    Diff { deltas }
+}
+
+/// Where the hunk starts in the merge result, the last range of the header.
+fn new_start(header: &str) -> Option<u32> {
+    header
+        .split_whitespace()
+        .find_map(|range| range.strip_prefix('+'))?
+        .split(',')
+        .next()?
+        .parse()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+    use crate::config;
+
+    #[test]
+    fn parse_conflict_resolution() {
+        let diff = parse(
+            &config::init_test_config().unwrap(),
+            "\
+diff --cc file.txt
+index 68a11f2,7be73ce..93d7e8c
+--- a/file.txt
++++ b/file.txt
+@@@ -1,3 -1,3 +1,3 @@@
+  a
+- bb
+ -B
+++resolved
+  c
+",
+        );
+
+        assert_eq!(diff.deltas.len(), 1);
+        let delta = &diff.deltas[0];
+        assert_eq!(delta.new_file.to_str(), Some("file.txt"));
+        assert!(delta.file_header.ends_with("+++ b/file.txt\n"));
+
+        let hunk = &delta.hunks[0];
+        assert_eq!(hunk.header, "@@@ -1,3 -1,3 +1,3 @@@");
+        assert_eq!(hunk.new_start, 1);
+
+        let origins = hunk
+            .content
+            .lines
+            .iter()
+            .map(|line| line.spans[0].content.as_ref())
+            .collect::<Vec<_>>();
+        assert_eq!(origins, ["  ", "- ", " -", "++", "  "]);
+        assert_eq!(hunk.new_line_number(3), 2);
+    }
+
+    #[test]
+    fn parse_malformed_hunk() {
+        let diff = parse(
+            &config::init_test_config().unwrap(),
+            "\
+diff --cc broken.txt
+@@@ nonsense @@@
+++ignored
+diff --cc file.txt
+@@@ -1 -1 +1 @@@
+++resolved
+",
+        );
+
+        assert_eq!(diff.deltas.len(), 2);
+        assert_eq!(
+            diff.deltas[0].warning.as_deref(),
+            Some("Couldn't parse hunk header @@@ nonsense @@@")
+        );
+        assert!(diff.deltas[0].hunks.is_empty());
+        assert_eq!(diff.deltas[1].warning, None);
+        assert_eq!(diff.deltas[1].hunks.len(), 1);
+    }
+}
@@ -0,0 +1,59 @@
+use crate::Res;
+use git2::Repository;
+use std::{collections::BTreeSet, fs};
+
+/// How far along resolving the conflicts of a merge, rebase or revert is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Conflicts {
+    pub resolved: usize,
+    pub total: usize,
+}
+
+/// Files resolved so far are no longer in conflict in the index, but git lists every file that
+/// was in the "Conflicts:" comment of `MERGE_MSG`. `None` if there never were any conflicts.
+pub(crate) fn conflicts(repo: &Repository) -> Res<Option<Conflicts>> {
+    let conflicted = repo
+        .index()?
+        .conflicts()?
+        .map(|conflict| {
+            let conflict = conflict?;
+            let entry = conflict
+                .our
+                .or(conflict.their)
+                .or(conflict.ancestor)
+                .ok_or("Conflict without any sides")?;
+            Ok(String::from_utf8_lossy(&entry.path).to_string())
+        })
+        .collect::<Res<BTreeSet<_>>>()?;
+
+    let listed = fs::read_to_string(repo.path().join("MERGE_MSG"))
+        .map(|message| listed_conflicts(&message))
+        .unwrap_or_default();
+
+    let total = listed.union(&conflicted).count();
+    if total == 0 {
+        return Ok(None);
+    }
+
+    Ok(Some(Conflicts {
+        resolved: listed.difference(&conflicted).count(),
+        total,
+    }))
+}
+
+/// The files under "# Conflicts:", with whatever `core.commentChar` is.
+fn listed_conflicts(message: &str) -> BTreeSet<String> {
+    let mut lines = message.lines();
+    let Some(comment_char) = lines.find_map(|line| {
+        let mut chars = line.chars();
+        let comment_char = chars.next()?;
+        (chars.as_str() == " Conflicts:").then_some(comment_char)
+    }) else {
+        return BTreeSet::new();
+    };
+
+    lines
+        .filter_map(|line| line.strip_prefix(comment_char)?.strip_prefix('\t'))
+        .map(|file| file.to_string())
+        .collect()
+}
@@ -1,9 +1,11 @@
 use crate::{
     config::Config,
-    syntax_highlight::{self},
+    syntax_highlight::{self, LazyTree},
     Res,
 };
-use git2::Repository;
+use chardetng::EncodingDetector;
+use encoding_rs::{Encoding, UTF_8};
+use git2::{AttrCheckFlags, Repository};
 use itertools::Itertools;
 use ratatui::{
     style::Style,
@@ -11,7 +13,9 @@ use ratatui::{
 };
 use similar::{Algorithm, DiffOp, DiffTag, DiffableStr, TextDiff};
 use std::{
+    cell::OnceCell,
     fs,
+    io::{self, Read},
     iter::{self},
     ops::Range,
     path::PathBuf,
@@ -20,26 +24,134 @@ use std::{
 };
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
 pub(crate) struct Diff {
     pub deltas: Vec<Delta>,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
 pub(crate) struct Delta {
+    #[cfg_attr(feature = "json", serde(skip))]
     pub file_header: String,
     pub old_file: PathBuf,
     pub new_file: PathBuf,
     pub hunks: Vec<Rc<Hunk>>,
+    #[cfg_attr(feature = "json", serde(serialize_with = "crate::json::ser::status"))]
     pub status: git2::Delta,
+    #[cfg_attr(feature = "json", serde(serialize_with = "crate::json::ser::mode"))]
+    pub old_mode: git2::FileMode,
+    #[cfg_attr(feature = "json", serde(serialize_with = "crate::json::ser::mode"))]
+    pub new_mode: git2::FileMode,
+    /// The blobs of the file before and after, zero for a side that doesn't exist or isn't hashed, like the worktree.
+    #[cfg_attr(feature = "json", serde(serialize_with = "crate::json::ser::oid"))]
+    pub old_id: git2::Oid,
+    #[cfg_attr(feature = "json", serde(serialize_with = "crate::json::ser::oid"))]
+    pub new_id: git2::Oid,
+    /// The file before and after, for rendering the change with an external diff tool.
+    /// Only kept if `general.external_diff` is set.
+    #[cfg_attr(feature = "json", serde(skip))]
+    pub contents: Option<Rc<FileContents>>,
+    /// Set instead of `hunks` and `contents` when either side of the file isn't text.
+    pub binary: Option<Rc<BinaryFile>>,
+    /// Why the file couldn't be diffed, when it's not for being binary. The rest of the diff is shown anyway.
+    pub warning: Option<Box<str>>,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+pub(crate) struct BinaryFile {
+    /// `None` if the file was added.
+    pub old: Option<BinarySide>,
+    /// `None` if the file was deleted.
+    pub new: Option<BinarySide>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+pub(crate) struct BinarySide {
+    #[cfg_attr(feature = "json", serde(serialize_with = "crate::json::ser::mode"))]
+    pub mode: git2::FileMode,
+    pub size: usize,
+    pub image: Option<ImageInfo>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+pub(crate) struct ImageInfo {
+    pub format: &'static str,
+    /// Width and height, if the format has them at a fixed place in its header.
+    pub dimensions: Option<(u32, u32)>,
+}
+
+#[derive(Debug)]
+pub(crate) struct FileContents {
+    pub old: String,
+    pub new: String,
+}
+
+impl Delta {
+    /// A short description of the change in file type, e.g. `file -> symlink`.
+    pub(crate) fn type_change(&self) -> Option<String> {
+        (self.status == git2::Delta::Typechange).then(|| {
+            format!(
+                "{} -> {}",
+                file_kind(self.old_mode),
+                file_kind(self.new_mode)
+            )
+        })
+    }
+
+    /// Whether the executable bit of a regular file was flipped.
+    pub(crate) fn has_mode_change(&self) -> bool {
+        let is_regular =
+            |mode| matches!(mode, git2::FileMode::Blob | git2::FileMode::BlobExecutable);
+        is_regular(self.old_mode) && is_regular(self.new_mode) && self.old_mode != self.new_mode
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
 pub(crate) struct Hunk {
+    #[cfg_attr(feature = "json", serde(skip))]
     pub file_header: String,
+    #[cfg_attr(feature = "json", serde(skip))]
     pub new_file: PathBuf,
+    /// The blob of the file before the changes, like the one in the index for unstaged ones.
+    #[cfg_attr(feature = "json", serde(skip))]
+    pub old_id: git2::Oid,
     pub new_start: u32,
     pub header: String,
+    #[cfg_attr(
+        feature = "json",
+        serde(serialize_with = "crate::json::ser::definitions")
+    )]
+    pub definitions: HunkDefinitions,
+    #[cfg_attr(feature = "json", serde(serialize_with = "crate::json::ser::lines"))]
     pub content: Text<'static>,
+    /// The encodings of the old and new file, which patches need to be converted back to.
+    #[cfg_attr(feature = "json", serde(skip))]
+    pub encodings: (&'static Encoding, &'static Encoding),
+}
+
+/// The definitions a hunk's changes are in. Only looked up once shown, as the file needs parsing.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct HunkDefinitions {
+    file: Option<Rc<LazyTree>>,
+    row: usize,
+    names: OnceCell<Vec<String>>,
+}
+
+impl HunkDefinitions {
+    /// Names of the definitions, outermost first.
+    pub(crate) fn get(&self) -> &[String] {
+        self.names.get_or_init(|| {
+            self.file
+                .as_ref()
+                .map(|file| file.enclosing_definitions(self.row))
+                .unwrap_or_default()
+        })
+    }
 }
 
 #[derive(Debug)]
@@ -49,6 +161,34 @@ pub(crate) enum PatchMode {
 }
 
 impl Hunk {
+    /// Added lines are encoded like the new file, everything else in the hunk like the old one.
+    /// The file header stays UTF-8, as do lines that the encoding can't represent.
+    pub(crate) fn encode_patch(&self, patch: String) -> Vec<u8> {
+        let (old_encoding, new_encoding) = self.encodings;
+        if old_encoding == UTF_8 && new_encoding == UTF_8 {
+            return patch.into_bytes();
+        }
+
+        let mut in_hunk = false;
+        let mut bytes = Vec::with_capacity(patch.len());
+        for line in patch.split_inclusive('\n') {
+            in_hunk |= line.starts_with("@@");
+            let encoding = if !in_hunk {
+                UTF_8
+            } else if line.starts_with('+') {
+                new_encoding
+            } else {
+                old_encoding
+            };
+
+            match encoding.encode(line) {
+                (encoded, _, false) => bytes.extend_from_slice(&encoded),
+                (_, _, true) => bytes.extend_from_slice(line.as_bytes()),
+            }
+        }
+        bytes
+    }
+
     pub(crate) fn format_patch(&self) -> String {
         format!("{}{}\n{}\n", &self.file_header, self.header, self.content)
     }
@@ -90,6 +230,41 @@ impl Hunk {
         )
     }
 
+    /// Whether the removed and added lines only differ in whitespace, e.g. after reindenting.
+    pub(crate) fn is_whitespace_only(&self) -> bool {
+        let changed_text = |prefix: char| -> String {
+            self.content
+                .lines
+                .iter()
+                .map(|line| format!("{line}"))
+                .filter_map(|line| {
+                    line.strip_prefix(prefix).map(|text| {
+                        text.chars()
+                            .filter(|c| !c.is_whitespace())
+                            .collect::<String>()
+                    })
+                })
+                .collect()
+        };
+
+        changed_text('-') == changed_text('+')
+    }
+
+    /// The line in the new file that content line `line_i` is at.
+    /// Removed lines are placed at the line that follows them.
+    pub(crate) fn new_line_number(&self, line_i: usize) -> u32 {
+        let preceding_new_lines = self.content.lines[..line_i]
+            .iter()
+            .filter(|line| {
+                line.spans
+                    .first()
+                    .is_some_and(|span| !span.content.contains('-'))
+            })
+            .count();
+
+        self.new_start + preceding_new_lines as u32
+    }
+
     pub(crate) fn first_diff_line(&self) -> u32 {
         self.content
             .lines
@@ -97,7 +272,7 @@ impl Hunk {
             .enumerate()
             .filter(|(_, line)| {
                 let start = &line.spans.first().unwrap().content;
-                start.starts_with('+') || start.starts_with('-')
+                start.contains(['+', '-'])
             })
             .map(|(i, _)| i)
             .next()
@@ -106,6 +281,15 @@ impl Hunk {
     }
 }
 
+fn file_kind(mode: git2::FileMode) -> &'static str {
+    match mode {
+        git2::FileMode::Link => "symlink",
+        git2::FileMode::Commit => "submodule",
+        git2::FileMode::Tree => "directory",
+        _ => "file",
+    }
+}
+
 pub(crate) fn convert_diff(
     config: &Config,
     repo: &Repository,
@@ -127,10 +311,30 @@ pub(crate) fn convert_diff(
                     new_file: path(&diffdelta.new_file()),
                     hunks: vec![],
                     status: diffdelta.status(),
+                    old_mode: diffdelta.old_file().mode(),
+                    new_mode: diffdelta.new_file().mode(),
+                    old_id: diffdelta.old_file().id(),
+                    new_id: diffdelta.new_file().id(),
+                    contents: None,
+                    binary: None,
+                    warning: None,
                 };
 
-                if let Ok(hunks) = diff_files(repo, diffdelta, workdir, config, &delta) {
-                    delta.hunks = hunks;
+                match diff_files(repo, &diffdelta, workdir, config, &delta) {
+                    Ok((hunks, contents)) => {
+                        delta.hunks = hunks;
+                        delta.contents =
+                            (!config.general.external_diff.is_empty()).then_some(contents);
+                    }
+                    Err(err) => {
+                        delta.binary = binary_file(repo, &diffdelta, workdir).map(Rc::new);
+
+                        // Submodules have no content of their own to diff
+                        let is_submodule = delta.new_mode == git2::FileMode::Commit;
+                        if delta.binary.is_none() && !is_submodule {
+                            delta.warning = Some(err.to_string().into());
+                        }
+                    }
                 }
 
                 deltas.push(delta);
@@ -148,20 +352,33 @@ pub(crate) fn convert_diff(
 
 fn diff_files(
     repo: &Repository,
-    diffdelta: git2::DiffDelta<'_>,
+    diffdelta: &git2::DiffDelta<'_>,
     workdir: bool,
     config: &Config,
     delta: &Delta,
-) -> Res<Vec<Rc<Hunk>>> {
-    let old_content = read_blob(repo, &diffdelta.old_file())?.replace("\r\n", "\n");
-    let new_content = if workdir {
+) -> Res<(Vec<Rc<Hunk>>, Rc<FileContents>)> {
+    let (old_content, old_encoding) = read_blob(repo, &diffdelta.old_file())?;
+    let (new_content, new_encoding) = if workdir {
         read_workdir(repo, &diffdelta.new_file())?
     } else {
         read_blob(repo, &diffdelta.new_file())?
-    }
-    .replace("\r\n", "\n");
+    };
 
-    diff_content(config, delta, &old_content, &new_content)
+    let hunks = diff_content(
+        config,
+        delta,
+        &old_content.replace("\r\n", "\n"),
+        &new_content.replace("\r\n", "\n"),
+        (old_encoding, new_encoding),
+    )?;
+
+    Ok((
+        hunks,
+        Rc::new(FileContents {
+            old: old_content,
+            new: new_content,
+        }),
+    ))
 }
 
 fn diff_content(
@@ -169,6 +386,7 @@ fn diff_content(
     delta: &Delta,
     old_content: &str,
     new_content: &str,
+    encodings: (&'static Encoding, &'static Encoding),
 ) -> Res<Vec<Rc<Hunk>>> {
     let style = &config.style;
     let old_lines = old_content.tokenize_lines();
@@ -181,6 +399,10 @@ fn diff_content(
         .algorithm(Algorithm::Patience)
         .diff_slices(&old_lines, &new_lines);
 
+    // Kept for the side a hunk's definitions are looked up in, as most hunks only need the new one
+    let old_tree = OnceCell::new();
+    let new_tree = OnceCell::new();
+
     let mut old_syntax_highlights = if config.style.syntax_highlight.enabled {
         syntax_highlight::highlight(config, &delta.old_file, old_content)
     } else {
@@ -291,6 +513,38 @@ fn diff_content(
 
             let formatted_hunk = Text::from(lines);
 
+            let definitions = hunk
+                .ops()
+                .iter()
+                .find(|op| op.tag() != DiffTag::Equal)
+                .map(|op| {
+                    let (_, old_line, new_line) = op.as_tag_tuple();
+                    let (tree, row) = if new_line.is_empty() {
+                        let tree = old_tree.get_or_init(|| {
+                            Rc::new(LazyTree::new(
+                                delta.old_file.clone(),
+                                old_content.to_string(),
+                            ))
+                        });
+                        (tree, old_line.start)
+                    } else {
+                        let tree = new_tree.get_or_init(|| {
+                            Rc::new(LazyTree::new(
+                                delta.new_file.clone(),
+                                new_content.to_string(),
+                            ))
+                        });
+                        (tree, new_line.start)
+                    };
+
+                    HunkDefinitions {
+                        file: Some(Rc::clone(tree)),
+                        row,
+                        names: OnceCell::new(),
+                    }
+                })
+                .unwrap_or_default();
+
             let new_start = hunk
                 .header()
                 .to_string()
@@ -303,16 +557,27 @@ fn diff_content(
                 .unwrap();
 
             Rc::new(Hunk {
-                file_header: delta.file_header.clone(),
+                file_header: hunk_file_header(&delta.file_header),
                 new_file: delta.new_file.clone(),
+                old_id: delta.old_id,
                 new_start,
                 header: format!("{}", hunk.header()),
+                definitions,
                 content: formatted_hunk,
+                encodings,
             })
         })
         .collect::<Vec<_>>())
 }
 
+/// Mode changes are left out, so that they can be staged independently from the hunks.
+fn hunk_file_header(file_header: &str) -> String {
+    file_header
+        .split_inclusive('\n')
+        .filter(|line| !line.starts_with("old mode ") && !line.starts_with("new mode "))
+        .collect()
+}
+
 fn map_from_token_to_byte_range(
     word_range: &Range<usize>,
     old_lines_range: &Range<usize>,
@@ -471,18 +736,160 @@ fn next_merged_style_range(
     }
 }
 
-fn read_workdir(repo: &Repository, new_file: &git2::DiffFile<'_>) -> Res<String> {
-    Ok(fs::read_to_string(
-        repo.workdir()
-            .expect("No workdir")
-            .join(new_file.path().unwrap()),
-    )?)
+/// Describes both sides of the file if either of them is binary.
+fn binary_file(
+    repo: &Repository,
+    diffdelta: &git2::DiffDelta<'_>,
+    workdir: bool,
+) -> Option<BinaryFile> {
+    let side = |file: git2::DiffFile<'_>, from_workdir: bool| -> Option<(BinarySide, bool)> {
+        if !file.exists() {
+            return None;
+        }
+
+        let (size, head) = if from_workdir {
+            let file = fs::File::open(repo.workdir()?.join(file.path()?)).ok()?;
+            let size = file.metadata().ok()?.len() as usize;
+            let mut head = vec![];
+            file.take(BINARY_SNIFF_LEN as u64)
+                .read_to_end(&mut head)
+                .ok()?;
+            (size, head)
+        } else {
+            let blob = repo.find_blob(file.id()).ok()?;
+            let head = &blob.content()[..blob.size().min(BINARY_SNIFF_LEN)];
+            (blob.size(), head.to_vec())
+        };
+
+        let side = BinarySide {
+            mode: file.mode(),
+            size,
+            image: image_info(&head),
+        };
+
+        Some((side, looks_binary(head)))
+    };
+
+    let old = side(diffdelta.old_file(), false);
+    let new = side(diffdelta.new_file(), workdir);
+    let is_binary = |side: &Option<(BinarySide, bool)>| side.as_ref().is_some_and(|s| s.1);
+
+    (is_binary(&old) || is_binary(&new)).then(|| BinaryFile {
+        old: old.map(|(side, _)| side),
+        new: new.map(|(side, _)| side),
+    })
+}
+
+/// How much of the start of a file is enough to tell if it's binary, like git looks at.
+const BINARY_SNIFF_LEN: usize = 8000;
+
+/// Whether the start of a file wouldn't `decode` as text, without reading all of it.
+fn looks_binary(head: Vec<u8>) -> bool {
+    match str::from_utf8(&head) {
+        Ok(_) => false,
+        // Only cut off in the middle of a character
+        Err(err) if err.error_len().is_none() => false,
+        Err(_) => decode(head).is_err(),
+    }
+}
+
+/// Recognizes common image formats by their magic bytes.
+fn image_info(bytes: &[u8]) -> Option<ImageInfo> {
+    let be_u32 = |at: usize| Some(u32::from_be_bytes(bytes.get(at..at + 4)?.try_into().ok()?));
+    let le_u16 =
+        |at: usize| Some(u16::from_le_bytes(bytes.get(at..at + 2)?.try_into().ok()?) as u32);
+    let le_u32 = |at: usize| Some(u32::from_le_bytes(bytes.get(at..at + 4)?.try_into().ok()?));
+
+    let (format, dimensions) = if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        ("PNG", be_u32(16).zip(be_u32(20)))
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        ("GIF", le_u16(6).zip(le_u16(8)))
+    } else if bytes.starts_with(b"BM") && bytes.len() >= 26 {
+        // Rows are stored bottom-up if the height is positive, top-down if negative
+        (
+            "BMP",
+            le_u32(18).zip(le_u32(22).map(|h| (h as i32).unsigned_abs())),
+        )
+    } else if bytes.starts_with(b"\xff\xd8\xff") {
+        ("JPEG", None)
+    } else if bytes.starts_with(b"RIFF") && bytes.get(8..12) == Some(b"WEBP") {
+        ("WebP", None)
+    } else {
+        return None;
+    };
+
+    Some(ImageInfo { format, dimensions })
 }
 
-fn read_blob(repo: &Repository, file: &git2::DiffFile<'_>) -> Res<String> {
-    let blob = repo.find_blob(file.id());
-    blob.map(|blob| Ok(String::from_utf8(blob.content().to_vec())?))
-        .unwrap_or(Ok("".to_string()))
+fn read_workdir(
+    repo: &Repository,
+    new_file: &git2::DiffFile<'_>,
+) -> Res<(String, &'static Encoding)> {
+    let relative_path = new_file.path().unwrap();
+    let path = repo.workdir().expect("No workdir").join(relative_path);
+
+    let metadata = match fs::symlink_metadata(&path) {
+        Ok(metadata) => metadata,
+        // Deleted in the worktree
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok((String::new(), UTF_8)),
+        Err(err) => return Err(err.into()),
+    };
+
+    // Like git, diff the target path of symlinks, rather than the contents of what they point to
+    if metadata.is_symlink() {
+        return Ok((fs::read_link(&path)?.to_string_lossy().into_owned(), UTF_8));
+    }
+
+    let bytes = fs::read(path)?;
+
+    // Git stores these as UTF-8, only the worktree copy is in the declared encoding
+    let declared = repo
+        .get_attr(
+            relative_path,
+            "working-tree-encoding",
+            AttrCheckFlags::default(),
+        )?
+        .and_then(|label| Encoding::for_label(label.as_bytes()));
+
+    match declared {
+        Some(encoding) => Ok((encoding.decode(&bytes).0.into_owned(), UTF_8)),
+        None => decode(bytes),
+    }
+}
+
+fn read_blob(repo: &Repository, file: &git2::DiffFile<'_>) -> Res<(String, &'static Encoding)> {
+    match repo.find_blob(file.id()) {
+        Ok(blob) => decode(blob.content().to_vec()),
+        Err(_) => Ok(("".to_string(), UTF_8)),
+    }
+}
+
+/// Decodes text as UTF-8, or if that fails, whatever legacy encoding it most likely is in.
+fn decode(bytes: Vec<u8>) -> Res<(String, &'static Encoding)> {
+    let bytes = match String::from_utf8(bytes) {
+        Ok(text) => return Ok((text, UTF_8)),
+        Err(err) => err.into_bytes(),
+    };
+
+    // Some ASCII (if only line breaks) is expected in text of any of the legacy encodings
+    if bytes.contains(&0) || !bytes.iter().any(u8::is_ascii) {
+        return Err("Binary content".into());
+    }
+
+    let mut detector = EncodingDetector::new();
+    detector.feed(&bytes, true);
+    let encoding = detector.guess(None, true);
+    let text = encoding.decode_without_bom_handling(&bytes).0.into_owned();
+
+    let is_binary = text
+        .chars()
+        .any(|c| c.is_control() && !matches!(c, '\t' | '\n' | '\r' | '\x0c'));
+
+    if is_binary {
+        return Err("Binary content".into());
+    }
+
+    Ok((text, encoding))
 }
 
 fn path(file: &git2::DiffFile) -> PathBuf {
@@ -512,6 +919,70 @@ mod tests {
         insta::assert_snapshot!(hunks[0].format_patch());
     }
 
+    #[test]
+    fn new_line_numbers() {
+        let hunks = diff_content("a\nb\nc\nd\n", "a\nB\nc\nd\n");
+        // " a", "-b", "+B", " c", " d"
+        let line_numbers = (0..5)
+            .map(|i| hunks[0].new_line_number(i))
+            .collect::<Vec<_>>();
+        assert_eq!(line_numbers, [1, 2, 2, 3, 4]);
+    }
+
+    #[test]
+    fn decode_legacy_encoding() {
+        let (text, encoding) = super::decode(b"Gr\xfc\xdfe aus K\xf6ln\n".to_vec()).unwrap();
+        assert_eq!(text, "Grüße aus Köln\n");
+        assert_eq!(encoding, encoding_rs::WINDOWS_1252);
+    }
+
+    #[test]
+    fn decode_utf8() {
+        let (text, encoding) = super::decode("Grüße".as_bytes().to_vec()).unwrap();
+        assert_eq!(text, "Grüße");
+        assert_eq!(encoding, encoding_rs::UTF_8);
+    }
+
+    #[test]
+    fn encode_patch_per_side() {
+        let mut hunk = (*diff_content("Köln\nalt\n", "Köln\nneu €\n")[0]).clone();
+        hunk.encodings = (encoding_rs::WINDOWS_1252, encoding_rs::UTF_8);
+        let patch = hunk.encode_patch(hunk.format_patch());
+        let body = &patch[patch.windows(2).position(|w| w == b"@@").unwrap()..];
+
+        let contains = |line: &[u8]| body.windows(line.len()).any(|w| w == line);
+        assert!(contains(b" K\xf6ln\n"));
+        assert!(contains(b"-alt\n"));
+        assert!(contains("+neu €\n".as_bytes()));
+    }
+
+    #[test]
+    fn encode_patch_falls_back_when_lossy() {
+        let mut hunk = (*diff_content("a\n", "€\n")[0]).clone();
+        hunk.encodings = (encoding_rs::WINDOWS_1252, encoding_rs::SHIFT_JIS);
+        let patch = hunk.encode_patch(hunk.format_patch());
+        assert!(patch.windows(5).any(|w| w == "+€\n".as_bytes()));
+    }
+
+    #[test]
+    fn png_dimensions() {
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+        png.extend(640u32.to_be_bytes());
+        png.extend(480u32.to_be_bytes());
+        assert_eq!(
+            super::image_info(&png),
+            Some(super::ImageInfo {
+                format: "PNG",
+                dimensions: Some((640, 480))
+            })
+        );
+    }
+
+    #[test]
+    fn not_an_image() {
+        assert_eq!(super::image_info(b"\0\x01\x02"), None);
+    }
+
     fn diff_content(old_content: &str, new_content: &str) -> Vec<std::rc::Rc<super::Hunk>> {
         super::diff_content(
             &config::init_test_config().unwrap(),
@@ -521,9 +992,17 @@ mod tests {
                 old_file: "old_file".into(),
                 hunks: vec![],
                 status: git2::Delta::Modified,
+                old_mode: git2::FileMode::Blob,
+                new_mode: git2::FileMode::Blob,
+                old_id: git2::Oid::zero(),
+                new_id: git2::Oid::zero(),
+                contents: None,
+                binary: None,
+                warning: None,
             },
             old_content,
             new_content,
+            (encoding_rs::UTF_8, encoding_rs::UTF_8),
         )
         .unwrap()
     }
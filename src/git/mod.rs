@@ -1,8 +1,8 @@
-use git2::{DiffFindOptions, Repository};
+use git2::{DiffFindOptions, Oid, Repository};
 use itertools::Itertools;
 
 use self::{commit::Commit, diff::Diff, merge_status::MergeStatus, rebase_status::RebaseStatus};
-use crate::{config::Config, git2_opts, Res};
+use crate::{config::Config, git2_opts, locale, Res};
 use std::{
     fs,
     path::Path,
@@ -10,11 +10,20 @@ use std::{
     str::{self},
 };
 
+pub(crate) mod apply;
+pub(crate) mod blame;
+pub(crate) mod combined_diff;
 pub(crate) mod commit;
+pub(crate) mod conflicts;
 pub(crate) mod diff;
 pub(crate) mod merge_status;
+pub(crate) mod range_diff;
 pub(crate) mod rebase_status;
 pub(crate) mod remote;
+pub(crate) mod revise;
+pub(crate) mod signature;
+pub(crate) mod stash;
+pub(crate) mod workspace;
 
 // TODO Use only plumbing commands
 
@@ -26,6 +35,14 @@ pub(crate) fn rebase_status(repo: &Repository) -> Res<Option<RebaseStatus>> {
     let mut rebase_head_name_file = dir.to_path_buf();
     rebase_head_name_file.push(".git/rebase-merge/head-name");
 
+    let read_number = |name: &str| -> Option<usize> {
+        fs::read_to_string(dir.join(".git/rebase-merge").join(name))
+            .ok()?
+            .trim()
+            .parse()
+            .ok()
+    };
+
     match fs::read_to_string(&rebase_onto_file) {
         Ok(content) => {
             let onto_hash = content.trim().to_string();
@@ -36,6 +53,7 @@ pub(crate) fn rebase_status(repo: &Repository) -> Res<Option<RebaseStatus>> {
                     .strip_prefix("refs/heads/")
                     .unwrap()
                     .to_string(),
+                step: read_number("msgnum").zip(read_number("end")),
                 // TODO include log of 'done' items
             }))
         }
@@ -114,17 +132,33 @@ fn branch_name(dir: &Path, hash: &str) -> Res<Option<String>> {
         .map(|line| line.split(' ').nth(1).unwrap().to_string()))
 }
 
-pub(crate) fn diff_unstaged(config: &Config, repo: &Repository) -> Res<Diff> {
-    let diff = repo.diff_index_to_workdir(None, Some(&mut git2_opts::diff(repo)?))?;
+/// The worktree compared to `base`, or the index if there is none.
+pub(crate) fn diff_unstaged(config: &Config, repo: &Repository, base: Option<&str>) -> Res<Diff> {
+    let opts = &mut git2_opts::diff(repo)?;
+    let diff = match base {
+        Some(base) => {
+            let tree = repo.revparse_single(base)?.peel_to_tree()?;
+            repo.diff_tree_to_workdir(Some(&tree), Some(opts))?
+        }
+        None => repo.diff_index_to_workdir(None, Some(opts))?,
+    };
+
     diff::convert_diff(config, repo, diff, true)
 }
 
-pub(crate) fn diff_staged(config: &Config, repo: &Repository) -> Res<Diff> {
+/// The index compared to `base`, or HEAD if there is none.
+pub(crate) fn diff_staged(config: &Config, repo: &Repository, base: Option<&str>) -> Res<Diff> {
     let opts = &mut git2_opts::diff(repo)?;
 
-    let mut diff = match repo.head() {
-        Ok(head) => repo.diff_tree_to_index(Some(&head.peel_to_tree()?), None, Some(opts))?,
-        Err(_) => repo.diff_tree_to_index(None, None, Some(opts))?,
+    let mut diff = match (base, repo.head()) {
+        (Some(base), _) => {
+            let tree = repo.revparse_single(base)?.peel_to_tree()?;
+            repo.diff_tree_to_index(Some(&tree), None, Some(opts))?
+        }
+        (None, Ok(head)) => {
+            repo.diff_tree_to_index(Some(&head.peel_to_tree()?), None, Some(opts))?
+        }
+        (None, Err(_)) => repo.diff_tree_to_index(None, None, Some(opts))?,
     };
 
     diff.find_similar(Some(&mut DiffFindOptions::new().renames(true)))?;
@@ -136,6 +170,11 @@ pub(crate) fn show(config: &Config, repo: &Repository, reference: &str) -> Res<D
     let object = &repo.revparse_single(reference)?;
 
     let commit = object.peel_to_commit()?;
+    if commit.parent_count() > 1 {
+        let dir = repo.workdir().unwrap_or(repo.path());
+        return combined_diff::combined_diff(config, dir, &commit.id().to_string());
+    }
+
     let tree = commit.tree()?;
     let parent_tree = commit
         .parents()
@@ -151,7 +190,62 @@ pub(crate) fn show(config: &Config, repo: &Repository, reference: &str) -> Res<D
     diff::convert_diff(config, repo, diff, false)
 }
 
-pub(crate) fn show_summary(repo: &Repository, reference: &str) -> Res<Commit> {
+/// The combined changes of a range like `git diff A..B`, or from where they branched off with `A...B`.
+pub(crate) fn diff_range(config: &Config, repo: &Repository, range: &str) -> Res<Diff> {
+    let spec = repo.revparse(range)?;
+    let (Some(from), Some(to)) = (spec.from(), spec.to()) else {
+        return Err(format!("{} isn't a range like A..B", range).into());
+    };
+
+    let to = to.peel_to_commit()?;
+    let from = if spec.mode().contains(git2::RevparseMode::MERGE_BASE) {
+        repo.find_commit(repo.merge_base(from.id(), to.id())?)?
+    } else {
+        from.peel_to_commit()?
+    };
+
+    let diff = repo.diff_tree_to_tree(
+        Some(&from.tree()?),
+        Some(&to.tree()?),
+        Some(&mut git2_opts::diff(repo)?),
+    )?;
+
+    diff::convert_diff(config, repo, diff, false)
+}
+
+/// Where HEAD branched off `general.base_branch`, unless it's not set, HEAD isn't on a branch,
+/// or there's been nothing committed since.
+pub(crate) fn branch_point(config: &Config, repo: &Repository) -> Res<Option<Oid>> {
+    let base_branch = &config.general.base_branch;
+    if base_branch.is_empty() || repo.head_detached()? {
+        return Ok(None);
+    }
+
+    let head = repo.head()?.peel_to_commit()?.id();
+    let base = repo.revparse_single(base_branch)?.peel_to_commit()?.id();
+    let merge_base = repo.merge_base(head, base)?;
+    Ok((merge_base != head).then_some(merge_base))
+}
+
+/// Formats a date with `general.date_format` (checked as the config loads) in the user's locale.
+/// If it's empty, like the locale does, or like RFC 2822 without one.
+pub(crate) fn format_date<Tz: chrono::TimeZone>(
+    config: &Config,
+    time: &chrono::DateTime<Tz>,
+) -> String
+where
+    Tz::Offset: std::fmt::Display,
+{
+    let format = &config.general.date_format;
+    match (format.is_empty(), locale::locale(config)) {
+        (true, None) => time.to_rfc2822(),
+        (true, Some(locale)) => time.format_localized("%c", locale).to_string(),
+        (false, Some(locale)) => time.format_localized(format, locale).to_string(),
+        (false, None) => time.format(format).to_string(),
+    }
+}
+
+pub(crate) fn show_summary(config: &Config, repo: &Repository, reference: &str) -> Res<Commit> {
     let object = &repo.revparse_single(reference)?;
     let commit = object.peel_to_commit()?;
 
@@ -180,7 +274,7 @@ pub(crate) fn show_summary(repo: &Repository, reference: &str) -> Res<Commit> {
     let details = format!(
         "Author: {}\nDate:   {}\n\n{}",
         [name, &email].join(" "),
-        time.to_rfc2822(),
+        format_date(config, &time),
         message
     );
 
@@ -190,6 +284,82 @@ pub(crate) fn show_summary(repo: &Repository, reference: &str) -> Res<Commit> {
     })
 }
 
+/// Describes where a detached HEAD came from like `git status` does, `at <rev>` if it's still there
+/// and `from <rev>` if commits were made or it was moved since. `<rev>` is what was checked out.
+pub(crate) fn detached_from(repo: &Repository) -> Res<String> {
+    let head_id = repo.head()?.peel_to_commit()?.id();
+    let checked_out = repo
+        .reflog("HEAD")?
+        .iter()
+        .find_map(|entry| {
+            let (_, rev) = parse_checkout(entry.message()?)?;
+            Some(rev.to_string())
+        })
+        .filter(|rev| rev != "HEAD");
+
+    let Some(rev) = checked_out else {
+        return Ok(format!("at {}", short_id(repo, head_id)?));
+    };
+
+    let rev_id = repo
+        .revparse_single(&rev)
+        .and_then(|obj| obj.peel_to_commit());
+    let rev = if rev.len() == 40 && Oid::from_str(&rev).is_ok() {
+        short_id(repo, Oid::from_str(&rev)?)?
+    } else {
+        rev
+    };
+
+    Ok(match rev_id {
+        Ok(commit) if commit.id() == head_id => format!("at {}", rev),
+        _ => format!("from {}", rev),
+    })
+}
+
+/// The local branch most recently checked out before the current one (or before HEAD got detached).
+pub(crate) fn previous_branch(repo: &Repository) -> Res<Option<String>> {
+    let head = repo.head()?;
+    let current = head.is_branch().then(|| head.shorthand()).flatten();
+
+    Ok(repo.reflog("HEAD")?.iter().find_map(|entry| {
+        let (from, _) = parse_checkout(entry.message()?)?;
+        let is_branch = repo.find_branch(from, git2::BranchType::Local).is_ok();
+        (is_branch && Some(from) != current).then(|| from.to_string())
+    }))
+}
+
+/// What `@{-1}` refers to: the branch, or commit if HEAD was detached, checked out before the current one.
+pub(crate) fn previous_checkout(repo: &Repository) -> Res<Option<String>> {
+    let reflog = repo.reflog("HEAD")?;
+    let Some(from) = reflog.iter().find_map(|entry| {
+        let (from, _) = parse_checkout(entry.message()?)?;
+        Some(from.to_string())
+    }) else {
+        return Ok(None);
+    };
+
+    Ok(Some(match Oid::from_str(&from) {
+        Ok(oid) if from.len() == 40 => short_id(repo, oid)?,
+        _ => from,
+    }))
+}
+
+/// The `(from, to)` of a reflog entry left by a checkout, e.g. "checkout: moving from main to feature".
+fn parse_checkout(message: &str) -> Option<(&str, &str)> {
+    message
+        .strip_prefix("checkout: moving from ")?
+        .rsplit_once(" to ")
+}
+
+fn short_id(repo: &Repository, oid: Oid) -> Res<String> {
+    Ok(repo
+        .find_object(oid, None)?
+        .short_id()?
+        .as_str()
+        .unwrap_or_default()
+        .to_string())
+}
+
 pub(crate) fn get_head(repo: &git2::Repository) -> Res<String> {
     let head = repo.head()?;
     if head.is_branch() {
@@ -198,3 +368,47 @@ pub(crate) fn get_head(repo: &git2::Repository) -> Res<String> {
         Err("Head is not a branch".into())
     }
 }
+
+/// The `fixup!`, `squash!` and `amend!` commits between the upstream and HEAD, newest first,
+/// along with the commit an autosquash rebase of them starts from. `None` without an upstream.
+pub(crate) fn autosquash_commits(repo: &Repository) -> Res<Option<(Oid, Vec<Oid>)>> {
+    let Ok(head) = repo.head() else {
+        return Ok(None);
+    };
+    let Some(upstream) = head
+        .name()
+        .and_then(|name| repo.branch_upstream_name(name).ok())
+    else {
+        return Ok(None);
+    };
+    let Ok(upstream_id) = repo.refname_to_id(upstream.as_str().unwrap_or_default()) else {
+        return Ok(None);
+    };
+
+    let head_id = head.peel_to_commit()?.id();
+    // No merge base is found when it's cut off in a shallow clone
+    let Ok(base) = repo.merge_base(head_id, upstream_id) else {
+        return Ok(None);
+    };
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(head_id)?;
+    revwalk.hide(base)?;
+
+    let mut fixups = vec![];
+    for oid in revwalk {
+        let oid = oid?;
+        let summary = repo
+            .find_commit(oid)?
+            .summary()
+            .unwrap_or_default()
+            .to_string();
+        if ["fixup! ", "squash! ", "amend! "]
+            .iter()
+            .any(|prefix| summary.starts_with(prefix))
+        {
+            fixups.push(oid);
+        }
+    }
+
+    Ok(Some((base, fixups)))
+}
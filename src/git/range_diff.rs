@@ -0,0 +1,112 @@
+use crate::Res;
+use regex::Regex;
+use std::{path::Path, process::Command, str};
+
+/// How a commit in the old range relates to one in the new range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PairStatus {
+    Equal,
+    Changed,
+    Removed,
+    Added,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CommitPair {
+    pub old_hash: Option<String>,
+    pub new_hash: Option<String>,
+    pub status: PairStatus,
+    pub subject: String,
+    /// The diff between the two patches, if they differ.
+    pub body: Vec<String>,
+}
+
+pub(crate) fn range_diff(dir: &Path, args: &[&str]) -> Res<Vec<CommitPair>> {
+    let out = Command::new("git")
+        .args(["range-diff", "--no-color"])
+        .args(args)
+        .current_dir(dir)
+        .output()?;
+
+    if !out.status.success() {
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        return Err(stderr
+            .lines()
+            .next()
+            .unwrap_or("git range-diff failed")
+            .into());
+    }
+
+    Ok(parse(str::from_utf8(&out.stdout)?))
+}
+
+pub(crate) fn parse(output: &str) -> Vec<CommitPair> {
+    let pair_line =
+        Regex::new(r"^\s*(?:\d+|-):\s+([0-9a-f]+|-+) ([=!<>])\s+(?:\d+|-):\s+([0-9a-f]+|-+) (.*)$")
+            .unwrap();
+    let mut pairs: Vec<CommitPair> = vec![];
+
+    for line in output.lines() {
+        if let Some(captures) = pair_line.captures(line) {
+            let hash =
+                |i: usize| Some(captures[i].to_string()).filter(|hash| !hash.starts_with('-'));
+
+            pairs.push(CommitPair {
+                old_hash: hash(1),
+                new_hash: hash(3),
+                status: match &captures[2] {
+                    "=" => PairStatus::Equal,
+                    "!" => PairStatus::Changed,
+                    "<" => PairStatus::Removed,
+                    _ => PairStatus::Added,
+                },
+                subject: captures[4].to_string(),
+                body: vec![],
+            });
+        } else if let Some(pair) = pairs.last_mut() {
+            pair.body
+                .push(line.strip_prefix("    ").unwrap_or(line).to_string());
+        }
+    }
+
+    for pair in &mut pairs {
+        while pair.body.last().is_some_and(|line| line.trim().is_empty()) {
+            pair.body.pop();
+        }
+    }
+
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, PairStatus};
+
+    #[test]
+    fn parse_pairs() {
+        let pairs = parse(
+            "\
+1:  1111111 = 1:  aaaaaaa Unchanged commit
+2:  2222222 ! 2:  bbbbbbb Changed commit
+    @@ file.txt
+    -old
+    +new
+
+3:  3333333 < -:  ------- Dropped commit
+-:  ------- > 3:  ccccccc Added commit
+",
+        );
+
+        assert_eq!(pairs.len(), 4);
+        assert_eq!(pairs[0].status, PairStatus::Equal);
+        assert_eq!(pairs[0].old_hash.as_deref(), Some("1111111"));
+        assert_eq!(pairs[0].new_hash.as_deref(), Some("aaaaaaa"));
+        assert_eq!(pairs[1].status, PairStatus::Changed);
+        assert_eq!(pairs[1].body, ["@@ file.txt", "-old", "+new"]);
+        assert_eq!(pairs[2].status, PairStatus::Removed);
+        assert_eq!(pairs[2].new_hash, None);
+        assert_eq!(pairs[3].status, PairStatus::Added);
+        assert_eq!(pairs[3].old_hash, None);
+        assert_eq!(pairs[3].subject, "Added commit");
+    }
+}
@@ -1,4 +1,6 @@
 pub(crate) struct RebaseStatus {
     pub onto: String,
     pub head_name: String,
+    /// The step it's at out of how many there are, from `rebase-merge/msgnum` and `rebase-merge/end`.
+    pub step: Option<(usize, usize)>,
 }
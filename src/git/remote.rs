@@ -1,8 +1,8 @@
-use git2::{Branch, Remote, Repository};
+use git2::{Branch, Oid, Remote, Repository};
 
-use crate::Res;
+use crate::{config::Config, locale::format_count, Res};
 
-pub(crate) fn get_upstream(repo: &Repository) -> Res<Option<Branch>> {
+pub(crate) fn get_upstream(repo: &Repository) -> Res<Option<Branch<'_>>> {
     let r = if repo.head()?.is_branch() {
         Branch::wrap(repo.head()?)
     } else {
@@ -68,6 +68,64 @@ pub(crate) fn get_upstream_shortname(repo: &Repository) -> Res<Option<String>> {
     ))
 }
 
+/// What pushing to (if `outgoing`) or pulling from the upstream would transfer,
+/// e.g. `2 commits to origin/main, 1 file changed, +3 -1`.
+pub(crate) fn upstream_transfer_summary(
+    config: &Config,
+    repo: &Repository,
+    outgoing: bool,
+) -> Res<Option<String>> {
+    let Some(upstream) = get_upstream(repo)? else {
+        return Ok(None);
+    };
+
+    let upstream_name = upstream.get().shorthand().ok_or("Upstream ref not utf-8")?;
+    let upstream_id = upstream.get().peel_to_commit()?.id();
+    let head_id = repo.head()?.peel_to_commit()?.id();
+
+    let (from, to) = if outgoing {
+        (upstream_id, head_id)
+    } else {
+        (head_id, upstream_id)
+    };
+
+    let (commits, _) = repo.graph_ahead_behind(to, from)?;
+    let direction = if outgoing { "to" } else { "from" };
+    if commits == 0 {
+        return Ok(Some(format!("No commits {} {}", direction, upstream_name)));
+    }
+
+    let base_tree = repo.find_commit(repo.merge_base(from, to)?)?.tree()?;
+    let to_tree = repo.find_commit(to)?.tree()?;
+    let stats = repo
+        .diff_tree_to_tree(Some(&base_tree), Some(&to_tree), None)?
+        .stats()?;
+
+    Ok(Some(format!(
+        "{} {} {} {}, {} {} changed, +{} -{}",
+        format_count(config, commits),
+        if commits == 1 { "commit" } else { "commits" },
+        direction,
+        upstream_name,
+        format_count(config, stats.files_changed()),
+        if stats.files_changed() == 1 {
+            "file"
+        } else {
+            "files"
+        },
+        format_count(config, stats.insertions()),
+        format_count(config, stats.deletions())
+    )))
+}
+
+/// Commits on HEAD that aren't on any branch of `remote` yet, newest first, like `git log HEAD --not --remotes=origin`.
+pub(crate) fn unpushed_commits(repo: &Repository, remote: &str) -> Res<Vec<Oid>> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push_head()?;
+    revwalk.hide_glob(&format!("refs/remotes/{}/*", remote))?;
+    Ok(revwalk.collect::<Result<_, _>>()?)
+}
+
 pub(crate) fn get_push_remote(repo: &Repository) -> Res<Option<String>> {
     let push_remote_cfg = head_push_remote_cfg(repo)?;
     let config = repo.config()?;
@@ -0,0 +1,181 @@
+use crate::Res;
+use git2::{Commit, Oid, Repository, Signature, Time, Tree};
+use std::process::Command;
+
+/// What to change about a commit with `revise`.
+pub(crate) enum Revision {
+    Message(String),
+    /// Squash what's staged into it.
+    AddStaged,
+}
+
+/// Rewrites `target` and the commits on top of it up to HEAD, like `git rebase` would, but in memory.
+/// The branch is moved onto the result, leaving the index and worktree as they are.
+pub(crate) fn revise(repo: &Repository, target: Oid, revision: Revision) -> Res<Oid> {
+    let head = repo.head()?;
+    let head_commit = head.peel_to_commit()?;
+
+    // Newest first, all of them with a single parent to replay them onto
+    let mut descendants = vec![];
+    let mut commit = head_commit.clone();
+    while commit.id() != target {
+        if commit.parent_count() != 1 {
+            return Err(format!(
+                "{} isn't on the current branch, or there's a merge in between",
+                short_id(&repo.find_commit(target)?)?
+            )
+            .into());
+        }
+
+        let parent = commit.parent(0)?;
+        descendants.push(commit);
+        commit = parent;
+    }
+
+    let target_commit = commit;
+    let committer = committer(repo)?;
+
+    // The commits would be made anew without a signature
+    let signed = |commit: &Commit| repo.extract_signature(&commit.id(), None).is_ok();
+    if repo.config()?.get_bool("commit.gpgSign").unwrap_or(false)
+        || signed(&target_commit)
+        || descendants.iter().any(signed)
+    {
+        return Err(
+            "Revising in place can't sign commits, reword or fix up with a rebase instead".into(),
+        );
+    }
+
+    let (tree, message) = match &revision {
+        Revision::Message(message) => (target_commit.tree()?, message.as_str()),
+        Revision::AddStaged => {
+            let staged = repo.find_tree(repo.index()?.write_tree()?)?;
+            if staged.id() == head_commit.tree_id() {
+                return Err("Nothing staged to add".into());
+            }
+
+            let tree = merge_trees(repo, &head_commit.tree()?, &target_commit.tree()?, &staged)
+                .map_err(|_| {
+                    format!(
+                        "The staged changes don't apply cleanly to {}",
+                        short_id(&target_commit).unwrap_or_default()
+                    )
+                })?;
+
+            (tree, target_commit.message_raw().unwrap_or_default())
+        }
+    };
+
+    let parents = target_commit.parents().collect::<Vec<_>>();
+    let mut new_commit = repo.find_commit(repo.commit(
+        None,
+        &target_commit.author(),
+        &committer,
+        message,
+        &tree,
+        &parents.iter().collect::<Vec<_>>(),
+    )?)?;
+
+    for commit in descendants.iter().rev() {
+        let tree = match revision {
+            Revision::Message(_) => commit.tree()?,
+            Revision::AddStaged => merge_trees(
+                repo,
+                &commit.parent(0)?.tree()?,
+                &new_commit.tree()?,
+                &commit.tree()?,
+            )
+            .map_err(|_| {
+                format!(
+                    "{} doesn't apply cleanly once revised",
+                    short_id(commit).unwrap_or_default()
+                )
+            })?,
+        };
+
+        new_commit = repo.find_commit(repo.commit(
+            None,
+            &commit.author(),
+            &committer,
+            commit.message_raw().unwrap_or_default(),
+            &tree,
+            &[&new_commit],
+        )?)?;
+    }
+
+    let log_message = format!("revise: {}", short_id(&target_commit)?);
+    if head.is_branch() {
+        repo.reference_matching(
+            head.name().ok_or("Branch name not utf-8")?,
+            new_commit.id(),
+            true,
+            head_commit.id(),
+            &log_message,
+        )?;
+    } else {
+        repo.set_head_detached(new_commit.id())?;
+    }
+
+    Ok(new_commit.id())
+}
+
+/// `theirs` applied onto `ours`, as a tree. Fails on conflicts.
+fn merge_trees<'a>(
+    repo: &'a Repository,
+    ancestor: &Tree,
+    ours: &Tree,
+    theirs: &Tree,
+) -> Res<Tree<'a>> {
+    let mut index = repo.merge_trees(ancestor, ours, theirs, None)?;
+    if index.has_conflicts() {
+        return Err("Conflicts".into());
+    }
+
+    Ok(repo.find_tree(index.write_tree_to(repo)?)?)
+}
+
+/// Who git itself would commit as, honoring `GIT_COMMITTER_*` like it does.
+fn committer(repo: &Repository) -> Res<Signature<'static>> {
+    let output = Command::new("git")
+        .args(["var", "GIT_COMMITTER_IDENT"])
+        .current_dir(repo.workdir().unwrap_or(repo.path()))
+        .output()?;
+
+    if !output.status.success() {
+        return Err("Couldn't tell who to commit as, configure user.name and user.email".into());
+    }
+
+    // e.g. "Jane Doe <jane@example.com> 1708261200 +0100"
+    let ident = String::from_utf8(output.stdout)?;
+    let mut parts = ident.trim_end().rsplitn(3, ' ');
+    let (Some(offset), Some(seconds), Some(name_email)) =
+        (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(format!("Unexpected committer: {}", ident).into());
+    };
+
+    let (name, email) = name_email
+        .strip_suffix('>')
+        .and_then(|rest| rest.split_once(" <"))
+        .ok_or_else(|| format!("Unexpected committer: {}", ident))?;
+
+    let sign = if offset.starts_with('-') { -1 } else { 1 };
+    let offset = offset.trim_start_matches(['+', '-']);
+    let hours: i32 = offset.get(..2).unwrap_or("0").parse()?;
+    let minutes: i32 = offset.get(2..).unwrap_or("0").parse()?;
+
+    Ok(Signature::new(
+        name,
+        email,
+        &Time::new(seconds.parse()?, sign * (hours * 60 + minutes)),
+    )?)
+}
+
+fn short_id(commit: &Commit) -> Res<String> {
+    Ok(commit
+        .as_object()
+        .short_id()?
+        .as_str()
+        .unwrap_or_default()
+        .to_string())
+}
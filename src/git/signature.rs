@@ -0,0 +1,53 @@
+use crate::Res;
+use std::{collections::HashMap, path::Path, process::Command};
+
+/// Whether a commit is signed, and if the signature checks out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SignatureStatus {
+    Good,
+    Bad,
+    /// Signed, but the key is unknown, expired or revoked, or it couldn't be checked at all.
+    Unknown,
+    Unsigned,
+}
+
+impl SignatureStatus {
+    /// Reads a `%G?` placeholder of `git log`.
+    fn parse(status: &str) -> Self {
+        match status {
+            "G" => Self::Good,
+            "B" => Self::Bad,
+            "N" | "" => Self::Unsigned,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// Verifies the signatures of `hashes`, all with the same `git log` to not start gpg once per commit.
+pub(crate) fn signature_statuses(
+    dir: &Path,
+    hashes: &[String],
+) -> Res<HashMap<String, SignatureStatus>> {
+    if hashes.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let out = Command::new("git")
+        .args(["log", "--no-walk=unsorted", "--format=%H %G?"])
+        .args(hashes)
+        .current_dir(dir)
+        .output()?;
+
+    if !out.status.success() {
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        return Err(stderr.lines().next().unwrap_or("git log failed").into());
+    }
+
+    Ok(String::from_utf8_lossy(&out.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (hash, status) = line.split_once(' ')?;
+            Some((hash.to_string(), SignatureStatus::parse(status)))
+        })
+        .collect())
+}
@@ -0,0 +1,117 @@
+use super::diff::{self, Diff};
+use crate::{config::Config, git2_opts, Res};
+use git2::{DiffFindOptions, Oid, Repository, Status, StatusOptions, Tree};
+use std::collections::BTreeSet;
+
+/// A stash taken apart into what `git stash pop --index` would put back where.
+pub(crate) struct StashParts {
+    pub staged: Diff,
+    pub unstaged: Diff,
+    /// Only there if stashed with `--include-untracked` or `--all`.
+    pub untracked: Option<Diff>,
+}
+
+/// Whether `commit` is one of the stashes, rather than any merge.
+pub(crate) fn is_stash(repo: &Repository, commit: Oid) -> Res<bool> {
+    Ok(repo
+        .reflog("refs/stash")?
+        .iter()
+        .any(|entry| entry.id_new() == commit))
+}
+
+/// The parts of a stash, or None if `reference` isn't one.
+/// Its commit is the worktree, with HEAD, the index, and then the untracked files as parents.
+pub(crate) fn split(
+    config: &Config,
+    repo: &Repository,
+    reference: &str,
+) -> Res<Option<StashParts>> {
+    let stash = repo.revparse_single(reference)?.peel_to_commit()?;
+    if stash.parent_count() < 2 || !is_stash(repo, stash.id())? {
+        return Ok(None);
+    }
+
+    let base_tree = stash.parent(0)?.tree()?;
+    let index_tree = stash.parent(1)?.tree()?;
+    let diff = |old: Option<&Tree>, new: &Tree| -> Res<Diff> {
+        let mut diff = repo.diff_tree_to_tree(old, Some(new), Some(&mut git2_opts::diff(repo)?))?;
+        diff.find_similar(Some(&mut DiffFindOptions::new().renames(true)))?;
+        diff::convert_diff(config, repo, diff, false)
+    };
+
+    Ok(Some(StashParts {
+        staged: diff(Some(&base_tree), &index_tree)?,
+        unstaged: diff(Some(&index_tree), &stash.tree()?)?,
+        untracked: stash
+            .parent(2)
+            .ok()
+            .map(|untracked| diff(None, &untracked.tree()?))
+            .transpose()?,
+    }))
+}
+
+/// What would go wrong when applying a stash onto the current worktree.
+pub(crate) struct StashCheck {
+    /// Files where the stash and HEAD have diverged in a way that can't be merged cleanly.
+    pub conflicts: Vec<String>,
+    /// Files the stash touches that also have local changes, which git refuses to overwrite.
+    pub overwritten: Vec<String>,
+}
+
+impl StashCheck {
+    pub(crate) fn is_clean(&self) -> bool {
+        self.conflicts.is_empty() && self.overwritten.is_empty()
+    }
+}
+
+/// A dry-run of `git stash apply`, without touching the index or worktree.
+pub(crate) fn check_apply(repo: &Repository, stash_rev: &str) -> Res<StashCheck> {
+    let stash = repo.revparse_single(stash_rev)?.peel_to_commit()?;
+    let base_tree = stash.parent(0)?.tree()?;
+    let stash_tree = stash.tree()?;
+    let head_tree = repo.head()?.peel_to_tree()?;
+
+    let merged = repo.merge_trees(&base_tree, &head_tree, &stash_tree, None)?;
+    let conflicts = merged
+        .conflicts()?
+        .filter_map(|conflict| {
+            let conflict = conflict.ok()?;
+            let entry = conflict.their.or(conflict.our).or(conflict.ancestor)?;
+            Some(String::from_utf8_lossy(&entry.path).into_owned())
+        })
+        .collect::<BTreeSet<_>>();
+
+    let mut stashed_paths = changed_paths(repo, Some(&base_tree), &stash_tree)?;
+
+    // Untracked files are stashed in a separate, parentless commit
+    if let Ok(untracked) = stash.parent(2) {
+        stashed_paths.extend(changed_paths(repo, None, &untracked.tree()?)?);
+    }
+
+    let statuses = repo.statuses(Some(
+        StatusOptions::new()
+            .include_untracked(true)
+            .include_ignored(false),
+    ))?;
+
+    let overwritten = statuses
+        .iter()
+        .filter(|entry| entry.status() != Status::CURRENT)
+        .filter_map(|entry| entry.path().map(String::from))
+        .filter(|path| stashed_paths.contains(path) && !conflicts.contains(path))
+        .collect();
+
+    Ok(StashCheck {
+        conflicts: conflicts.into_iter().collect(),
+        overwritten,
+    })
+}
+
+fn changed_paths(repo: &Repository, old: Option<&Tree>, new: &Tree) -> Res<BTreeSet<String>> {
+    Ok(repo
+        .diff_tree_to_tree(old, Some(new), None)?
+        .deltas()
+        .filter_map(|delta| delta.new_file().path().or(delta.old_file().path()))
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect())
+}
@@ -0,0 +1,81 @@
+use crate::Res;
+use git2::{BranchType, Repository, StatusOptions};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// What the workspace screen tells about a repository.
+pub(crate) struct RepoSummary {
+    /// The branch checked out, none if HEAD is detached.
+    pub branch: Option<String>,
+    /// How many commits the branch is ahead and behind its upstream, if it has one.
+    pub ahead_behind: Option<(usize, usize)>,
+    /// Whether there's anything staged, unstaged or untracked.
+    pub dirty: bool,
+}
+
+/// The repositories among `dirs` and directly within them, sorted by path.
+pub(crate) fn find_repos(dirs: &[PathBuf]) -> Vec<PathBuf> {
+    let mut repos = dirs
+        .iter()
+        .flat_map(|dir| {
+            if is_repo(dir) {
+                return vec![dir.clone()];
+            }
+
+            fs::read_dir(dir)
+                .into_iter()
+                .flatten()
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .filter(|path| path.is_dir() && is_repo(path))
+                .collect()
+        })
+        .collect::<Vec<_>>();
+
+    repos.sort();
+    repos.dedup();
+    repos
+}
+
+fn is_repo(dir: &Path) -> bool {
+    dir.join(".git").exists() || Repository::open_bare(dir).is_ok()
+}
+
+pub(crate) fn summarize(path: &Path) -> Res<RepoSummary> {
+    let repo = Repository::open(path)?;
+    let head = repo.head().ok();
+    let branch = head
+        .as_ref()
+        .filter(|head| head.is_branch())
+        .and_then(|head| head.shorthand())
+        .map(str::to_string);
+
+    let ahead_behind = branch
+        .as_deref()
+        .and_then(|name| repo.find_branch(name, BranchType::Local).ok())
+        .and_then(|local| {
+            Some((
+                local.get().target()?,
+                local.upstream().ok()?.get().target()?,
+            ))
+        })
+        .map(|(local, upstream)| repo.graph_ahead_behind(local, upstream))
+        .transpose()?;
+
+    let dirty = !repo.is_bare()
+        && !repo
+            .statuses(Some(
+                StatusOptions::new()
+                    .include_untracked(true)
+                    .include_ignored(false),
+            ))?
+            .is_empty();
+
+    Ok(RepoSummary {
+        branch,
+        ahead_behind,
+        dirty,
+    })
+}
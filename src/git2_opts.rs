@@ -17,5 +17,6 @@ pub(crate) fn status(repo: &Repository) -> Res<StatusOptions> {
 pub(crate) fn diff(_repo: &Repository) -> Res<DiffOptions> {
     let mut diff_options = DiffOptions::new();
     diff_options.patience(true);
+    diff_options.include_typechange(true);
     Ok(diff_options)
 }
@@ -0,0 +1,91 @@
+use crate::{term::Term, Res};
+use crossterm::event::{Event, KeyCode, KeyEventKind};
+use ratatui::{
+    style::{Style, Stylize},
+    text::Line,
+    widgets::Paragraph,
+};
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// Runs `git init` in `dir`, which is created if need be.
+pub(crate) fn init(dir: &Path) -> Res<()> {
+    let output = Command::new("git").arg("init").arg(dir).output()?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().into());
+    }
+
+    Ok(())
+}
+
+/// What to do when launched outside of a repository.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Choice {
+    Init,
+    Open(PathBuf),
+    Quit,
+}
+
+/// Asks whether to initialize a repository in `dir`, or to open one of the `recent` ones instead.
+pub(crate) fn choose(
+    term: &mut Term,
+    dir: &Path,
+    recent: &[PathBuf],
+    mut next_event: impl FnMut() -> Res<Event>,
+) -> Res<Choice> {
+    let mut selected = 0;
+
+    loop {
+        draw(term, dir, recent, selected)?;
+
+        let Event::Key(key) = next_event()? else {
+            continue;
+        };
+
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        match key.code {
+            KeyCode::Char('i') => return Ok(Choice::Init),
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(Choice::Quit),
+            KeyCode::Char('j') | KeyCode::Down => {
+                selected = (selected + 1).min(recent.len().saturating_sub(1))
+            }
+            KeyCode::Char('k') | KeyCode::Up => selected = selected.saturating_sub(1),
+            KeyCode::Enter => {
+                if let Some(repo) = recent.get(selected) {
+                    return Ok(Choice::Open(repo.clone()));
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+fn draw(term: &mut Term, dir: &Path, recent: &[PathBuf], selected: usize) -> Res<()> {
+    let mut lines = vec![
+        Line::raw(format!("No git repository in {}", dir.display())).bold(),
+        Line::raw(""),
+        Line::raw("i Initialize one here"),
+        Line::raw("q Quit"),
+    ];
+
+    if !recent.is_empty() {
+        lines.push(Line::raw(""));
+        lines.push(Line::raw("Or open a recent one (j/k to select, enter to open):").bold());
+        lines.extend(recent.iter().enumerate().map(|(i, repo)| {
+            let line = Line::raw(format!("  {}", repo.display()));
+            if i == selected {
+                line.style(Style::new().reversed())
+            } else {
+                line
+            }
+        }));
+    }
+
+    term.draw(|frame| frame.render_widget(Paragraph::new(lines), frame.area()))?;
+    Ok(())
+}
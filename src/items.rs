@@ -1,17 +1,27 @@
 use crate::config::Config;
+use crate::git::diff::BinarySide;
 use crate::git::diff::Delta;
 use crate::git::diff::Diff;
 use crate::git::diff::Hunk;
+use crate::git::diff::HunkDefinitions;
+use crate::git::diff::ImageInfo;
 use crate::Res;
 use git2::Commit;
+use git2::FileMode;
 use git2::Oid;
 use git2::Repository;
+use git2::Revwalk;
+use git2::Sort;
 use ratatui::style::Style;
 use ratatui::text::Line;
 use ratatui::text::Span;
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
+use std::collections::HashSet;
 use std::iter;
+use std::path::Component;
+use std::path::Path;
 use std::path::PathBuf;
 use std::rc::Rc;
 
@@ -24,6 +34,8 @@ pub(crate) struct Item {
     pub(crate) depth: usize,
     pub(crate) unselectable: bool,
     pub(crate) target_data: Option<TargetData>,
+    /// Shown after `display`, but only looked up once the item is in view.
+    pub(crate) definitions: Option<HunkDefinitions>,
 }
 
 #[derive(Clone, Debug)]
@@ -35,11 +47,67 @@ pub(crate) enum TargetData {
     Commit(String),
     Delta(Delta),
     File(PathBuf),
+    /// A setting on the git config screen, by its key.
+    GitConfig(String),
     Hunk(Rc<Hunk>),
+    Remote(String),
+    /// A repository on the workspace screen, by its path.
+    Repo(PathBuf),
+    Tag(String),
+    /// A file with merge conflicts.
+    Unmerged(PathBuf),
+    /// A file's line in a diffstat, leading to its diff.
+    FileStat(PathBuf),
     HunkLine(Rc<Hunk>, usize),
-    Stash { commit: String, id: usize },
+    ModeChange {
+        file: PathBuf,
+        old_mode: FileMode,
+        new_mode: FileMode,
+    },
+    Stash {
+        commit: String,
+        id: usize,
+    },
 }
 
+/// Formats a path relative to the worktree root for display,
+/// making it relative to the launch directory if `relative_paths` is enabled.
+pub(crate) fn display_path(config: &Config, path: &Path) -> String {
+    if !config.general.relative_paths.enabled {
+        return path.to_string_lossy().to_string();
+    }
+
+    let relative = relative_path(&config.launch_dir, path);
+    let mut display = match relative.to_string_lossy() {
+        display if display.is_empty() => ".".to_string(),
+        display => display.to_string(),
+    };
+
+    // Untracked directories are listed with a trailing slash
+    if path.to_string_lossy().ends_with('/') {
+        display.push('/');
+    }
+
+    display
+}
+
+/// `path` relative to `base`, both relative to the same root.
+pub(crate) fn relative_path(base: &Path, path: &Path) -> PathBuf {
+    let common = base
+        .components()
+        .zip(path.components())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    base.components()
+        .skip(common)
+        .map(|_| Component::ParentDir)
+        .chain(path.components().skip(common))
+        .collect()
+}
+
+/// Items of each file and its hunks. Their ids are paths like `diff/modified/src/main.rs/@@ -1,2 +1,3 @@`,
+/// which stay the same as long as the hunk is there, also when it's staged or unstaged.
 pub(crate) fn create_diff_items<'a>(
     config: Rc<Config>,
     diff: &'a Diff,
@@ -49,20 +117,34 @@ pub(crate) fn create_diff_items<'a>(
     diff.deltas.iter().flat_map(move |delta| {
         let target_data = TargetData::Delta(delta.clone());
         let config = Rc::clone(&config);
+        let is_type_change = delta.status == git2::Delta::Typechange;
+        let file_id = format!(
+            "diff/{}/{}",
+            format!("{:?}", delta.status).to_lowercase(),
+            delta.new_file.to_string_lossy()
+        );
 
         iter::once(Item {
-            id: delta.file_header.to_string().into(),
+            id: file_id.clone().into(),
             display: Line::styled(
                 format!(
                     "{:8}   {}",
                     format!("{:?}", delta.status).to_lowercase(),
-                    match delta.status {
-                        git2::Delta::Renamed => format!(
+                    match (delta.status, delta.type_change()) {
+                        (git2::Delta::Renamed, _) => format!(
                             "{} -> {}",
-                            delta.old_file.to_string_lossy(),
-                            delta.new_file.to_string_lossy()
+                            display_path(&config, &delta.old_file),
+                            display_path(&config, &delta.new_file)
+                        ),
+                        (_, Some(type_change)) => format!(
+                            "{} ({})",
+                            display_path(&config, &delta.new_file),
+                            type_change
                         ),
-                        _ => delta.new_file.to_string_lossy().to_string(),
+                        _ if delta.new_mode == git2::FileMode::Link => {
+                            format!("{} (symlink)", display_path(&config, &delta.new_file))
+                        }
+                        _ => display_path(&config, &delta.new_file),
                     }
                 ),
                 &config.style.file_header,
@@ -73,29 +155,148 @@ pub(crate) fn create_diff_items<'a>(
             target_data: Some(target_data),
             ..Default::default()
         })
+        .chain(create_mode_change_item(
+            &config,
+            &file_id,
+            delta,
+            *depth + 1,
+        ))
+        .chain(create_binary_item(&config, &file_id, delta, *depth + 1))
+        .chain(create_warning_item(&file_id, delta, *depth + 1))
         .chain(
             delta
                 .hunks
                 .iter()
                 .cloned()
-                .flat_map(move |hunk| create_hunk_items(Rc::clone(&config), hunk, *depth + 1)),
+                .flat_map(move |hunk| {
+                    create_hunk_items(Rc::clone(&config), &file_id, hunk, *depth + 1)
+                })
+                .map(move |item| {
+                    // A patch can't change the type of a file, so only the whole file can be staged
+                    if is_type_change {
+                        Item {
+                            target_data: None,
+                            unselectable: !item.section,
+                            ..item
+                        }
+                    } else {
+                        item
+                    }
+                }),
         )
     })
 }
 
+fn create_mode_change_item(
+    config: &Config,
+    file_id: &str,
+    delta: &Delta,
+    depth: usize,
+) -> Option<Item> {
+    if !delta.has_mode_change() {
+        return None;
+    }
+
+    Some(Item {
+        id: format!("{}/mode_change", file_id).into(),
+        display: Line::styled(
+            format!(
+                "mode change {:o} -> {:o}",
+                u32::from(delta.old_mode),
+                u32::from(delta.new_mode)
+            ),
+            &config.style.hunk_header,
+        ),
+        depth,
+        target_data: Some(TargetData::ModeChange {
+            file: delta.new_file.clone(),
+            old_mode: delta.old_mode,
+            new_mode: delta.new_mode,
+        }),
+        ..Default::default()
+    })
+}
+
+/// Instead of hunks, binary files show what little can be told about them, like their size.
+fn create_binary_item(config: &Config, file_id: &str, delta: &Delta, depth: usize) -> Option<Item> {
+    let binary = delta.binary.as_ref()?;
+    let side = |side: &Option<BinarySide>| match side {
+        None => "/dev/null".to_string(),
+        Some(side) => {
+            let image = match &side.image {
+                Some(ImageInfo {
+                    format,
+                    dimensions: Some((width, height)),
+                }) => format!(" ({format} {width}×{height})"),
+                Some(ImageInfo { format, .. }) => format!(" ({format})"),
+                None => "".to_string(),
+            };
+
+            format!(
+                "{:o} {}{}",
+                u32::from(side.mode),
+                format_size(side.size),
+                image
+            )
+        }
+    };
+
+    Some(Item {
+        id: format!("{}/binary", file_id).into(),
+        display: Line::styled(
+            format!("binary {} -> {}", side(&binary.old), side(&binary.new)),
+            &config.style.hunk_header,
+        ),
+        depth,
+        unselectable: true,
+        ..Default::default()
+    })
+}
+
+fn create_warning_item(file_id: &str, delta: &Delta, depth: usize) -> Option<Item> {
+    let warning = delta.warning.as_ref()?;
+
+    Some(Item {
+        id: format!("{}/warning", file_id).into(),
+        display: Line::raw(format!("! Couldn't diff this file: {}", warning)),
+        depth,
+        unselectable: true,
+        ..Default::default()
+    })
+}
+
+fn format_size(bytes: usize) -> String {
+    const UNITS: [&str; 3] = ["KiB", "MiB", "GiB"];
+
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{size:.1} {}", UNITS[unit])
+}
+
 fn create_hunk_items(
     config: Rc<Config>,
+    file_id: &str,
     hunk: Rc<Hunk>,
     depth: usize,
 ) -> impl Iterator<Item = Item> {
     let target_data = TargetData::Hunk(Rc::clone(&hunk));
 
     iter::once(Item {
-        id: hunk.format_patch().into(),
+        id: format!("{}/{}", file_id, hunk.header).into(),
         display: Line::styled(hunk.header.clone(), &config.style.hunk_header),
         section: true,
         depth,
         target_data: Some(target_data),
+        definitions: Some(hunk.definitions.clone()),
         ..Default::default()
     })
     .chain(format_diff_hunk_items(depth + 1, hunk))
@@ -108,10 +309,11 @@ fn format_diff_hunk_items(depth: usize, hunk: Rc<Hunk>) -> Vec<Item> {
         .enumerate()
         .map(|(i, line)| Item {
             display: replace_tabs_with_spaces(line.clone()),
+            // Combined diffs of merges have a column per parent, e.g. `  ` or ` +`
             unselectable: line
                 .spans
                 .first()
-                .is_some_and(|s| s.content.starts_with(' ')),
+                .is_some_and(|s| s.content.chars().all(|c| c == ' ')),
             depth,
             target_data: Some(TargetData::HunkLine(Rc::clone(&hunk), i)),
             ..Default::default()
@@ -119,11 +321,72 @@ fn format_diff_hunk_items(depth: usize, hunk: Rc<Hunk>) -> Vec<Item> {
         .collect()
 }
 
+/// Folds runs of more than `max_lines` unchanged lines within hunks into a collapsed section,
+/// so that the changes themselves dominate the screen.
+pub(crate) fn fold_unchanged_lines(
+    config: &Config,
+    items: impl IntoIterator<Item = Item>,
+    max_lines: usize,
+) -> Vec<Item> {
+    let is_unchanged = |item: &Item| {
+        item.unselectable && matches!(item.target_data, Some(TargetData::HunkLine(..)))
+    };
+
+    let mut folded = vec![];
+    let mut run: Vec<Item> = vec![];
+
+    let flush_run = |run: &mut Vec<Item>, folded: &mut Vec<Item>| {
+        if max_lines == 0 || run.len() <= max_lines {
+            folded.append(run);
+            return;
+        }
+
+        let Some(TargetData::HunkLine(_, first_line)) = &run[0].target_data else {
+            unreachable!();
+        };
+
+        let hunk_id = folded
+            .iter()
+            .rev()
+            .find(|item| matches!(item.target_data, Some(TargetData::Hunk(_))))
+            .map(|hunk| hunk.id.as_ref())
+            .unwrap_or_default();
+
+        folded.push(Item {
+            id: format!("{}/unchanged_{}", hunk_id, first_line).into(),
+            display: Line::styled(
+                format!(" {} unchanged lines", run.len()),
+                &config.style.diff_highlight.unchanged_new,
+            ),
+            section: true,
+            default_collapsed: true,
+            depth: run[0].depth,
+            ..Default::default()
+        });
+
+        folded.extend(run.drain(..).map(|item| Item {
+            depth: item.depth + 1,
+            ..item
+        }));
+    };
+
+    for item in items {
+        if is_unchanged(&item) {
+            run.push(item);
+        } else {
+            flush_run(&mut run, &mut folded);
+            folded.push(item);
+        }
+    }
+
+    flush_run(&mut run, &mut folded);
+    folded
+}
+
 fn replace_tabs_with_spaces(line: Line<'_>) -> Line<'_> {
     let spans = line
         .spans
         .iter()
-        .cloned()
         .map(|span| Span::styled(span.content.replace('\t', "    "), span.style))
         .collect::<Vec<_>>();
 
@@ -171,20 +434,76 @@ pub(crate) fn stash_list(config: &Config, repo: &Repository, limit: usize) -> Re
         .collect::<Vec<_>>())
 }
 
+/// Which commits the log walks through, like the flags of `git log` by the same names.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct LogWalk {
+    /// Those of every branch, remote and tag rather than only `rev`.
+    pub all: bool,
+    pub first_parent: bool,
+    /// Only those a branch, remote or tag points at, for an overview of them.
+    pub simplify_by_decoration: bool,
+}
+
+/// The commits of `rev` (HEAD by default) not in `since`, `None` if there's no HEAD yet.
+pub(crate) fn log_revwalk(
+    repo: &Repository,
+    rev: Option<Oid>,
+    since: Option<Oid>,
+    walk: LogWalk,
+) -> Res<Option<Revwalk<'_>>> {
+    let mut revwalk = repo.revwalk()?;
+    if let Some(r) = rev {
+        revwalk.push(r)?;
+    } else if revwalk.push_head().is_err() && !walk.all {
+        return Ok(None);
+    }
+
+    if walk.all {
+        revwalk.push_glob("*")?;
+        // Newest first, rather than one branch after the other
+        revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+    }
+
+    if walk.first_parent {
+        revwalk.simplify_first_parent()?;
+    }
+
+    if let Some(since) = since {
+        revwalk.hide(since)?;
+    }
+
+    Ok(Some(revwalk))
+}
+
+/// Whether a reference is shown next to the commit it points at, by its short name.
+fn is_decoration(name: &str) -> bool {
+    !name.ends_with("/HEAD") && !name.starts_with("prefetch/remotes/")
+}
+
+/// The commits branches, remotes and tags point at, as shown in the log.
+pub(crate) fn decorated_commits(repo: &Repository) -> Res<HashSet<Oid>> {
+    Ok(repo
+        .references()?
+        .filter_map(Result::ok)
+        .filter(|reference| reference.shorthand().is_some_and(is_decoration))
+        .filter_map(|reference| reference.peel_to_commit().ok())
+        .map(|commit| commit.id())
+        .collect())
+}
+
 pub(crate) fn log(
     config: &Config,
     repo: &Repository,
     limit: usize,
     rev: Option<Oid>,
+    since: Option<Oid>,
     msg_regex: Option<Regex>,
+    walk: LogWalk,
 ) -> Res<Vec<Item>> {
     let style = &config.style;
-    let mut revwalk = repo.revwalk()?;
-    if let Some(r) = rev {
-        revwalk.push(r)?;
-    } else if revwalk.push_head().is_err() {
+    let Some(revwalk) = log_revwalk(repo, rev, since, walk)? else {
         return Ok(vec![]);
-    }
+    };
 
     let references = repo
         .references()?
@@ -192,7 +511,7 @@ pub(crate) fn log(
         .filter_map(
             |reference| match (reference.peel_to_commit(), reference.shorthand()) {
                 (Ok(target), Some(name)) => {
-                    if name.ends_with("/HEAD") || name.starts_with("prefetch/remotes/") {
+                    if !is_decoration(name) {
                         return None;
                     }
 
@@ -237,6 +556,12 @@ pub(crate) fn log(
                 }
             }
 
+            if walk.simplify_by_decoration
+                && !references.iter().any(|(commit, _)| commit.id() == oid)
+            {
+                return Ok(None);
+            }
+
             Ok(Some(Item {
                 id: oid.to_string().into(),
                 display: Line::from(spans),
@@ -0,0 +1,233 @@
+use crate::{cli::Commands, config::Config, git, git2_opts, screen, Res};
+use git2::{Delta, Repository, Statuses};
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// The diff of a `gitu show --json <rev>` or `gitu diff --json`, for scripts and editor plugins.
+pub(crate) fn diff(config: &Config, repo: &Repository, command: &Commands) -> Res<String> {
+    let diff = match command {
+        Commands::Show { reference, .. } if screen::show::is_range(reference) => {
+            git::diff_range(config, repo, reference)?
+        }
+        Commands::Show { reference, .. } => git::show(config, repo, reference)?,
+        Commands::Diff { staged: true, .. } => git::diff_staged(config, repo, None)?,
+        Commands::Diff { staged: false, .. } => git::diff_unstaged(config, repo, None)?,
+        _ => return Err("Only diffs can be printed as JSON".into()),
+    };
+
+    to_json(&diff)
+}
+
+/// What the status screen shows, printed by `gitu status --porcelain-json`.
+#[derive(Serialize)]
+struct Status {
+    branch: BranchStatus,
+    untracked: Vec<PathBuf>,
+    unmerged: Vec<PathBuf>,
+    unstaged: Vec<File>,
+    staged: Vec<File>,
+    stashes: Vec<Stash>,
+    recent_commits: Vec<Commit>,
+}
+
+#[derive(Serialize)]
+struct BranchStatus {
+    /// None when detached, or before the first commit.
+    name: Option<String>,
+    detached: bool,
+    upstream: Option<String>,
+    /// Commits not on the upstream, None without one.
+    ahead: Option<usize>,
+    behind: Option<usize>,
+}
+
+#[derive(Serialize)]
+struct File {
+    status: String,
+    path: PathBuf,
+    /// Where a renamed file was before.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    old_path: Option<PathBuf>,
+}
+
+#[derive(Serialize)]
+struct Stash {
+    index: usize,
+    hash: String,
+    message: String,
+}
+
+#[derive(Serialize)]
+struct Commit {
+    hash: String,
+    summary: String,
+}
+
+/// Read from `git status`-like statuses rather than diffs, as only the files are printed.
+pub(crate) fn status(config: &Config, repo: &Repository) -> Res<String> {
+    let statuses = repo.statuses(Some(
+        git2_opts::status(repo)?
+            .recurse_untracked_dirs(true)
+            .renames_head_to_index(true),
+    ))?;
+    let paths = |filter: fn(&git2::Status) -> bool| {
+        statuses
+            .iter()
+            .filter(|status| filter(&status.status()))
+            .filter_map(|status| status.path().map(PathBuf::from))
+            .collect()
+    };
+
+    let general = &config.general;
+    let status = Status {
+        branch: branch_status(repo)?,
+        untracked: paths(git2::Status::is_wt_new),
+        unmerged: paths(git2::Status::is_conflicted),
+        unstaged: files(&statuses, false),
+        staged: files(&statuses, true),
+        stashes: stashes(repo, general.stash_list_limit)?,
+        recent_commits: recent_commits(repo, general.recent_commits_limit)?,
+    };
+
+    to_json(&status)
+}
+
+/// The files changed in the index if `staged`, otherwise in the worktree, untracked ones aside.
+fn files(statuses: &Statuses, staged: bool) -> Vec<File> {
+    statuses
+        .iter()
+        .filter_map(|entry| {
+            let delta = if staged {
+                entry.head_to_index()?
+            } else {
+                entry.index_to_workdir()?
+            };
+
+            if matches!(delta.status(), Delta::Untracked | Delta::Ignored) {
+                return None;
+            }
+
+            let path = delta.new_file().path().map(PathBuf::from)?;
+            let old_path = delta.old_file().path().map(PathBuf::from);
+            Some(File {
+                status: format!("{:?}", delta.status()).to_lowercase(),
+                old_path: old_path.filter(|old_path| *old_path != path),
+                path,
+            })
+        })
+        .collect()
+}
+
+fn branch_status(repo: &Repository) -> Res<BranchStatus> {
+    let detached = repo.head_detached().unwrap_or(false);
+    let head = repo.head().ok().filter(|_| !detached);
+    let name = head
+        .as_ref()
+        .and_then(|head| head.shorthand())
+        .map(String::from);
+
+    let upstream = head.as_ref().and_then(|head| {
+        let upstream_name = repo.branch_upstream_name(head.name()?).ok()?;
+        let upstream_name = upstream_name.as_str()?.to_string();
+        let upstream_id = repo.refname_to_id(&upstream_name).ok();
+        Some((upstream_name, upstream_id))
+    });
+
+    let (ahead, behind) = match (&head, &upstream) {
+        (Some(head), Some((_, Some(upstream_id)))) => {
+            let (ahead, behind) = repo.graph_ahead_behind(head.target().unwrap(), *upstream_id)?;
+            (Some(ahead), Some(behind))
+        }
+        _ => (None, None),
+    };
+
+    Ok(BranchStatus {
+        name,
+        detached,
+        upstream: upstream.map(|(upstream_name, _)| {
+            upstream_name
+                .strip_prefix("refs/remotes/")
+                .unwrap_or(&upstream_name)
+                .to_string()
+        }),
+        ahead,
+        behind,
+    })
+}
+
+fn stashes(repo: &Repository, limit: usize) -> Res<Vec<Stash>> {
+    let Ok(reflog) = repo.reflog("refs/stash") else {
+        return Ok(vec![]);
+    };
+
+    Ok(reflog
+        .iter()
+        .take(limit)
+        .enumerate()
+        .map(|(index, stash)| Stash {
+            index,
+            hash: stash.id_new().to_string(),
+            message: stash.message().unwrap_or("").to_string(),
+        })
+        .collect())
+}
+
+fn recent_commits(repo: &Repository, limit: usize) -> Res<Vec<Commit>> {
+    let mut revwalk = repo.revwalk()?;
+    if revwalk.push_head().is_err() {
+        return Ok(vec![]);
+    }
+
+    revwalk
+        .take(limit)
+        .map(|oid| {
+            let commit = repo.find_commit(oid?)?;
+            Ok(Commit {
+                hash: commit.id().to_string(),
+                summary: commit.summary().unwrap_or("").to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(feature = "json")]
+fn to_json<T: Serialize>(value: &T) -> Res<String> {
+    Ok(serde_json::to_string_pretty(value)?)
+}
+
+#[cfg(not(feature = "json"))]
+fn to_json<T>(_value: &T) -> Res<String> {
+    Err("Gitu was built without the json feature".into())
+}
+
+/// How the parts of a diff that aren't plain data are serialized.
+#[cfg(feature = "json")]
+pub(crate) mod ser {
+    use crate::git::diff::HunkDefinitions;
+    use ratatui::text::Text;
+    use serde::Serializer;
+
+    pub(crate) fn status<S: Serializer>(status: &git2::Delta, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&format!("{:?}", status).to_lowercase())
+    }
+
+    pub(crate) fn mode<S: Serializer>(mode: &git2::FileMode, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&format!("{:o}", u32::from(*mode)))
+    }
+
+    pub(crate) fn oid<S: Serializer>(oid: &git2::Oid, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&oid.to_string())
+    }
+
+    pub(crate) fn definitions<S: Serializer>(
+        definitions: &HunkDefinitions,
+        s: S,
+    ) -> Result<S::Ok, S::Error> {
+        s.collect_seq(definitions.get())
+    }
+
+    /// Just the text of each line, without styling.
+    pub(crate) fn lines<S: Serializer>(text: &Text, s: S) -> Result<S::Ok, S::Error> {
+        s.collect_seq(text.lines.iter().map(|line| line.to_string()))
+    }
+}
@@ -2,19 +2,41 @@ use crossterm::event::{KeyCode, KeyModifiers};
 use nom::{
     branch::alt,
     bytes::complete::tag,
-    character::complete::{anychar, char, none_of},
+    character::complete::{anychar, char, none_of, u64},
     combinator::{all_consuming, map, opt, value},
     multi::{many0, separated_list0},
     sequence::{delimited, preceded},
     IResult,
 };
+use std::time::Duration;
 
 // TODO Improve error messages
 
+/// A step of the keys sent with `--keys`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ScriptStep {
+    Key(KeyModifiers, KeyCode),
+    /// `<wait-cmd>`: until the command that's running is done, like a push.
+    WaitCmd,
+    /// `<sleep:ms>`
+    Sleep(Duration),
+}
+
 pub(crate) fn parse_keys(input: &str) -> IResult<&str, Vec<(KeyModifiers, KeyCode)>> {
     all_consuming(many0(parse_key))(input)
 }
 
+/// Like `parse_keys`, but with `<wait-cmd>` and `<sleep:ms>` in between to wait for things to happen.
+pub(crate) fn parse_script(input: &str) -> IResult<&str, Vec<ScriptStep>> {
+    all_consuming(many0(alt((
+        value(ScriptStep::WaitCmd, tag("<wait-cmd>")),
+        map(delimited(tag("<sleep:"), u64, char('>')), |ms| {
+            ScriptStep::Sleep(Duration::from_millis(ms))
+        }),
+        map(parse_key, |(mods, key)| ScriptStep::Key(mods, key)),
+    ))))(input)
+}
+
 fn parse_key(input: &str) -> IResult<&str, (KeyModifiers, KeyCode)> {
     alt((parse_quoted, parse_char_key))(input)
 }
@@ -133,6 +155,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn script() {
+        assert_eq!(
+            parse_script("P<wait-cmd><sleep:250>q"),
+            Ok((
+                "",
+                vec![
+                    ScriptStep::Key(KeyModifiers::SHIFT, Char('P')),
+                    ScriptStep::WaitCmd,
+                    ScriptStep::Sleep(Duration::from_millis(250)),
+                    ScriptStep::Key(KeyModifiers::NONE, Char('q')),
+                ]
+            ))
+        );
+    }
+
     #[test]
     fn multiple() {
         assert_eq!(
@@ -1,15 +1,28 @@
 mod bindings;
 pub mod cli;
+mod clone;
 mod cmd_log;
+mod commit_lint;
 pub mod config;
+mod external_diff;
+mod file_watcher;
 mod git;
 mod git2_opts;
+mod init;
 mod items;
+mod json;
 mod key_parser;
+mod locale;
 mod menu;
+mod op_log;
 mod ops;
+mod picker;
 mod prompt;
+mod recent_repos;
+mod review;
 mod screen;
+#[cfg(feature = "session")]
+mod session;
 pub mod state;
 mod syntax_highlight;
 pub mod term;
@@ -17,11 +30,19 @@ pub mod term;
 mod tests;
 mod ui;
 
-use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventState, KeyModifiers};
+use crossterm::event::{self, Event, KeyEvent, KeyEventState};
 use git2::Repository;
 use items::Item;
+use key_parser::ScriptStep;
 use ops::Action;
-use std::{error::Error, path::PathBuf, process::Command, rc::Rc, time::Duration};
+use std::{
+    error::Error,
+    path::{Path, PathBuf},
+    process::Command,
+    rc::Rc,
+    thread,
+    time::{Duration, Instant},
+};
 use term::Term;
 
 //                                An overview of Gitu's ui and terminology:
@@ -64,26 +85,92 @@ use term::Term;
 pub type Res<T> = Result<T, Box<dyn Error>>;
 
 pub fn run(args: &cli::Args, term: &mut Term) -> Res<()> {
-    log::debug!("Finding git dir");
-    let dir = PathBuf::from(
-        String::from_utf8(
-            Command::new("git")
-                .args(["rev-parse", "--show-toplevel"])
-                .output()?
-                .stdout,
-        )?
-        .trim_end(),
-    );
+    let opened = match &args.command {
+        Some(cli::Commands::Clone { url, dir }) => {
+            let poll_event = || Ok(event::poll(Duration::ZERO)?.then(event::read).transpose()?);
+            Some(open_at(&clone::clone(
+                term,
+                url,
+                dir.as_deref(),
+                poll_event,
+            )?)?)
+        }
+        Some(cli::Commands::Init { dir }) => {
+            let dir = dir.clone().unwrap_or_else(|| PathBuf::from("."));
+            init::init(&dir)?;
+            Some(open_at(&dir)?)
+        }
+        _ => match open_from_args(args) {
+            // One is opened once picked on the workspace screen
+            Err(_) if args.workspace.is_some() => None,
+            Err(_) if args.repo.is_none() && !args.print && outside_of_repo() => {
+                match choose_when_outside_of_repo(term)? {
+                    Some(opened) => Some(opened),
+                    None => return Ok(()),
+                }
+            }
+            opened => Some(opened?),
+        },
+    };
 
-    log::debug!("Opening repo");
-    let repo = open_repo_from_env()?;
-    repo.set_workdir(&dir, false)?;
+    let (repo, mut config) = match opened {
+        Some((repo, config)) => (Some(Rc::new(repo)), config),
+        None => (None, config::init_config(&std::env::current_dir()?)?),
+    };
 
-    log::debug!("Initializing config");
-    let config = config::init_config()?;
+    if !args.print {
+        if let Some(sort) = screen::show_refs::load_sort() {
+            config.general.refs_sort = sort;
+        }
+    }
+
+    if config.general.mouse_support.enabled && !args.print {
+        term::enable_mouse_capture()?;
+    }
 
     log::debug!("Creating initial state");
-    let mut state = state::State::create(Rc::new(repo), term.size()?, args, Rc::new(config), true)?;
+    let mut state = state::State::create(repo, term.size()?, args, Rc::new(config), true)?;
+
+    #[cfg(feature = "session")]
+    let restore_session = state.config.general.restore_session.enabled
+        && !args.print
+        && args.command.is_none()
+        && args.screen.is_none()
+        && args.workspace.is_none();
+
+    #[cfg(feature = "session")]
+    let persist_session =
+        !args.print && (restore_session || state.config.general.persist_menu_args.enabled);
+
+    #[cfg(feature = "session")]
+    if persist_session {
+        log::debug!("Restoring session");
+        session::restore(&mut state, term.size()?, restore_session)
+            .unwrap_or_else(|error| log::warn!("Couldn't restore the session: {}", error));
+    }
+
+    // Printing the screen doesn't count as seeing the changelog
+    if !args.print {
+        let new_version = screen::changelog::mark_version_seen().unwrap_or_else(|error| {
+            log::warn!("Couldn't record the seen version: {}", error);
+            false
+        });
+
+        if new_version {
+            log::debug!("New version, showing changelog");
+            state.screens.push(screen::changelog::create(
+                Rc::clone(&state.config),
+                term.size()?,
+            )?);
+        }
+    }
+
+    if !args.print {
+        state.recent_repos = recent_repos::load();
+        if let Some(repo) = &state.repo {
+            recent_repos::record(&mut state.recent_repos, repo);
+        }
+    }
 
     log::debug!("Initial update");
     state.update(term, &[Event::FocusGained])?;
@@ -92,11 +179,22 @@ pub fn run(args: &cli::Args, term: &mut Term) -> Res<()> {
         return Ok(());
     }
 
+    if let Some(repo) = state
+        .repo
+        .as_ref()
+        .filter(|_| state.config.general.refresh_on_file_change.enabled)
+    {
+        state.file_watcher = file_watcher::FileWatcher::new(repo)
+            .inspect_err(|e| log::warn!("Couldn't watch for file changes: {}", e))
+            .ok();
+    }
+
     if let Some(keys_string) = &args.keys {
-        let ("", keys) = key_parser::parse_keys(keys_string).expect("Couldn't parse keys") else {
+        let ("", steps) = key_parser::parse_script(keys_string).expect("Couldn't parse keys")
+        else {
             panic!("Couldn't parse keys");
         };
-        handle_initial_send_keys(&keys, &mut state, term)?;
+        handle_initial_send_keys(&steps, &mut state, term)?;
     }
 
     while !state.quit {
@@ -109,9 +207,126 @@ pub fn run(args: &cli::Args, term: &mut Term) -> Res<()> {
         state.update(term, &events)?;
     }
 
+    #[cfg(feature = "session")]
+    if persist_session {
+        session::save(&state)
+            .unwrap_or_else(|error| log::warn!("Couldn't save the session: {}", error));
+    }
+
+    if state.refs_sort.get() != state.config.general.refs_sort {
+        screen::show_refs::save_sort(state.refs_sort.get())
+            .unwrap_or_else(|error| log::warn!("Couldn't save the refs sort order: {}", error));
+    }
+
+    recent_repos::save(&state.recent_repos)
+        .unwrap_or_else(|error| log::warn!("Couldn't save the recent repositories: {}", error));
+
+    Ok(())
+}
+
+/// Prints the diff asked for with `--json`, or the status with `--porcelain-json`, rather than running the ui.
+pub fn print_json(args: &cli::Args) -> Res<()> {
+    let (repo, config) = open_from_args(args)?;
+    let Some(command) = &args.command else {
+        return Ok(());
+    };
+
+    match command {
+        cli::Commands::Status { .. } => println!("{}", json::status(&config, &repo)?),
+        _ => println!("{}", json::diff(&config, &repo, command)?),
+    }
+
     Ok(())
 }
 
+fn open_from_args(args: &cli::Args) -> Res<(Repository, config::Config)> {
+    match &args.repo {
+        Some(path) => open_at(path),
+        None => open(),
+    }
+}
+
+/// Like `open`, but for the repository at `path` rather than the one in the current directory.
+pub(crate) fn open_at(path: &Path) -> Res<(Repository, config::Config)> {
+    log::debug!("Opening repo at {:?}", path);
+    let repo = Repository::discover(path)
+        .map_err(|_| format!("No git repository at {}", path.display()))?;
+
+    if repo.is_bare() {
+        let config = config::init_config(repo.path())?;
+        return Ok((repo, config));
+    }
+
+    let dir = repo.workdir().ok_or("No workdir")?.to_path_buf();
+    let mut config = config::init_config(&dir)?;
+    config.launch_dir = path
+        .canonicalize()?
+        .strip_prefix(dir.canonicalize()?)
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+
+    Ok((repo, config))
+}
+
+fn open() -> Res<(Repository, config::Config)> {
+    log::debug!("Opening repo");
+    let repo = open_repo_from_env()?;
+
+    if repo.is_bare() {
+        log::debug!("Bare repo, opening read-only");
+        let config = config::init_config(repo.path())?;
+        return Ok((repo, config));
+    }
+
+    log::debug!("Finding git dir");
+    let dir = PathBuf::from(
+        String::from_utf8(
+            Command::new("git")
+                .args(["rev-parse", "--show-toplevel"])
+                .output()?
+                .stdout,
+        )?
+        .trim_end(),
+    );
+    repo.set_workdir(&dir, false)?;
+
+    log::debug!("Initializing config");
+    let mut config = config::init_config(&dir)?;
+    config.launch_dir = PathBuf::from(
+        String::from_utf8(
+            Command::new("git")
+                .args(["rev-parse", "--show-prefix"])
+                .output()?
+                .stdout,
+        )?
+        .trim_end(),
+    );
+
+    Ok((repo, config))
+}
+
+fn outside_of_repo() -> bool {
+    Repository::open_from_env().is_err_and(|err| err.code() == git2::ErrorCode::NotFound)
+}
+
+/// Rather than exiting, offers to initialize a repository in the current directory, or to open a recent one.
+fn choose_when_outside_of_repo(term: &mut Term) -> Res<Option<(Repository, config::Config)>> {
+    let dir = std::env::current_dir()?;
+    let recent = recent_repos::load()
+        .into_iter()
+        .filter(|repo| repo.exists())
+        .collect::<Vec<_>>();
+
+    match init::choose(term, &dir, &recent, || Ok(event::read()?))? {
+        init::Choice::Init => {
+            init::init(&dir)?;
+            open_at(&dir).map(Some)
+        }
+        init::Choice::Open(repo) => open_at(&repo).map(Some),
+        init::Choice::Quit => Ok(None),
+    }
+}
+
 fn open_repo_from_env() -> Res<Repository> {
     match Repository::open_from_env() {
         Ok(repo) => Ok(repo),
@@ -122,25 +337,55 @@ fn open_repo_from_env() -> Res<Repository> {
     }
 }
 
+/// How long `<wait-cmd>` waits for a command, so that a stuck one doesn't hang a script.
+const WAIT_CMD_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Sends the keys of `--keys`, waiting in between where asked to.
 fn handle_initial_send_keys(
-    keys: &[(KeyModifiers, KeyCode)],
+    steps: &[ScriptStep],
     state: &mut state::State,
-    term: &mut ratatui::prelude::Terminal<term::TermBackend>,
-) -> Result<(), Box<dyn Error>> {
-    let initial_events = keys
-        .iter()
-        .map(|(mods, key)| {
-            Event::Key(KeyEvent {
-                code: *key,
-                modifiers: *mods,
-                kind: event::KeyEventKind::Press,
-                state: KeyEventState::NONE,
-            })
-        })
-        .collect::<Vec<_>>();
+    term: &mut Term,
+) -> Res<()> {
+    let mut events = vec![];
 
-    if !initial_events.is_empty() {
-        state.update(term, &initial_events)?;
+    for step in steps {
+        let (mods, key) = match *step {
+            ScriptStep::Key(mods, key) => (mods, key),
+            ScriptStep::WaitCmd => {
+                state.update(term, &std::mem::take(&mut events))?;
+                let started = Instant::now();
+                while state.pending_cmd.is_some() {
+                    if started.elapsed() > WAIT_CMD_TIMEOUT {
+                        return Err(format!(
+                            "<wait-cmd> gave up after {}s, the command is still running",
+                            WAIT_CMD_TIMEOUT.as_secs()
+                        )
+                        .into());
+                    }
+
+                    thread::sleep(Duration::from_millis(10));
+                    state.update(term, &[])?;
+                }
+                continue;
+            }
+            ScriptStep::Sleep(duration) => {
+                state.update(term, &std::mem::take(&mut events))?;
+                thread::sleep(duration);
+                state.update(term, &[])?;
+                continue;
+            }
+        };
+
+        events.push(Event::Key(KeyEvent {
+            code: key,
+            modifiers: mods,
+            kind: event::KeyEventKind::Press,
+            state: KeyEventState::NONE,
+        }));
+    }
+
+    if !events.is_empty() {
+        state.update(term, &events)?;
     }
 
     Ok(())
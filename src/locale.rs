@@ -0,0 +1,72 @@
+use crate::config::Config;
+use chrono::Locale;
+use std::env;
+
+/// `general.locale`, or the one set in the environment like `LANG=de_DE.UTF-8` if that's empty.
+/// The "C" and "POSIX" locales, or ones that aren't known, give `None`: English with no digit grouping.
+pub(crate) fn locale(config: &Config) -> Option<Locale> {
+    let name = Some(config.general.locale.clone())
+        .filter(|name| !name.is_empty())
+        .or_else(|| {
+            ["LC_ALL", "LC_TIME", "LANG"]
+                .into_iter()
+                .filter_map(|var| env::var(var).ok())
+                .find(|value| !value.is_empty())
+        })?;
+
+    parse(&name)
+}
+
+/// Parses a name like "de_DE.UTF-8@euro", leaving out the encoding and modifier.
+pub(crate) fn parse(name: &str) -> Option<Locale> {
+    let name = name.split(['.', '@']).next()?;
+    Locale::try_from(name)
+        .ok()
+        .filter(|locale| *locale != Locale::POSIX)
+}
+
+/// A count, with its digits grouped by thousands like the locale does, e.g. "12.345" in German.
+pub(crate) fn format_count(config: &Config, count: usize) -> String {
+    let separator = locale(config)
+        .map(|locale| pure_rust_locales::locale_match!(locale => LC_NUMERIC::THOUSANDS_SEP))
+        .unwrap_or("");
+
+    group_digits(&count.to_string(), separator)
+}
+
+fn group_digits(digits: &str, separator: &str) -> String {
+    if separator.is_empty() {
+        return digits.to_string();
+    }
+
+    let mut grouped = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push_str(separator);
+        }
+        grouped.push(digit);
+    }
+
+    grouped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{group_digits, parse};
+    use chrono::Locale;
+
+    #[test]
+    fn parse_names() {
+        assert_eq!(parse("de_DE.UTF-8"), Some(Locale::de_DE));
+        assert_eq!(parse("fr_FR@euro"), Some(Locale::fr_FR));
+        assert_eq!(parse("C"), None);
+        assert_eq!(parse("POSIX"), None);
+    }
+
+    #[test]
+    fn group() {
+        assert_eq!(group_digits("1234567", "."), "1.234.567");
+        assert_eq!(group_digits("123", ","), "123");
+        assert_eq!(group_digits("1234", ""), "1234");
+    }
+}
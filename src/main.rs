@@ -18,6 +18,10 @@ pub fn main() -> Res<()> {
         simple_logging::log_to_file("gitu.log", LevelFilter::Trace)?;
     }
 
+    if args.json() {
+        return gitu::print_json(&args);
+    }
+
     panic::set_hook(Box::new(|panic_info| {
         term::cleanup_alternate_screen();
         term::cleanup_raw_mode();
@@ -29,6 +29,8 @@ pub(crate) enum Menu {
     Push,
     #[serde(rename = "rebase_menu")]
     Rebase,
+    #[serde(rename = "remote_menu")]
+    Remote,
     #[serde(rename = "reset_menu")]
     Reset,
     #[serde(rename = "revert_menu")]
@@ -37,10 +39,15 @@ pub(crate) enum Menu {
     Stash,
 }
 
+/// Which args of a menu were active, and with what value, keyed by arg.
+pub(crate) type SavedArgs = BTreeMap<String, Option<String>>;
+
 pub(crate) struct PendingMenu {
     pub menu: Menu,
     pub is_hidden: bool,
     pub(crate) args: BTreeMap<Cow<'static, str>, arg::Arg>,
+    /// A line about what the menu would act on, computed as it opens.
+    pub(crate) summary: Option<String>,
 }
 
 impl PendingMenu {
@@ -58,6 +65,7 @@ impl PendingMenu {
                 Menu::Pull => ops::pull::init_args(),
                 Menu::Push => ops::push::init_args(),
                 Menu::Rebase => ops::rebase::init_args(),
+                Menu::Remote => vec![],
                 Menu::Reset => ops::reset::init_args(),
                 Menu::Revert => ops::revert::init_args(),
                 Menu::Stash => ops::stash::init_args(),
@@ -65,6 +73,42 @@ impl PendingMenu {
             .into_iter()
             .map(|arg| (Cow::from(arg.arg), arg))
             .collect(),
+            summary: None,
+        }
+    }
+
+    /// The state of every arg, to be put back with `restore_args` when the menu is opened again.
+    pub(crate) fn saved_args(&self) -> SavedArgs {
+        self.args
+            .iter()
+            .filter(|(_, arg)| !arg.is_transient)
+            .map(|(key, arg)| {
+                let value = arg
+                    .is_active()
+                    .then(|| arg.value_as_string().unwrap_or_default());
+                (key.to_string(), value)
+            })
+            .collect()
+    }
+
+    /// Sets args the way they were saved. Those that are unknown or no longer parse are left as they are.
+    pub(crate) fn restore_args(&mut self, saved: &SavedArgs) {
+        for (key, value) in saved {
+            // Saved before it was made transient
+            let Some(arg) = self
+                .args
+                .get_mut(key.as_str())
+                .filter(|arg| !arg.is_transient)
+            else {
+                continue;
+            };
+
+            match value {
+                Some(value) => {
+                    let _ = arg.set(value);
+                }
+                None => arg.unset(),
+            }
         }
     }
 
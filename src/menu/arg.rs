@@ -5,6 +5,8 @@ use regex::Regex;
 pub(crate) struct Arg {
     pub arg: &'static str,
     pub display: &'static str,
+    /// Left out when menu args are saved, see `transient`.
+    pub is_transient: bool,
     value: Box<dyn ArgValue>,
 }
 
@@ -13,6 +15,7 @@ impl Arg {
         Arg {
             arg,
             display,
+            is_transient: false,
             value: Box::new(ArgBool { value: default }),
         }
     }
@@ -29,6 +32,7 @@ impl Arg {
         Arg {
             arg,
             display,
+            is_transient: false,
             value: Box::new(ArgT::<T> {
                 value: default.map(|fun| fun()),
                 default,
@@ -37,6 +41,14 @@ impl Arg {
         }
     }
 
+    /// Never saved by `general.persist_menu_args`, for args too risky to be left on, like `--force`.
+    pub fn transient(self) -> Self {
+        Arg {
+            is_transient: true,
+            ..self
+        }
+    }
+
     pub fn is_active(&self) -> bool {
         self.value.is_set()
     }
@@ -0,0 +1,82 @@
+use crate::Res;
+use chrono::{DateTime, FixedOffset};
+use git2::Repository;
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::PathBuf,
+    time::Duration,
+};
+
+/// A command Gitu ran, as recorded in `.git/gitu-oplog`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct OpLogEntry {
+    /// When the Gitu that ran it was started, telling sessions apart.
+    pub session: DateTime<FixedOffset>,
+    pub started: DateTime<FixedOffset>,
+    pub duration: Duration,
+    /// `None` if it was killed by a signal.
+    pub exit_code: Option<i32>,
+    pub args: String,
+}
+
+/// How many entries are kept, the oldest being dropped to make room for new ones.
+pub(crate) const MAX_ENTRIES: usize = 1000;
+
+/// Appends `entry` to the op log of `repo`, one tab-separated line per command.
+pub(crate) fn record(repo: &Repository, entry: &OpLogEntry) -> Res<()> {
+    let line = format!(
+        "{}\t{}\t{}\t{}\t{}\n",
+        entry.session.to_rfc3339(),
+        entry.started.to_rfc3339(),
+        entry.duration.as_millis(),
+        entry
+            .exit_code
+            .map(|code| code.to_string())
+            .unwrap_or("-".into()),
+        entry.args.replace(['\t', '\n'], " "),
+    );
+
+    let existing = fs::read_to_string(path(repo)).unwrap_or_default();
+    let count = existing.lines().count();
+    if count < MAX_ENTRIES {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path(repo))?;
+        file.write_all(line.as_bytes())?;
+    } else {
+        let kept = existing
+            .lines()
+            .skip(count + 1 - MAX_ENTRIES)
+            .map(|line| format!("{}\n", line))
+            .collect::<String>();
+        fs::write(path(repo), kept + &line)?;
+    }
+
+    Ok(())
+}
+
+/// Everything recorded, oldest first. Lines that can't be made sense of are skipped.
+pub(crate) fn load(repo: &Repository) -> Vec<OpLogEntry> {
+    fs::read_to_string(path(repo))
+        .unwrap_or_default()
+        .lines()
+        .filter_map(parse_line)
+        .collect()
+}
+
+fn parse_line(line: &str) -> Option<OpLogEntry> {
+    let mut fields = line.splitn(5, '\t');
+    Some(OpLogEntry {
+        session: DateTime::parse_from_rfc3339(fields.next()?).ok()?,
+        started: DateTime::parse_from_rfc3339(fields.next()?).ok()?,
+        duration: Duration::from_millis(fields.next()?.parse().ok()?),
+        exit_code: fields.next()?.parse().ok(),
+        args: fields.next()?.to_string(),
+    })
+}
+
+fn path(repo: &Repository) -> PathBuf {
+    repo.path().join("gitu-oplog")
+}
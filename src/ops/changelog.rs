@@ -0,0 +1,21 @@
+use super::{Action, OpTrait};
+use crate::{items::TargetData, screen, state::State, term::Term};
+use std::rc::Rc;
+
+pub(crate) struct ShowChangelog;
+impl OpTrait for ShowChangelog {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            state.close_menu();
+            let size = state.screens.last().unwrap().size;
+            state
+                .screens
+                .push(screen::changelog::create(Rc::clone(&state.config), size)?);
+            Ok(())
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Show changelog".into()
+    }
+}
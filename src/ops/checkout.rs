@@ -1,5 +1,17 @@
-use super::{create_prompt_with_default, selected_rev, Action, OpTrait};
-use crate::{items::TargetData, menu::arg::Arg, prompt::PromptData, state::State, term::Term, Res};
+use super::{
+    create_picker_prompt, picked_rev, remote_branch_candidates, rev_candidates, selected_rev,
+    set_picker_prompt, set_prompt, Action, OpTrait,
+};
+use crate::{
+    git::{self, get_head, remote::get_upstream},
+    items::TargetData,
+    menu::arg::Arg,
+    prompt::PromptData,
+    state::State,
+    term::Term,
+    Res,
+};
+use git2::{BranchType, Repository};
 use std::{process::Command, rc::Rc};
 use tui_prompts::State as _;
 
@@ -10,8 +22,9 @@ pub(crate) fn init_args() -> Vec<Arg> {
 pub(crate) struct Checkout;
 impl OpTrait for Checkout {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
-        Some(create_prompt_with_default(
+        Some(create_picker_prompt(
             "Checkout",
+            rev_candidates,
             checkout,
             selected_rev,
             true,
@@ -23,7 +36,21 @@ impl OpTrait for Checkout {
     }
 }
 
-fn checkout(state: &mut State, term: &mut Term, rev: &str) -> Res<()> {
+fn checkout(state: &mut State, term: &mut Term, input: &str) -> Res<()> {
+    let rev = picked_rev(input);
+    if let Some(local_name) = untracked_remote_branch(state.repo(), rev) {
+        let remote_branch = rev.to_string();
+        state.close_menu();
+        set_prompt(
+            state,
+            "Create tracking branch",
+            Box::new(move |state, term, name| checkout_tracking(state, term, &remote_branch, name)),
+            Box::new(move |_| Some(local_name.clone())),
+            true,
+        );
+        return Ok(());
+    }
+
     let mut cmd = Command::new("git");
     cmd.args(["checkout"]);
     cmd.args(state.pending_menu.as_ref().unwrap().args());
@@ -34,6 +61,34 @@ fn checkout(state: &mut State, term: &mut Term, rev: &str) -> Res<()> {
     Ok(())
 }
 
+/// If `rev` is a remote branch without a local counterpart, returns the name a local tracking branch would get.
+fn untracked_remote_branch(repo: &Repository, rev: &str) -> Option<String> {
+    let branch = repo.find_branch(rev, BranchType::Remote).ok()?;
+    let remote = repo.branch_remote_name(branch.get().name()?).ok()?;
+    let local_name = rev
+        .strip_prefix(&format!("{}/", remote.as_str()?))?
+        .to_string();
+
+    if local_name == "HEAD" || repo.find_branch(&local_name, BranchType::Local).is_ok() {
+        return None;
+    }
+
+    Some(local_name)
+}
+
+fn checkout_tracking(
+    state: &mut State,
+    term: &mut Term,
+    remote_branch: &str,
+    name: &str,
+) -> Res<()> {
+    let mut cmd = Command::new("git");
+    cmd.args(["checkout", "-b", name, "--track", remote_branch]);
+
+    state.run_cmd(term, &[], cmd)?;
+    Ok(())
+}
+
 pub(crate) struct CheckoutNewBranch;
 impl OpTrait for CheckoutNewBranch {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
@@ -47,8 +102,12 @@ impl OpTrait for CheckoutNewBranch {
         }))
     }
 
-    fn display(&self, _state: &State) -> String {
-        "Checkout new branch".into()
+    fn display(&self, state: &State) -> String {
+        if state.repo().head_detached().unwrap_or(false) {
+            "Create branch here".into()
+        } else {
+            "Checkout new branch".into()
+        }
     }
 }
 
@@ -64,3 +123,272 @@ fn checkout_new_branch_prompt_update(state: &mut State, term: &mut Term) -> Res<
     }
     Ok(())
 }
+
+pub(crate) struct ReturnToBranch;
+impl OpTrait for ReturnToBranch {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            state.close_menu();
+            let branch = git::previous_branch(state.repo())?.ok_or("No previous branch found")?;
+
+            let mut cmd = Command::new("git");
+            cmd.args(["checkout", &branch]);
+            state.run_cmd(term, &[], cmd)
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Return to previous branch".into()
+    }
+}
+
+/// Like `git checkout -`, going back to whatever was checked out before, be it a branch or not.
+pub(crate) struct CheckoutPrevious;
+impl OpTrait for CheckoutPrevious {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            state.close_menu();
+            let previous = git::previous_checkout(state.repo())?.ok_or("No previous checkout")?;
+
+            let mut cmd = Command::new("git");
+            cmd.args(["checkout", "@{-1}"]);
+            state.run_cmd(term, &[], cmd)?;
+
+            state.display_info(format!("Switched back to {}", previous));
+            Ok(())
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Checkout previous".into()
+    }
+}
+
+pub(crate) struct CheckoutDetached;
+impl OpTrait for CheckoutDetached {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        match target {
+            Some(TargetData::Tag(rev) | TargetData::Commit(rev)) => {
+                let rev = rev.clone();
+                Some(Rc::new(move |state: &mut State, term: &mut Term| {
+                    let mut cmd = Command::new("git");
+                    cmd.args(["checkout", "--detach", &rev]);
+
+                    state.close_menu();
+                    state.run_cmd(term, &[], cmd)
+                }))
+            }
+            _ => None,
+        }
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Checkout detached".into()
+    }
+}
+
+pub(crate) struct CreateBranch;
+impl OpTrait for CreateBranch {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        match target {
+            Some(TargetData::Tag(rev) | TargetData::Commit(rev)) => {
+                let rev = rev.clone();
+                Some(Rc::new(move |state: &mut State, _term: &mut Term| {
+                    let rev = rev.clone();
+                    set_prompt(
+                        state,
+                        "Create branch",
+                        Box::new(move |state, term, name| create_branch(state, term, name, &rev)),
+                        Box::new(|_| None),
+                        true,
+                    );
+                    Ok(())
+                }))
+            }
+            _ => None,
+        }
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Create branch here".into()
+    }
+}
+
+fn create_branch(state: &mut State, term: &mut Term, name: &str, rev: &str) -> Res<()> {
+    let mut cmd = Command::new("git");
+    cmd.args(["branch", name, rev]);
+
+    state.close_menu();
+    state.run_cmd(term, &[], cmd)
+}
+
+pub(crate) struct Spinoff;
+impl OpTrait for Spinoff {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            let upstream = get_upstream(state.repo())?
+                .map(|upstream| upstream.get().peel_to_commit().map(|c| c.id().to_string()))
+                .transpose()?;
+
+            let Some(base) = upstream else {
+                state.close_menu();
+                return Err("Cannot spin off: branch has no upstream".into());
+            };
+
+            prompt_spinoff(state, base);
+            Ok(())
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Spin off unpushed commits".into()
+    }
+}
+
+pub(crate) struct SpinoffFrom;
+impl OpTrait for SpinoffFrom {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        match target {
+            Some(TargetData::Commit(r)) => {
+                let base = r.clone();
+                Some(Rc::new(move |state: &mut State, _term: &mut Term| {
+                    let head = state.repo().head()?.peel_to_commit()?.id();
+                    let base_id = state.repo().revparse_single(&base)?.peel_to_commit()?.id();
+
+                    if !state.repo().graph_descendant_of(head, base_id)? {
+                        state.close_menu();
+                        return Err("Cannot spin off: commit is not an ancestor of HEAD".into());
+                    }
+
+                    prompt_spinoff(state, base.clone());
+                    Ok(())
+                }))
+            }
+            _ => None,
+        }
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Spin off commits after this".into()
+    }
+}
+
+fn prompt_spinoff(state: &mut State, base: String) {
+    set_prompt(
+        state,
+        "Spin off to new branch",
+        Box::new(move |state, term, name| spinoff(state, term, name, &base)),
+        Box::new(|_| None),
+        true,
+    );
+}
+
+/// Moves the commits after `base` onto a new branch, and resets the current branch to `base`.
+fn spinoff(state: &mut State, term: &mut Term, name: &str, base: &str) -> Res<()> {
+    let current = get_head(state.repo())?;
+    let current = current.strip_prefix("refs/heads/").unwrap_or(&current);
+
+    let mut checkout_cmd = Command::new("git");
+    checkout_cmd.args(["checkout", "-b", name]);
+
+    let mut reset_cmd = Command::new("git");
+    reset_cmd.args(["branch", "--force", current, base]);
+
+    state.close_menu();
+    state.run_cmd(term, &[], checkout_cmd)?;
+    state.run_cmd(term, &[], reset_cmd)
+}
+
+/// Sets what the selected branch, or else the current one, tracks, picking among the remote branches.
+pub(crate) struct SetUpstream;
+impl OpTrait for SetUpstream {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let target = target.cloned();
+        Some(Rc::new(move |state: &mut State, _term: &mut Term| {
+            let branch = target_or_current_branch(state.repo(), target.as_ref())?;
+            let default = upstream_or_namesake(state.repo(), &branch);
+            let candidates = remote_branch_candidates(state);
+
+            set_picker_prompt(
+                state,
+                "Set upstream",
+                candidates,
+                Box::new(move |state, term, upstream| {
+                    let mut cmd = Command::new("git");
+                    cmd.args(["branch", "--set-upstream-to", upstream, &branch]);
+
+                    state.close_menu();
+                    state.run_cmd(term, &[], cmd)
+                }),
+                Box::new(move |_| default.clone()),
+                true,
+            )
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Set upstream".into()
+    }
+}
+
+pub(crate) struct UnsetUpstream;
+impl OpTrait for UnsetUpstream {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let target = target.cloned();
+        Some(Rc::new(move |state: &mut State, term: &mut Term| {
+            let branch = target_or_current_branch(state.repo(), target.as_ref())?;
+
+            let mut cmd = Command::new("git");
+            cmd.args(["branch", "--unset-upstream", &branch]);
+
+            state.close_menu();
+            state.run_cmd(term, &[], cmd)
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Unset upstream".into()
+    }
+}
+
+/// The local branch selected, if any, or else the one checked out.
+fn target_or_current_branch(repo: &Repository, target: Option<&TargetData>) -> Res<String> {
+    if let Some(TargetData::Branch(branch)) = target {
+        if repo.find_branch(branch, BranchType::Local).is_ok() {
+            return Ok(branch.clone());
+        }
+    }
+
+    let head = repo.head()?;
+    if !head.is_branch() {
+        return Err("Head is not a branch".into());
+    }
+
+    Ok(head.shorthand().ok_or("Branch name not utf-8")?.to_string())
+}
+
+/// The branch's upstream if it has one, or else a remote branch of the same name.
+fn upstream_or_namesake(repo: &Repository, branch: &str) -> Option<String> {
+    let local = repo.find_branch(branch, BranchType::Local).ok()?;
+    if let Ok(upstream) = local.upstream() {
+        return upstream.name().ok().flatten().map(str::to_string);
+    }
+
+    repo.branches(Some(BranchType::Remote))
+        .ok()?
+        .filter_map(Result::ok)
+        .filter_map(|(remote, _)| remote.name().ok().flatten().map(str::to_string))
+        .find(|name| name.split_once('/').is_some_and(|(_, name)| name == branch))
+}
@@ -0,0 +1,45 @@
+use super::{Action, OpTrait};
+use crate::{items::TargetData, screen, state::State, term::Term};
+use std::rc::Rc;
+
+pub(crate) struct ShowCmdLog;
+impl OpTrait for ShowCmdLog {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            state.close_menu();
+            let size = state.screens.last().unwrap().size;
+            let history = Rc::clone(&state.current_cmd_log.history);
+            state.screens.push(screen::cmd_log::create(
+                Rc::clone(&state.config),
+                size,
+                history,
+            )?);
+            Ok(())
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Show command log".into()
+    }
+}
+
+pub(crate) struct ShowOpLog;
+impl OpTrait for ShowOpLog {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            state.close_menu();
+            let size = state.screens.last().unwrap().size;
+            state.screens.push(screen::op_log::create(
+                Rc::clone(&state.config),
+                Rc::clone(state.repo()),
+                size,
+                state.session_started,
+            )?);
+            Ok(())
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Show op log".into()
+    }
+}
@@ -1,7 +1,18 @@
-use super::{Action, OpTrait};
-use crate::{items::TargetData, menu::arg::Arg, state::State, term::Term};
+use super::{create_prompt, create_y_n_prompt, set_pick_only_prompt, Action, OpTrait};
+use crate::{
+    commit_lint,
+    git::revise::{self, Revision},
+    items::TargetData,
+    menu::arg::Arg,
+    state::State,
+    term::Term,
+    Res,
+};
+use git2::Oid;
 use std::{
     ffi::{OsStr, OsString},
+    fs,
+    path::{Path, PathBuf},
     process::Command,
     rc::Rc,
 };
@@ -27,6 +38,11 @@ pub(crate) struct Commit;
 impl OpTrait for Commit {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
         Some(Rc::new(|state: &mut State, term: &mut Term| {
+            if checks_message(state) {
+                let template = state.config.general.commit_template.clone();
+                return edit_and_commit(state, term, &[], &template);
+            }
+
             let mut cmd = Command::new("git");
             cmd.args(["commit"]);
             cmd.args(state.pending_menu.as_ref().unwrap().args());
@@ -46,6 +62,10 @@ pub(crate) struct CommitAmend;
 impl OpTrait for CommitAmend {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
         Some(Rc::new(|state: &mut State, term: &mut Term| {
+            if state.config.general.commit_lint.enabled {
+                return edit_and_commit(state, term, &["--amend"], "");
+            }
+
             let mut cmd = Command::new("git");
             cmd.args(["commit", "--amend"]);
             cmd.args(state.pending_menu.as_ref().unwrap().args());
@@ -61,6 +81,413 @@ impl OpTrait for CommitAmend {
     }
 }
 
+pub(crate) struct CommitFromClipboard;
+impl OpTrait for CommitFromClipboard {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            let message = match &mut state.clipboard {
+                Some(cb) => cb.get_text()?,
+                None => {
+                    state.close_menu();
+                    return Err("Clipboard not available".into());
+                }
+            };
+
+            if checks_message(state) {
+                return edit_and_commit(state, term, &[], &message);
+            }
+
+            let mut cmd = Command::new("git");
+            cmd.args(["commit", "--edit", "--message", &message]);
+            cmd.args(state.pending_menu.as_ref().unwrap().args());
+
+            state.close_menu();
+            state.run_cmd_interactive(term, cmd)?;
+            Ok(())
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "with clipboard message".into()
+    }
+}
+
+pub(crate) struct CommitWithPrefix;
+impl OpTrait for CommitWithPrefix {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            let prefixes = state.config.general.commit_prefixes.clone();
+            set_pick_only_prompt(
+                state,
+                "Commit prefix",
+                prefixes,
+                Box::new(commit_with_prefix),
+                true,
+            )
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "with prefix".into()
+    }
+}
+
+fn commit_with_prefix(state: &mut State, term: &mut Term, prefix: &str) -> Res<()> {
+    let message = format!("{} {}", prefix, state.config.general.commit_template);
+
+    if checks_message(state) {
+        return edit_and_commit(state, term, &[], &message);
+    }
+
+    let mut cmd = Command::new("git");
+    cmd.args(["commit", "--edit", "--message", &message]);
+    cmd.args(state.pending_menu.as_ref().unwrap().args());
+
+    state.close_menu();
+    state.run_cmd_interactive(term, cmd)
+}
+
+pub(crate) struct CommitQuick;
+impl OpTrait for CommitQuick {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt("Commit subject", commit_quick, true))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Commit all tracked".into()
+    }
+}
+
+fn commit_quick(state: &mut State, term: &mut Term, subject: &str) -> Res<()> {
+    let subject = subject.trim().to_string();
+    if subject.is_empty() {
+        return Err("Aborting commit due to empty commit message".into());
+    }
+
+    let problems = commit_lint::lint(&state.config.general.commit_lint, &subject);
+
+    let mut action: Action = Rc::new(move |state: &mut State, term: &mut Term| {
+        let mut cmd = Command::new("git");
+        cmd.args(["commit", "--all", "--message", &subject]);
+
+        state.close_menu();
+        state.run_cmd(term, &[], cmd)
+    });
+
+    if problems.is_empty() {
+        return Rc::get_mut(&mut action).unwrap()(state, term);
+    }
+
+    for problem in problems {
+        state.display_info(problem);
+    }
+
+    Rc::get_mut(&mut create_y_n_prompt(action, "Commit anyway?")).unwrap()(state, term)
+}
+
+/// Subject of checkpoint commits, recognized by `SquashWip`.
+const WIP_PREFIX: &str = "WIP:";
+
+pub(crate) struct CommitWip;
+impl OpTrait for CommitWip {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            state.close_menu();
+
+            let mut add_cmd = Command::new("git");
+            add_cmd.args(["add", "--all"]);
+            state.run_cmd(term, &[], add_cmd)?;
+
+            let mut commit_cmd = Command::new("git");
+            commit_cmd.args(["commit", "--no-verify", "--message"]);
+            commit_cmd.arg(format!("{} checkpoint", WIP_PREFIX));
+            state.run_cmd(term, &[], commit_cmd)
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "WIP checkpoint".into()
+    }
+}
+
+pub(crate) struct SquashWip;
+impl OpTrait for SquashWip {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            let count = count_wip_commits(state)?;
+            if count == 0 {
+                state.close_menu();
+                return Err("No WIP commits to squash".into());
+            }
+
+            // Each commit is made over a reset that's undone if it fails,
+            // so that declining the linted message leaves the WIP commits as they were
+            if checks_message(state) {
+                let template = state.config.general.commit_template.clone();
+                return edit_and_commit_with(
+                    state,
+                    term,
+                    &[],
+                    &template,
+                    Rc::new(move |state, term, cmd| squash_wip(state, term, count, cmd)),
+                );
+            }
+
+            let mut commit_cmd = Command::new("git");
+            commit_cmd.arg("commit");
+            commit_cmd.args(state.pending_menu.as_ref().unwrap().args());
+
+            state.close_menu();
+            squash_wip(state, term, count, commit_cmd)
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "squash WIP commits".into()
+    }
+}
+
+/// Soft resets the `count` WIP commits away and runs `commit_cmd`, putting them back if no commit was made.
+fn squash_wip(state: &mut State, term: &mut Term, count: usize, commit_cmd: Command) -> Res<()> {
+    let mut reset_cmd = Command::new("git");
+    reset_cmd.args(["reset", "--soft", &format!("HEAD~{}", count)]);
+    state.run_cmd(term, &[], reset_cmd)?;
+
+    let result = state.run_cmd_interactive(term, commit_cmd);
+    if result.is_err() {
+        let mut restore_cmd = Command::new("git");
+        restore_cmd.args(["reset", "--soft", "ORIG_HEAD"]);
+        state.run_cmd(term, &[], restore_cmd)?;
+    }
+
+    result
+}
+
+/// The number of consecutive WIP commits leading up to HEAD.
+fn count_wip_commits(state: &State) -> Res<usize> {
+    let mut count = 0;
+    let mut commit = state.repo().head()?.peel_to_commit()?;
+
+    while commit.summary().unwrap_or_default().starts_with(WIP_PREFIX) {
+        count += 1;
+        commit = match commit.parent(0) {
+            Ok(parent) => parent,
+            Err(_) => return Err("Can't squash WIP commits down to the root commit".into()),
+        };
+    }
+
+    Ok(count)
+}
+
+fn checks_message(state: &State) -> bool {
+    let general = &state.config.general;
+    general.commit_lint.enabled || !general.commit_template.is_empty()
+}
+
+/// Runs a prepared `git commit`.
+type CommitFn = Rc<dyn Fn(&mut State, &mut Term, Command) -> Res<()>>;
+
+/// Has the user write the message before the commit is made, so that it can be linted first.
+/// `extra_args` go to both `git commit`s, like `--amend`.
+fn edit_and_commit(
+    state: &mut State,
+    term: &mut Term,
+    extra_args: &'static [&'static str],
+    initial_message: &str,
+) -> Res<()> {
+    edit_and_commit_with(
+        state,
+        term,
+        extra_args,
+        initial_message,
+        Rc::new(|state, term, cmd| state.run_cmd_interactive(term, cmd)),
+    )
+}
+
+/// Like `edit_and_commit`, but leaving running each `git commit` to `commit`.
+fn edit_and_commit_with(
+    state: &mut State,
+    term: &mut Term,
+    extra_args: &'static [&'static str],
+    initial_message: &str,
+    commit: CommitFn,
+) -> Res<()> {
+    let args = state.pending_menu.as_ref().unwrap().args();
+    state.close_menu();
+
+    let comment_char = comment_char(state);
+    let path = git_dir_path(state, "GITU_EDITMSG");
+    let full_path = state.repo().workdir().expect("No workdir").join(&path);
+    if full_path.exists() {
+        fs::remove_file(&full_path)?;
+    }
+
+    // git's own editor flow, status comments and the --verbose diff included. Its editor saves
+    // the message here and then fails, so that nothing is committed before it's linted.
+    let mut edit_cmd = Command::new("git");
+    if comment_char_is_auto(state) {
+        edit_cmd.args(["-c", &format!("core.commentChar={}", comment_char)]);
+    }
+    edit_cmd.args(["commit", "--no-verify"]);
+    edit_cmd.args(extra_args);
+    if !initial_message.is_empty() {
+        edit_cmd.args(["--edit", "--message", initial_message]);
+    }
+    edit_cmd.args(&args);
+    edit_cmd.env(
+        "GIT_EDITOR",
+        format!(
+            "{} \"$1\" && cp \"$1\" '{}' && false",
+            git_editor(state)?,
+            path.to_string_lossy().replace('\'', r"'\''")
+        ),
+    );
+
+    let edit_result = commit(state, term, edit_cmd);
+    if !full_path.exists() {
+        return edit_result;
+    }
+
+    let message = strip_message(&fs::read_to_string(&full_path)?, &comment_char);
+    if message.trim().is_empty() {
+        return Err("Aborting commit due to empty commit message".into());
+    }
+
+    fs::write(&full_path, &message)?;
+
+    let action: Action = Rc::new(move |state: &mut State, term: &mut Term| {
+        let mut cmd = Command::new("git");
+        cmd.args(["commit", "--cleanup=whitespace"]);
+        cmd.args(extra_args);
+        cmd.arg("--file");
+        cmd.arg(&path);
+        cmd.args(&args);
+
+        commit(state, term, cmd)
+    });
+
+    lint_then(state, term, &message, action)
+}
+
+/// Runs `action` if `message` passes `general.commit_lint`, otherwise lists the problems and asks first.
+fn lint_then(state: &mut State, term: &mut Term, message: &str, mut action: Action) -> Res<()> {
+    let problems = commit_lint::lint(&state.config.general.commit_lint, message.trim());
+    if problems.is_empty() {
+        return Rc::get_mut(&mut action).unwrap()(state, term);
+    }
+
+    for problem in problems {
+        state.display_info(problem);
+    }
+
+    Rc::get_mut(&mut create_y_n_prompt(action, "Commit anyway?")).unwrap()(state, term)
+}
+
+/// Has the user edit a message in `.git/GITU_EDITMSG`, giving back the message.
+fn edit_message(state: &mut State, term: &mut Term, initial_message: &str) -> Res<String> {
+    let path = edit_in_git_dir(state, term, "GITU_EDITMSG", initial_message.as_bytes())?;
+    let full_path = state.repo().workdir().expect("No workdir").join(&path);
+    let message = strip_message(&fs::read_to_string(&full_path)?, &comment_char(state));
+
+    if message.trim().is_empty() {
+        return Err("Aborting commit due to empty commit message".into());
+    }
+
+    Ok(message)
+}
+
+/// The `core.commentChar` lines of commit messages start with.
+/// git picks one per message when it's `auto`, here that's pinned to the default.
+fn comment_char(state: &State) -> String {
+    configured_comment_char(state)
+        .filter(|comment_char| comment_char != "auto")
+        .unwrap_or("#".into())
+}
+
+fn comment_char_is_auto(state: &State) -> bool {
+    configured_comment_char(state).is_some_and(|comment_char| comment_char == "auto")
+}
+
+fn configured_comment_char(state: &State) -> Option<String> {
+    let config = state.repo().config().ok()?;
+    config.get_string("core.commentChar").ok()
+}
+
+/// Cleans up a message like `git commit --cleanup=strip`,
+/// leaving out comment lines and anything past the scissors line of `--verbose`.
+fn strip_message(message: &str, comment_char: &str) -> String {
+    let scissors = format!(
+        "{} ------------------------ >8 ------------------------",
+        comment_char
+    );
+
+    message
+        .lines()
+        .take_while(|line| *line != scissors)
+        .filter(|line| !line.starts_with(comment_char))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Writes `contents` to the file `name` in the git dir and opens it in the editor.
+/// Gives back its path relative to the workdir, which is where commands run.
+pub(crate) fn edit_in_git_dir(
+    state: &mut State,
+    term: &mut Term,
+    name: &str,
+    contents: &[u8],
+) -> Res<PathBuf> {
+    let path = git_dir_path(state, name);
+    fs::write(
+        state.repo().workdir().expect("No workdir").join(&path),
+        contents,
+    )?;
+    state.run_cmd_interactive(term, editor_cmd(state, &path)?)?;
+    Ok(path)
+}
+
+/// The path of the file `name` in the git dir, relative to the workdir.
+fn git_dir_path(state: &State, name: &str) -> PathBuf {
+    // Commands run in the workdir, where the git dir usually is
+    let git_dir = state.repo().path();
+    let workdir = state.repo().workdir().expect("No workdir");
+    git_dir.strip_prefix(workdir).unwrap_or(git_dir).join(name)
+}
+
+/// Runs the editor git would use, the same way git does.
+pub(crate) fn editor_cmd(state: &State, path: &Path) -> Res<Command> {
+    let editor = git_editor(state)?;
+
+    // Anything more than a plain program path, like arguments, is left to the shell
+    let mut cmd = if editor
+        .chars()
+        .all(|c| c.is_alphanumeric() || "-_./".contains(c))
+    {
+        Command::new(&editor)
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", &format!("{} \"$@\"", editor), &editor]);
+        cmd
+    };
+
+    cmd.arg(path);
+    Ok(cmd)
+}
+
+/// The editor git would use, as a shell command.
+fn git_editor(state: &State) -> Res<String> {
+    let output = Command::new("git")
+        .args(["var", "GIT_EDITOR"])
+        .current_dir(state.repo().workdir().expect("No workdir"))
+        .output()?;
+
+    if !output.status.success() {
+        return Err("Couldn't find an editor, configure one with core.editor".into());
+    }
+
+    Ok(String::from_utf8(output.stdout)?.trim_end().to_string())
+}
+
 pub(crate) struct CommitFixup;
 impl OpTrait for CommitFixup {
     fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
@@ -88,10 +515,58 @@ impl OpTrait for CommitFixup {
     }
 }
 
-fn commit_fixup_cmd(args: &[OsString], rev: &OsStr) -> Command {
+/// Like `CommitFixup`, but making an `amend!` commit, which also replaces the message once squashed.
+pub(crate) struct CommitAmendFixup;
+impl OpTrait for CommitAmendFixup {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        fixup_kind_action(target, "amend")
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "amend! fixup".into()
+    }
+}
+
+/// Like `CommitAmendFixup`, but only replacing the message, leaving out what's staged.
+pub(crate) struct CommitRewordFixup;
+impl OpTrait for CommitRewordFixup {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        fixup_kind_action(target, "reword")
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "reword! fixup".into()
+    }
+}
+
+/// Runs `git commit --fixup=<kind>:<rev>`, which has the message edited.
+fn fixup_kind_action(target: Option<&TargetData>, kind: &str) -> Option<Action> {
+    let Some(TargetData::Commit(r)) = target else {
+        return None;
+    };
+
+    let fixup = OsString::from(format!("{}:{}", kind, r));
+    Some(Rc::new(move |state: &mut State, term: &mut Term| {
+        let args = state.pending_menu.as_ref().unwrap().args();
+
+        state.close_menu();
+        state.run_cmd_interactive(term, commit_fixup_cmd(&args, &fixup))
+    }))
+}
+
+/// `fixup` is the commit to fix up, optionally prefixed by `amend:` or `reword:`.
+fn commit_fixup_cmd(args: &[OsString], fixup: &OsStr) -> Command {
     let mut cmd = Command::new("git");
     cmd.args(["commit", "--fixup"]);
-    cmd.arg(rev);
+    cmd.arg(fixup);
     cmd.args(args);
     cmd
 }
@@ -125,6 +600,111 @@ impl OpTrait for CommitInstantFixup {
     }
 }
 
+pub(crate) struct CommitReword;
+impl OpTrait for CommitReword {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        match target {
+            Some(TargetData::Commit(r)) => {
+                let rev = OsString::from(r);
+
+                Some(Rc::new(move |state: &mut State, term: &mut Term| {
+                    state.close_menu();
+                    state.run_cmd_interactive(term, rebase_reword_cmd(&rev))
+                }))
+            }
+            _ => None,
+        }
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "reword".into()
+    }
+}
+
+/// Like `CommitReword`, but rewriting the commits in memory rather than with a rebase,
+/// so the worktree is left alone and nothing needs to be stashed.
+pub(crate) struct ReviseReword;
+impl OpTrait for ReviseReword {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let Some(TargetData::Commit(r)) = target else {
+            return None;
+        };
+
+        let oid = Oid::from_str(r).ok()?;
+        Some(Rc::new(move |state: &mut State, term: &mut Term| {
+            state.close_menu();
+            let initial_message = state
+                .repo()
+                .find_commit(oid)?
+                .message()
+                .unwrap_or_default()
+                .to_string();
+            let message = edit_message(state, term, &initial_message)?;
+
+            let revised = format!("{}\n", message.trim());
+            let action: Action = Rc::new(move |state: &mut State, _term: &mut Term| {
+                state.run_in_process(&format!("reword {} in place", oid), |state| {
+                    revise::revise(state.repo(), oid, Revision::Message(revised.clone()))
+                })?;
+                state.screen_mut().update()
+            });
+
+            lint_then(state, term, &message, action)
+        }))
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "reword in place".into()
+    }
+}
+
+/// Like `CommitInstantFixup`, but adding what's staged to the commit in memory rather than with a rebase.
+pub(crate) struct ReviseFixup;
+impl OpTrait for ReviseFixup {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let Some(TargetData::Commit(r)) = target else {
+            return None;
+        };
+
+        let oid = Oid::from_str(r).ok()?;
+        Some(Rc::new(move |state: &mut State, _term: &mut Term| {
+            state.close_menu();
+            state.run_in_process(&format!("instant fixup {} in place", oid), |state| {
+                revise::revise(state.repo(), oid, Revision::AddStaged)
+            })?;
+            state.screen_mut().update()
+        }))
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "instant fixup in place".into()
+    }
+}
+
+/// The target commit is always first in the todo list, so only that line is changed to `reword`.
+fn rebase_reword_cmd(rev: &OsStr) -> Command {
+    let mut cmd = Command::new("git");
+    cmd.args(["rebase", "-i", "-q", "--autostash", "--keep-empty"]);
+    cmd.arg(parent(rev));
+    cmd.env(
+        "GIT_SEQUENCE_EDITOR",
+        "sed -i.bak -e '1s/^[a-z]* /reword /'",
+    );
+    cmd
+}
+
 fn rebase_autosquash_cmd(rev: &OsStr) -> Command {
     let mut cmd = Command::new("git");
     cmd.args([
@@ -0,0 +1,42 @@
+use super::{Action, OpTrait};
+use crate::{items::TargetData, state::State};
+use std::rc::Rc;
+
+pub(crate) struct CopyMessage;
+impl OpTrait for CopyMessage {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        match target {
+            Some(TargetData::Commit(r)) => copy_message(r.clone()),
+            _ => None,
+        }
+    }
+    fn is_target_op(&self) -> bool {
+        true
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Copy message".into()
+    }
+}
+
+fn copy_message(r: String) -> Option<Action> {
+    Some(Rc::new(move |state, _term| {
+        state.close_menu();
+        let message = state
+            .repo()
+            .revparse_single(&r)?
+            .peel_to_commit()?
+            .message()
+            .ok_or("Commit message not utf-8")?
+            .to_string();
+
+        match &mut state.clipboard {
+            Some(cb) => {
+                cb.set_text(message)?;
+                state.display_info("Commit message copied to clipboard".to_owned());
+            }
+            None => state.display_error("Clipboard not available".to_owned()),
+        }
+        Ok(())
+    }))
+}
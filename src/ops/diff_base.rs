@@ -0,0 +1,46 @@
+use super::{picked_rev, rev_candidates, set_picker_prompt, Action, OpTrait};
+use crate::{items::TargetData, state::State, term::Term, Res};
+use std::rc::Rc;
+
+pub(crate) struct SetDiffBase;
+impl OpTrait for SetDiffBase {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            if state.screen().diff_base.is_none() {
+                state.close_menu();
+                return Err("This screen has no changes to compare".into());
+            }
+
+            let candidates = rev_candidates(state);
+            set_picker_prompt(
+                state,
+                "Compare changes to",
+                candidates,
+                Box::new(set_diff_base),
+                Box::new(|_| Some("HEAD".into())),
+                true,
+            )
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Set diff base".into()
+    }
+}
+
+fn set_diff_base(state: &mut State, _term: &mut Term, input: &str) -> Res<()> {
+    let Some(diff_base) = state.screen().diff_base.clone() else {
+        unreachable!();
+    };
+
+    let rev = match picked_rev(input) {
+        "HEAD" => "",
+        rev => {
+            state.repo().revparse_single(rev)?.peel_to_tree()?;
+            rev
+        }
+    };
+
+    diff_base.replace(rev.to_string());
+    state.screen_mut().update()
+}
@@ -1,23 +1,52 @@
-use super::{Action, OpTrait};
-use crate::{git::diff::Hunk, items::TargetData, state::State};
-use std::{path::PathBuf, process::Command, rc::Rc};
+use super::{Action, OpTrait, Preview};
+use crate::{config::DiscardToTrash, git::diff::Hunk, items::TargetData, state::State, Res};
+use ratatui::text::Line;
+use std::{fs, path::PathBuf, process::Command, rc::Rc};
 
 pub(crate) struct Discard;
 impl OpTrait for Discard {
     fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
-        let action = match target.cloned() {
-            Some(TargetData::Branch(branch)) => discard_branch(branch),
-            Some(TargetData::File(file)) => clean_file(file),
-            Some(TargetData::Delta(d)) => match d.status {
-                git2::Delta::Added => remove_file(d.new_file),
-                git2::Delta::Renamed => rename_file(d.new_file, d.old_file),
-                _ => checkout_file(d.old_file),
-            },
-            Some(TargetData::Hunk(h)) => discard_unstaged_patch(h),
+        let (action, preview): (Action, Preview) = match target.cloned() {
+            Some(TargetData::Branch(branch)) => {
+                return Some(super::create_y_n_prompt(
+                    discard_branch(branch),
+                    "Really discard?",
+                ))
+            }
+            Some(TargetData::GitConfig(key)) => return super::git_config::unset(key),
+            Some(TargetData::File(file)) => (clean_file(file.clone()), clean_preview(file)),
+            Some(TargetData::Delta(d)) => {
+                let preview_delta = d.clone();
+                let preview: Preview = Rc::new(move |state| {
+                    Ok(super::diff_preview(
+                        &state.config,
+                        std::slice::from_ref(&preview_delta),
+                    ))
+                });
+
+                let action = match d.status {
+                    git2::Delta::Added => remove_file(d.new_file),
+                    git2::Delta::Renamed => rename_file(d.new_file, d.old_file),
+                    _ => checkout_file(d.old_file),
+                };
+
+                (action, preview)
+            }
+            Some(TargetData::Hunk(h)) => {
+                let preview_hunk = Rc::clone(&h);
+                let preview: Preview =
+                    Rc::new(move |state| Ok(super::hunk_preview(&state.config, &preview_hunk)));
+
+                (discard_unstaged_patch(h), preview)
+            }
             _ => return None,
         };
 
-        Some(super::create_y_n_prompt(action, "Really discard?"))
+        Some(in_worktree(super::create_y_n_prompt_with_preview(
+            action,
+            "Really discard?",
+            preview,
+        )))
     }
 
     fn is_target_op(&self) -> bool {
@@ -29,6 +58,33 @@ impl OpTrait for Discard {
     }
 }
 
+/// Files, deltas and hunks can be discarded only from a worktree, while branches can be deleted in a bare repository too.
+fn in_worktree(mut action: Action) -> Action {
+    Rc::new(move |state, term| {
+        if state.repo().is_bare() {
+            return Err("Can't do that in a bare repository, there's no worktree".into());
+        }
+
+        Rc::get_mut(&mut action).unwrap()(state, term)
+    })
+}
+
+/// Lists what `git clean` would remove, as untracked files have no diff to show.
+fn clean_preview(file: PathBuf) -> Preview {
+    Rc::new(move |state| {
+        let output = Command::new("git")
+            .args(["clean", "--dry-run", "-d", "--"])
+            .arg(&file)
+            .current_dir(state.repo().workdir().expect("No workdir"))
+            .output()?;
+
+        Ok(String::from_utf8(output.stdout)?
+            .lines()
+            .map(|line| Line::styled(line.to_string(), &state.config.style.file_header))
+            .collect())
+    })
+}
+
 fn discard_branch(branch: String) -> Action {
     Rc::new(move |state, term| {
         let mut cmd = Command::new("git");
@@ -42,8 +98,36 @@ fn discard_branch(branch: String) -> Action {
 
 fn clean_file(file: PathBuf) -> Action {
     Rc::new(move |state, term| {
+        match state.config.general.discard_to_trash {
+            DiscardToTrash::Off => (),
+            DiscardToTrash::Os => {
+                state.close_menu();
+                state.run_in_process(
+                    &format!("move {} to the trash", file.display()),
+                    |state| {
+                        trash::delete(state.repo().workdir().expect("No workdir").join(&file))?;
+                        Ok(())
+                    },
+                )?;
+                state.display_info(format!("Moved {} to the trash", file.display()));
+                return state.screen_mut().update();
+            }
+            DiscardToTrash::Backup => {
+                state.close_menu();
+                state.run_in_process(
+                    &format!("back up {} in .git/gitu-trash", file.display()),
+                    |state| backup_file(state, &file),
+                )?;
+                state.display_info(format!("Backed up {} in .git/gitu-trash", file.display()));
+                return state.screen_mut().update();
+            }
+        }
+
         let mut cmd = Command::new("git");
         cmd.args(["clean", "--force"]);
+        if file.to_string_lossy().ends_with('/') {
+            cmd.arg("-d");
+        }
         cmd.arg(&file);
 
         state.close_menu();
@@ -51,6 +135,26 @@ fn clean_file(file: PathBuf) -> Action {
     })
 }
 
+/// Moves the file out of the way, under a directory per discard, rather than deleting it.
+fn backup_file(state: &State, file: &PathBuf) -> Res<()> {
+    let backup_dir = state.repo().path().join("gitu-trash").join(
+        chrono::Local::now()
+            .format("%Y-%m-%dT%H-%M-%S%.3f")
+            .to_string(),
+    );
+    let backup = backup_dir.join(file);
+
+    if let Some(parent) = backup.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    fs::rename(
+        state.repo().workdir().expect("No workdir").join(file),
+        backup,
+    )?;
+    Ok(())
+}
+
 fn rename_file(src: PathBuf, dest: PathBuf) -> Action {
     Rc::new(move |state, term| {
         let mut cmd = Command::new("git");
@@ -91,6 +195,6 @@ fn discard_unstaged_patch(h: Rc<Hunk>) -> Action {
         cmd.args(["apply", "--reverse"]);
 
         state.close_menu();
-        state.run_cmd(term, &h.format_patch().into_bytes(), cmd)
+        state.run_cmd(term, &h.encode_patch(h.format_patch()), cmd)
     })
 }
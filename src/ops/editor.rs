@@ -1,11 +1,16 @@
 use super::{set_prompt, Action, OpTrait};
 use crate::{
+    cmd_log::CmdLogEntry,
+    git::{self, remote::upstream_transfer_summary},
     items::TargetData,
-    menu::PendingMenu,
-    screen::NavMode,
+    menu::{Menu, PendingMenu},
+    screen::{HunkBlame, NavMode, ScreenKind},
     state::{root_menu, State},
+    syntax_highlight,
     term::Term,
+    Res,
 };
+use ratatui::text::Line;
 use std::rc::Rc;
 
 pub(crate) struct Quit;
@@ -20,16 +25,35 @@ impl OpTrait for Quit {
             if menu == root_menu(&state.config) {
                 if state.screens.len() == 1 {
                     let quit = Rc::new(|state: &mut State, _term: &mut Term| {
+                        // Rather than leaving it running on its own
+                        if let Some((mut child, _, _)) = state.pending_cmd.take() {
+                            let _ = child.kill();
+                            child.wait()?;
+                        }
+
                         state.quit = true;
                         Ok(())
                     });
 
-                    let mut action = if state.config.general.confirm_quit.enabled {
+                    // Stopping the command might leave things half done, so that's asked twice
+                    let mut action = if state.pending_cmd.is_some() {
+                        super::create_y_n_prompt(
+                            super::create_y_n_prompt(quit, "Really stop it?"),
+                            "A command is still running, stop it and quit?",
+                        )
+                    } else if state.config.general.confirm_quit.enabled {
                         super::create_y_n_prompt(quit, "Really quit?")
                     } else {
                         quit
                     };
 
+                    if state.pending_cmd.is_some() || state.config.general.confirm_quit.enabled {
+                        let summary = quit_summary(state)?;
+                        if !summary.is_empty() {
+                            state.prompt.preview = Some(summary);
+                        }
+                    }
+
                     Rc::get_mut(&mut action).unwrap()(state, term)?;
                 } else {
                     state.screens.pop();
@@ -51,12 +75,61 @@ impl OpTrait for Quit {
     }
 }
 
-pub(crate) struct OpenMenu(pub crate::menu::Menu);
+/// What's left undone when quitting: a command still running and changes staged but not committed.
+fn quit_summary(state: &State) -> Res<Vec<Line<'static>>> {
+    let style = &state.config.style;
+    let mut lines = vec![];
+
+    if let Some((_, log_entry, _)) = &state.pending_cmd {
+        if let CmdLogEntry::Cmd { args, .. } = &*log_entry.read().unwrap() {
+            lines.push(Line::styled(format!("Running: {}", args), &style.command));
+        }
+    }
+
+    if let Some(repo) = state.repo.as_ref().filter(|repo| !repo.is_bare()) {
+        let staged = git::diff_staged(&state.config, repo, None)?;
+        if !staged.deltas.is_empty() {
+            lines.push(Line::styled(
+                format!("Staged changes not committed ({})", staged.deltas.len()),
+                &style.section_header,
+            ));
+            lines.extend(staged.deltas.iter().map(|delta| {
+                Line::styled(
+                    format!(
+                        "{}   {}",
+                        format!("{:?}", delta.status).to_lowercase(),
+                        delta.new_file.display()
+                    ),
+                    &style.file_header,
+                )
+            }));
+        }
+    }
+
+    Ok(lines)
+}
+
+pub(crate) struct OpenMenu(pub Menu);
 impl OpTrait for OpenMenu {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
         let submenu = self.0;
         Some(Rc::new(move |state, _term| {
-            state.pending_menu = Some(PendingMenu::init(submenu));
+            let mut pending_menu = PendingMenu::init(submenu);
+            if let Some(saved) = state.menu_args.get(&submenu) {
+                pending_menu.restore_args(saved);
+            }
+
+            pending_menu.summary = match submenu {
+                Menu::Push => upstream_transfer_summary(&state.config, state.repo(), true)
+                    .ok()
+                    .flatten(),
+                Menu::Pull => upstream_transfer_summary(&state.config, state.repo(), false)
+                    .ok()
+                    .flatten(),
+                _ => None,
+            };
+
+            state.pending_menu = Some(pending_menu);
             Ok(())
         }))
     }
@@ -80,6 +153,23 @@ impl OpTrait for Refresh {
     }
 }
 
+/// Like `Refresh`, but also drops cached state that may have gone stale by opening the repository anew,
+/// starting over at the initial screen.
+pub(crate) struct HardRefresh;
+impl OpTrait for HardRefresh {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state, term| {
+            state.close_menu();
+            syntax_highlight::clear_cache();
+            state.reopen_repo(term.size()?)
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Hard refresh".into()
+    }
+}
+
 pub(crate) struct ToggleArg(pub String);
 impl OpTrait for ToggleArg {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
@@ -153,6 +243,113 @@ impl OpTrait for ToggleSection {
     }
 }
 
+pub(crate) struct ToggleWhitespaceHunks;
+impl OpTrait for ToggleWhitespaceHunks {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state, _term| {
+            state.close_menu();
+            let screen = state.screen_mut();
+            screen.hide_whitespace_hunks = !screen.hide_whitespace_hunks;
+            screen.update()
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Toggle whitespace-only hunks".into()
+    }
+}
+
+pub(crate) struct ToggleLineWrap;
+impl OpTrait for ToggleLineWrap {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state, _term| {
+            state.close_menu();
+            let screen = state.screen_mut();
+            screen.wrap_lines = !screen.wrap_lines;
+            screen.update()
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Toggle wrapping of long lines".into()
+    }
+}
+
+/// Annotates the selected hunk's unchanged lines with the commit and author that last changed them,
+/// to tell where a fixup should go.
+pub(crate) struct ToggleHunkBlame;
+impl OpTrait for ToggleHunkBlame {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let (Some(TargetData::Hunk(hunk)) | Some(TargetData::HunkLine(hunk, _))) = target else {
+            return None;
+        };
+
+        let hunk = Rc::clone(hunk);
+        Some(Rc::new(move |state, _term| {
+            state.close_menu();
+            if state
+                .screen()
+                .blame
+                .as_ref()
+                .is_some_and(|blame| blame.is_of(&hunk))
+            {
+                state.screen_mut().blame = None;
+                return state.screen_mut().update();
+            }
+
+            // A commit's diff is blamed as of the commit itself, others as of HEAD from before the changes
+            let (rev, new_side) = match &state.screen().kind {
+                Some(ScreenKind::Show { reference }) => (reference.clone(), true),
+                _ => ("HEAD".to_string(), false),
+            };
+
+            let lines = git::blame::blame_hunk(state.repo(), &rev, &hunk, new_side)?;
+            let screen = state.screen_mut();
+            screen.blame = Some(HunkBlame {
+                file_header: hunk.file_header.clone(),
+                header: hunk.header.clone(),
+                lines,
+            });
+            screen.update()
+        }))
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Toggle blame of hunk".into()
+    }
+}
+
+pub(crate) struct ToggleExternalDiff;
+impl OpTrait for ToggleExternalDiff {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state, _term| {
+            state.close_menu();
+            if state.config.general.external_diff.is_empty() {
+                return Err("Set general.external_diff to render diffs with another tool".into());
+            }
+
+            let screen = state.screen_mut();
+            screen.external_diff = !screen.external_diff;
+            let result = screen.update();
+            if result.is_err() {
+                // Back to Gitu's own diffs, rather than failing on every refresh
+                screen.external_diff = false;
+                screen.update()?;
+            }
+
+            result
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Toggle external diff".into()
+    }
+}
+
 pub(crate) struct MoveUp;
 impl OpTrait for MoveUp {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
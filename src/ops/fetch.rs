@@ -1,5 +1,14 @@
-use super::{create_prompt, Action, OpTrait};
-use crate::{items::TargetData, menu::arg::Arg, state::State, term::Term, Res};
+use super::{
+    create_picker_prompt, create_prompt, remote_branch_candidates, remote_candidates, Action,
+    OpTrait,
+};
+use crate::{
+    items::TargetData,
+    menu::arg::{positive_number, Arg},
+    state::State,
+    term::Term,
+    Res,
+};
 use std::{process::Command, rc::Rc};
 
 pub(crate) fn init_args() -> Vec<Arg> {
@@ -31,7 +40,13 @@ impl OpTrait for FetchAll {
 pub(crate) struct FetchElsewhere;
 impl OpTrait for FetchElsewhere {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
-        Some(create_prompt("Select remote", push_elsewhere, true))
+        Some(create_picker_prompt(
+            "Select remote",
+            remote_candidates,
+            fetch_elsewhere,
+            |_| None,
+            true,
+        ))
     }
 
     fn display(&self, _state: &State) -> String {
@@ -39,7 +54,7 @@ impl OpTrait for FetchElsewhere {
     }
 }
 
-fn push_elsewhere(state: &mut State, term: &mut Term, remote: &str) -> Res<()> {
+fn fetch_elsewhere(state: &mut State, term: &mut Term, remote: &str) -> Res<()> {
     let mut cmd = Command::new("git");
     cmd.args(["fetch"]);
     cmd.args(state.pending_menu.as_ref().unwrap().args());
@@ -49,3 +64,94 @@ fn push_elsewhere(state: &mut State, term: &mut Term, remote: &str) -> Res<()> {
     state.run_cmd_async(term, &[], cmd)?;
     Ok(())
 }
+
+/// Fetches just one branch, picked like "origin/main", or a refspec typed like "origin main:other".
+pub(crate) struct FetchRefspec;
+impl OpTrait for FetchRefspec {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_picker_prompt(
+            "Fetch branch or refspec",
+            remote_branch_candidates,
+            fetch_refspec,
+            |_| None,
+            true,
+        ))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "a branch or refspec".into()
+    }
+}
+
+fn fetch_refspec(state: &mut State, term: &mut Term, input: &str) -> Res<()> {
+    let (remote, refspecs) = match input.split_once(' ') {
+        Some((remote, refspecs)) => (remote.to_string(), refspecs.split_whitespace().collect()),
+        None => {
+            let remote = remote_candidates(state)
+                .into_iter()
+                .find(|remote| input.starts_with(&format!("{}/", remote)))
+                .ok_or("Pick a remote branch, or give a remote and refspec like \"origin main\"")?;
+
+            let branch = &input[remote.len() + 1..];
+            (remote, vec![branch])
+        }
+    };
+
+    let mut cmd = Command::new("git");
+    cmd.args(["fetch"]);
+    cmd.args(state.pending_menu.as_ref().unwrap().args());
+    cmd.arg(&remote);
+    cmd.args(refspecs);
+
+    state.close_menu();
+    state.run_cmd_async(term, &[], cmd)?;
+    Ok(())
+}
+
+pub(crate) struct FetchUnshallow;
+impl OpTrait for FetchUnshallow {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state, term| {
+            fetch_shallow(state, term, "--unshallow")
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "all history (unshallow)".into()
+    }
+}
+
+pub(crate) struct FetchDeepen;
+impl OpTrait for FetchDeepen {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt(
+            "Deepen history by commits",
+            fetch_deepen,
+            true,
+        ))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "more history (deepen)".into()
+    }
+}
+
+fn fetch_deepen(state: &mut State, term: &mut Term, input: &str) -> Res<()> {
+    let commits = positive_number(input)?;
+    fetch_shallow(state, term, &format!("--deepen={}", commits))
+}
+
+fn fetch_shallow(state: &mut State, term: &mut Term, depth_arg: &str) -> Res<()> {
+    if !state.repo().is_shallow() {
+        state.close_menu();
+        return Err("This isn't a shallow clone".into());
+    }
+
+    let mut cmd = Command::new("git");
+    cmd.args(["fetch", depth_arg]);
+    cmd.args(state.pending_menu.as_ref().unwrap().args());
+
+    state.close_menu();
+    state.run_cmd_async(term, &[], cmd)?;
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use super::{set_prompt, Action, OpTrait};
+use crate::{items::TargetData, state::State, term::Term, Res};
+use std::rc::Rc;
+
+pub(crate) struct Filter;
+impl OpTrait for Filter {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            if state.screen().filter.is_none() {
+                state.close_menu();
+                return Err("This screen can't be filtered".into());
+            }
+
+            set_prompt(
+                state,
+                "Filter (empty to clear)",
+                Box::new(apply_filter),
+                Box::new(|_| None),
+                true,
+            );
+            Ok(())
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Filter".into()
+    }
+}
+
+pub(crate) struct ClearFilter;
+impl OpTrait for ClearFilter {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            state.close_menu();
+            if state.screen().filter.is_none() {
+                return Err("This screen can't be filtered".into());
+            }
+
+            apply_filter(state, term, "")
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Clear filter".into()
+    }
+}
+
+fn apply_filter(state: &mut State, _term: &mut Term, query: &str) -> Res<()> {
+    let screen = state.screen_mut();
+    if let Some(filter) = &screen.filter {
+        filter.replace(query.to_string());
+    }
+
+    screen.update()
+}
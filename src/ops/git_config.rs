@@ -0,0 +1,90 @@
+use super::{set_pick_only_prompt, set_prompt, Action, OpTrait};
+use crate::{items::TargetData, screen, state::State, term::Term, Res};
+use git2::ConfigLevel;
+use std::{ffi::OsString, process::Command, rc::Rc};
+
+pub(crate) struct ShowGitConfig;
+impl OpTrait for ShowGitConfig {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            state.close_menu();
+            let size = state.screens.last().unwrap().size;
+            state.screens.push(screen::git_config::create(
+                Rc::clone(&state.config),
+                Rc::clone(state.repo()),
+                size,
+            )?);
+            Ok(())
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Show git config".into()
+    }
+}
+
+/// Asks whether to set `key` for this repository or globally, and then what to.
+pub(crate) fn edit(key: String) -> Option<Action> {
+    Some(Rc::new(move |state: &mut State, _term: &mut Term| {
+        let key = key.clone();
+        set_pick_only_prompt(
+            state,
+            "Set in scope",
+            vec!["local".into(), "global".into()],
+            Box::new(move |state, _term, scope| {
+                let (key, scope) = (key.clone(), scope.to_string());
+                let current = state.repo().config()?.snapshot()?.get_string(&key).ok();
+                set_prompt(
+                    state,
+                    "Value",
+                    Box::new(move |state, term, value| set(state, term, &scope, &key, value)),
+                    Box::new(move |_| current.clone()),
+                    true,
+                );
+                Ok(())
+            }),
+            true,
+        )
+    }))
+}
+
+fn set(state: &mut State, term: &mut Term, scope: &str, key: &str, value: &str) -> Res<()> {
+    let mut cmd = Command::new("git");
+    cmd.args(["config", &format!("--{}", scope), key, value]);
+
+    state.close_menu();
+    state.run_cmd(term, &[], cmd)
+}
+
+/// Unsets `key` where it's set, asking first.
+pub(crate) fn unset(key: String) -> Option<Action> {
+    Some(super::create_y_n_prompt(
+        Rc::new(move |state: &mut State, term: &mut Term| {
+            let level = state.repo().config()?.snapshot()?.get_entry(&key)?.level();
+
+            let mut cmd = Command::new("git");
+            cmd.arg("config");
+            cmd.args(scope_args(level)?);
+            cmd.args(["--unset", &key]);
+
+            state.close_menu();
+            state.run_cmd(term, &[], cmd)
+        }),
+        "Really unset?",
+    ))
+}
+
+/// The `git config` options that write to the file of `level`. `--global` may mean ~/.gitconfig rather than the
+/// XDG one, so that one is given by path.
+fn scope_args(level: ConfigLevel) -> Res<Vec<OsString>> {
+    if level == ConfigLevel::XDG {
+        return Ok(vec![
+            "--file".into(),
+            git2::Config::find_xdg()?.into_os_string(),
+        ]);
+    }
+
+    Ok(vec![
+        format!("--{}", screen::git_config::scope(level)).into()
+    ])
+}
@@ -0,0 +1,84 @@
+use super::{Action, OpTrait};
+use crate::{
+    items::TargetData,
+    screen::{Screen, ScreenKind},
+    state::State,
+    term::Term,
+    Res,
+};
+use std::{cell::Cell, rc::Rc};
+
+pub(crate) struct ListMore;
+impl OpTrait for ListMore {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            resize_selected_list(state, |limit| limit * 2)
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "List more stashes/commits".into()
+    }
+}
+
+pub(crate) struct ListFewer;
+impl OpTrait for ListFewer {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            resize_selected_list(state, |limit| (limit / 2).max(1))
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "List fewer stashes/commits".into()
+    }
+}
+
+fn resize_selected_list(state: &mut State, resize: fn(usize) -> usize) -> Res<()> {
+    state.close_menu();
+
+    let screen = state.screen_mut();
+    if let Some(log_limit) = &screen.log_limit {
+        let limit = resize(log_limit.get());
+        return set_log_limit(screen, limit);
+    }
+
+    let limits = Rc::clone(&state.list_limits);
+    let limit: &Cell<usize> = match state
+        .screen()
+        .get_selected_section()
+        .map(|section| section.id.as_ref())
+    {
+        Some("stashes") => &limits.stashes,
+        Some("recent_commits") => &limits.recent_commits,
+        _ => {
+            return Err(
+                "Select the stashes or recent commits to resize them, or open the log".into(),
+            )
+        }
+    };
+
+    limit.set(resize(limit.get()));
+
+    if state.config.general.persist_list_limits.enabled {
+        limits.save()?;
+    }
+
+    state.screen_mut().update()
+}
+
+/// Lists `limit` commits on a log screen.
+pub(crate) fn set_log_limit(screen: &mut Screen, limit: usize) -> Res<()> {
+    if let Some(log_limit) = &screen.log_limit {
+        log_limit.set(limit);
+    }
+
+    if let Some(ScreenKind::Log {
+        limit: kind_limit, ..
+    }) = &mut screen.kind
+    {
+        *kind_limit = limit;
+    }
+
+    screen.update()
+}
@@ -1,6 +1,10 @@
-use super::{create_prompt_with_default, selected_rev, Action, OpTrait};
+use super::{
+    create_picker_prompt, create_prompt, create_prompt_with_default, list_limits, picked_rev,
+    rev_candidates, selected_rev, Action, OpTrait,
+};
 use crate::{
-    items::TargetData,
+    git::{self, remote::get_upstream},
+    items::{Item, LogWalk, TargetData},
     menu::arg::{any_regex, positive_number, Arg},
     screen,
     state::State,
@@ -11,15 +15,21 @@ use git2::Oid;
 use regex::Regex;
 use std::rc::Rc;
 
+/// Number of commits listed unless `-n` is set.
+pub(crate) const DEFAULT_LIMIT: u32 = 256;
+
 pub(crate) fn init_args() -> Vec<Arg> {
     vec![
         Arg::new_arg(
             "-n",
             "Limit number of commits",
-            Some(|| 256),
+            Some(|| DEFAULT_LIMIT),
             positive_number,
         ),
         Arg::new_arg("--grep", "Search messages", None, any_regex),
+        Arg::new_flag("--all", "All branches, remotes and tags", false),
+        Arg::new_flag("--first-parent", "Only follow first parents", false),
+        Arg::new_flag("--simplify-by-decoration", "Only refs", false),
         // Arg::new_str("-S", "Search occurences"), // TOOD: Implement search
     ]
 }
@@ -28,7 +38,7 @@ pub(crate) struct LogCurrent;
 impl OpTrait for LogCurrent {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
         Some(Rc::new(|state: &mut State, _term: &mut Term| {
-            goto_log_screen(state, None);
+            goto_log_screen(state, None, None);
             Ok(())
         }))
     }
@@ -41,8 +51,9 @@ impl OpTrait for LogCurrent {
 pub(crate) struct LogOther;
 impl OpTrait for LogOther {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
-        Some(create_prompt_with_default(
+        Some(create_picker_prompt(
             "Log rev",
+            rev_candidates,
             log_other,
             selected_rev,
             true,
@@ -55,7 +66,7 @@ impl OpTrait for LogOther {
 }
 
 fn log_other(state: &mut State, _term: &mut Term, result: &str) -> Res<()> {
-    let oid_result = match state.repo.revparse_single(result) {
+    let oid_result = match state.repo().revparse_single(picked_rev(result)) {
         Ok(rev) => Ok(rev.id()),
         Err(err) => Err(format!("Failed due to: {:?}", err.code())),
     };
@@ -66,11 +77,177 @@ fn log_other(state: &mut State, _term: &mut Term, result: &str) -> Res<()> {
 
     let oid = oid_result?;
 
-    goto_log_screen(state, Some(oid));
+    goto_log_screen(state, Some(oid), None);
     Ok(())
 }
 
-fn goto_log_screen(state: &mut State, rev: Option<Oid>) {
+pub(crate) struct LogSinceBase;
+impl OpTrait for LogSinceBase {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            let since = required_branch_point(state)?;
+            goto_log_screen(state, None, Some(since));
+            Ok(())
+        }))
+    }
+
+    fn display(&self, state: &State) -> String {
+        format!("since {}", base_branch_name(state))
+    }
+}
+
+/// Where HEAD branched off `general.base_branch`, or an error telling why there's no such point.
+pub(crate) fn required_branch_point(state: &mut State) -> Res<Oid> {
+    let base_branch = &state.config.general.base_branch;
+    let branch_point = if base_branch.is_empty() {
+        Err("No `general.base_branch` set to compare with".into())
+    } else {
+        git::branch_point(&state.config, state.repo()).and_then(|branch_point| {
+            branch_point.ok_or_else(|| {
+                format!("Nothing committed since branching off {}", base_branch).into()
+            })
+        })
+    };
+
+    if branch_point.is_err() {
+        state.close_menu();
+    }
+
+    branch_point
+}
+
+pub(crate) fn base_branch_name(state: &State) -> &str {
+    match state.config.general.base_branch.as_str() {
+        "" => "base branch",
+        base_branch => base_branch,
+    }
+}
+
+pub(crate) struct LogSearch;
+impl OpTrait for LogSearch {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt("Search messages", log_search, true))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "search messages".into()
+    }
+}
+
+fn log_search(state: &mut State, _term: &mut Term, input: &str) -> Res<()> {
+    state.close_menu();
+    if input.is_empty() {
+        return Err("No search given".into());
+    }
+
+    state.screen_mut().search = Some(Regex::new(input)?);
+    select_match(state, true)
+}
+
+pub(crate) struct LogSearchNext;
+impl OpTrait for LogSearchNext {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            state.close_menu();
+            select_match(state, false)
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Next search match".into()
+    }
+}
+
+/// Moves to the next (or first) commit with a message matching the search, like `git log --grep`.
+/// More commits are listed if it's past the ones listed, and the search wraps around at the end.
+fn select_match(state: &mut State, from_top: bool) -> Res<()> {
+    let Some(search) = state.screen().search.clone() else {
+        return Err("Nothing searched for yet".into());
+    };
+
+    let repo = Rc::clone(state.repo());
+    let matches = |item: &Item| {
+        let Some(TargetData::Commit(hash)) = &item.target_data else {
+            return false;
+        };
+
+        Oid::from_str(hash)
+            .and_then(|oid| repo.find_commit(oid))
+            .is_ok_and(|commit| search.is_match(commit.message().unwrap_or_default()))
+    };
+
+    let screen = state.screen_mut();
+    if screen.select_matching(from_top, matches) {
+        return Ok(());
+    }
+
+    if let (Some(log_limit), Some(kind)) = (&screen.log_limit, &screen.kind) {
+        let limit = log_limit.get();
+        if let Some(index) = screen::log::find_match(&repo, kind, limit, &search)? {
+            let mut extended = limit.max(1);
+            while extended <= index {
+                extended *= 2;
+            }
+
+            list_limits::set_log_limit(screen, extended)?;
+            if screen.select_matching(from_top, matches) {
+                return Ok(());
+            }
+        }
+    }
+
+    if !screen.select_matching(true, matches) {
+        return Err(format!("No commit message matches {}", search).into());
+    }
+
+    Ok(())
+}
+
+pub(crate) struct LogRangeDiff;
+impl OpTrait for LogRangeDiff {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt_with_default(
+            "Range diff",
+            log_range_diff,
+            default_range,
+            true,
+        ))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "range-diff".into()
+    }
+}
+
+/// Compares the pushed version of a branch with what it looks like now, e.g. after a rebase.
+fn default_range(state: &State) -> Option<String> {
+    get_upstream(state.repo())
+        .ok()
+        .flatten()
+        .map(|_| "@{upstream}...HEAD".to_string())
+}
+
+fn log_range_diff(state: &mut State, _term: &mut Term, input: &str) -> Res<()> {
+    let args = input
+        .split_whitespace()
+        .map(String::from)
+        .collect::<Vec<_>>();
+    let size = state.screens.last().unwrap().size;
+    state.close_menu();
+
+    let screen = screen::range_diff::create(
+        Rc::clone(&state.config),
+        Rc::clone(state.repo()),
+        size,
+        args,
+    )?;
+
+    state.screens.drain(1..);
+    state.screens.push(screen);
+    Ok(())
+}
+
+fn goto_log_screen(state: &mut State, rev: Option<Oid>, since: Option<Oid>) {
     state.screens.drain(1..);
     let size = state.screens.last().unwrap().size;
     let limit = *state
@@ -87,16 +264,31 @@ fn goto_log_screen(state: &mut State, rev: Option<Oid>) {
 
     let msg_regex = msg_regex_menu.and_then(|arg| arg.value_as::<Regex>().cloned());
 
+    let flag = |name| {
+        state
+            .pending_menu
+            .as_ref()
+            .and_then(|m| m.args.get(name))
+            .is_some_and(|arg| arg.is_active())
+    };
+    let walk = LogWalk {
+        all: flag("--all"),
+        first_parent: flag("--first-parent"),
+        simplify_by_decoration: flag("--simplify-by-decoration"),
+    };
+
     state.close_menu();
 
     state.screens.push(
         screen::log::create(
             Rc::clone(&state.config),
-            Rc::clone(&state.repo),
+            Rc::clone(state.repo()),
             size,
             limit as usize,
             rev,
+            since,
             msg_regex,
+            walk,
         )
         .expect("Couldn't create screen"),
     );
@@ -0,0 +1,41 @@
+use super::{Action, OpTrait};
+use crate::{items::TargetData, state::State, term::Term};
+use std::{path::Path, process::Command, rc::Rc};
+
+pub(crate) struct Mergetool;
+impl OpTrait for Mergetool {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let Some(TargetData::Unmerged(file)) = target else {
+            return None;
+        };
+
+        let file = file.clone();
+        Some(Rc::new(move |state: &mut State, term: &mut Term| {
+            state.close_menu();
+            // `git mergetool` stages the file once it's resolved
+            state.run_cmd_interactive(term, mergetool_cmd(state, &file))?;
+            state.screen_mut().update()
+        }))
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Resolve with mergetool".into()
+    }
+}
+
+fn mergetool_cmd(state: &State, file: &Path) -> Command {
+    let mut cmd = Command::new("git");
+    cmd.args(["mergetool", "--no-prompt"]);
+
+    let tool = &state.config.editor.mergetool;
+    if !tool.is_empty() {
+        cmd.arg(format!("--tool={}", tool));
+    }
+
+    cmd.arg(file);
+    cmd
+}
@@ -2,31 +2,58 @@ use serde::{Deserialize, Serialize};
 use tui_prompts::State as _;
 
 use crate::{
-    cmd_log::CmdLogEntry, items::TargetData, menu::Menu, prompt::PromptData, state::State,
-    term::Term, Res,
+    cmd_log::CmdLogEntry,
+    config::Config,
+    git::diff::{Delta, Hunk},
+    items::TargetData,
+    menu::Menu,
+    picker::PickerState,
+    prompt::PromptData,
+    state::State,
+    term::Term,
+    Res,
 };
-use std::{fmt::Display, rc::Rc};
+use git2::BranchType;
+use ratatui::text::Line;
+use std::{borrow::Cow, fmt::Display, iter, rc::Rc};
 
+pub(crate) mod changelog;
 pub(crate) mod checkout;
+pub(crate) mod cmd_log;
 pub(crate) mod commit;
 pub(crate) mod copy_hash;
+pub(crate) mod copy_message;
+pub(crate) mod diff_base;
 pub(crate) mod discard;
 pub(crate) mod editor;
 pub(crate) mod fetch;
+pub(crate) mod filter;
+pub(crate) mod git_config;
+pub(crate) mod list_limits;
 pub(crate) mod log;
+pub(crate) mod mergetool;
 pub(crate) mod pull;
 pub(crate) mod push;
 pub(crate) mod rebase;
+pub(crate) mod remote;
+pub(crate) mod repair;
+pub(crate) mod repeat;
 pub(crate) mod reset;
+pub(crate) mod restore;
 pub(crate) mod revert;
 pub(crate) mod show;
 pub(crate) mod show_refs;
 pub(crate) mod stage;
+pub(crate) mod stage_interactive;
 pub(crate) mod stash;
+pub(crate) mod switch_repo;
 pub(crate) mod unstage;
 
 pub(crate) type Action = Rc<dyn FnMut(&mut State, &mut Term) -> Res<()>>;
 
+/// Lines showing what an action is about to affect.
+pub(crate) type Preview = Rc<dyn Fn(&State) -> Res<Vec<Line<'static>>>>;
+
 pub(crate) trait OpTrait {
     /// Get the implementation (which may or may not exist) of the Op given some TargetData.
     /// This indirection allows Gitu to show a contextual menu of applicable actions.
@@ -46,11 +73,33 @@ pub(crate) trait OpTrait {
 pub(crate) enum Op {
     Checkout,
     CheckoutNewBranch,
+    CheckoutDetached,
+    ReturnToBranch,
+    CheckoutPrevious,
+    SetUpstream,
+    UnsetUpstream,
+    CreateBranch,
     Commit,
     CommitAmend,
+    CommitFromClipboard,
+    CommitWithPrefix,
+    CommitQuick,
+    CommitWip,
+    SquashWip,
     FetchAll,
     FetchElsewhere,
+    FetchUnshallow,
+    FetchDeepen,
+    FetchRefspec,
+    Filter,
+    ClearFilter,
+    ListFewer,
+    ListMore,
     LogCurrent,
+    LogSearch,
+    Mergetool,
+    Repair,
+    LogSearchNext,
     PullFromPushRemote,
     PullFromUpstream,
     PullFromElsewhere,
@@ -60,7 +109,20 @@ pub(crate) enum Op {
     RebaseAbort,
     RebaseContinue,
     RebaseElsewhere,
+    SetRemoteUrl,
+    SetRemotePushUrl,
+    ShowChangelog,
+    ShowCmdLog,
+    ShowOpLog,
+    ShowGitConfig,
+    ShowChild,
+    ShowParent,
+    ShowRange,
+    ShowSinceBase,
     ShowRefs,
+    CycleRefsSort,
+    Spinoff,
+    SpinoffFrom,
     Stash,
     StashApply,
     StashIndex,
@@ -69,9 +131,17 @@ pub(crate) enum Op {
     StashPop,
     StashDrop,
     CommitFixup,
+    CommitAmendFixup,
+    CommitRewordFixup,
+    CommitReword,
     CommitInstantFixup,
+    ReviseReword,
+    ReviseFixup,
     LogOther,
+    LogRangeDiff,
+    LogSinceBase,
     RebaseAutosquash,
+    AutosquashFixups,
     RebaseInteractive,
     ResetSoft,
     ResetMixed,
@@ -81,12 +151,24 @@ pub(crate) enum Op {
     RevertCommit,
 
     Stage,
+    StageEditedHunk,
+    StageInteractively,
     Unstage,
+    RestoreFile,
+    RestoreFileStaged,
+    ToggleReviewed,
     Show,
     Discard,
     CopyHash,
+    CopyMessage,
 
+    SetDiffBase,
     ToggleSection,
+    ToggleWhitespaceHunks,
+    ToggleExternalDiff,
+    ToggleLineWrap,
+    ToggleHunkBlame,
+    SwitchRepo,
     MoveUp,
     MoveDown,
     MoveUpLine,
@@ -98,6 +180,10 @@ pub(crate) enum Op {
     HalfPageDown,
 
     Refresh,
+    HardRefresh,
+    RepeatLast,
+    RecordMacro,
+    ReplayMacro,
     Quit,
 
     #[serde(untagged)]
@@ -112,8 +198,18 @@ impl Op {
             Op::Quit => Box::new(editor::Quit),
             Op::OpenMenu(menu) => Box::new(editor::OpenMenu(menu)),
             Op::Refresh => Box::new(editor::Refresh),
+            Op::HardRefresh => Box::new(editor::HardRefresh),
+            Op::RepeatLast => Box::new(repeat::RepeatLast),
+            Op::RecordMacro => Box::new(repeat::RecordMacro),
+            Op::ReplayMacro => Box::new(repeat::ReplayMacro),
             Op::ToggleArg(name) => Box::new(editor::ToggleArg(name)),
+            Op::SetDiffBase => Box::new(diff_base::SetDiffBase),
             Op::ToggleSection => Box::new(editor::ToggleSection),
+            Op::ToggleWhitespaceHunks => Box::new(editor::ToggleWhitespaceHunks),
+            Op::ToggleExternalDiff => Box::new(editor::ToggleExternalDiff),
+            Op::ToggleLineWrap => Box::new(editor::ToggleLineWrap),
+            Op::ToggleHunkBlame => Box::new(editor::ToggleHunkBlame),
+            Op::SwitchRepo => Box::new(switch_repo::SwitchRepo),
             Op::MoveDown => Box::new(editor::MoveDown),
             Op::MoveUp => Box::new(editor::MoveUp),
             Op::MoveDownLine => Box::new(editor::MoveDownLine),
@@ -126,11 +222,31 @@ impl Op {
 
             Op::Checkout => Box::new(checkout::Checkout),
             Op::CheckoutNewBranch => Box::new(checkout::CheckoutNewBranch),
+            Op::CheckoutDetached => Box::new(checkout::CheckoutDetached),
+            Op::ReturnToBranch => Box::new(checkout::ReturnToBranch),
+            Op::CheckoutPrevious => Box::new(checkout::CheckoutPrevious),
+            Op::SetUpstream => Box::new(checkout::SetUpstream),
+            Op::UnsetUpstream => Box::new(checkout::UnsetUpstream),
+            Op::CreateBranch => Box::new(checkout::CreateBranch),
             Op::Commit => Box::new(commit::Commit),
             Op::CommitAmend => Box::new(commit::CommitAmend),
+            Op::CommitFromClipboard => Box::new(commit::CommitFromClipboard),
+            Op::CommitWithPrefix => Box::new(commit::CommitWithPrefix),
+            Op::CommitQuick => Box::new(commit::CommitQuick),
+            Op::CommitWip => Box::new(commit::CommitWip),
+            Op::SquashWip => Box::new(commit::SquashWip),
             Op::FetchAll => Box::new(fetch::FetchAll),
             Op::FetchElsewhere => Box::new(fetch::FetchElsewhere),
+            Op::FetchUnshallow => Box::new(fetch::FetchUnshallow),
+            Op::FetchDeepen => Box::new(fetch::FetchDeepen),
+            Op::FetchRefspec => Box::new(fetch::FetchRefspec),
+            Op::Filter => Box::new(filter::Filter),
+            Op::ClearFilter => Box::new(filter::ClearFilter),
             Op::LogCurrent => Box::new(log::LogCurrent),
+            Op::LogSearch => Box::new(log::LogSearch),
+            Op::Mergetool => Box::new(mergetool::Mergetool),
+            Op::Repair => Box::new(repair::Repair),
+            Op::LogSearchNext => Box::new(log::LogSearchNext),
             Op::PullFromPushRemote => Box::new(pull::PullFromPushRemote),
             Op::PullFromUpstream => Box::new(pull::PullFromUpstream),
             Op::PullFromElsewhere => Box::new(pull::PullFromElsewhere),
@@ -140,7 +256,23 @@ impl Op {
             Op::RebaseAbort => Box::new(rebase::RebaseAbort),
             Op::RebaseContinue => Box::new(rebase::RebaseContinue),
             Op::RebaseElsewhere => Box::new(rebase::RebaseElsewhere),
+            Op::SetRemoteUrl => Box::new(remote::SetRemoteUrl),
+            Op::SetRemotePushUrl => Box::new(remote::SetRemotePushUrl),
+            Op::ListFewer => Box::new(list_limits::ListFewer),
+            Op::ListMore => Box::new(list_limits::ListMore),
+            Op::ToggleReviewed => Box::new(show::ToggleReviewed),
+            Op::ShowChangelog => Box::new(changelog::ShowChangelog),
+            Op::ShowCmdLog => Box::new(cmd_log::ShowCmdLog),
+            Op::ShowOpLog => Box::new(cmd_log::ShowOpLog),
+            Op::ShowGitConfig => Box::new(git_config::ShowGitConfig),
+            Op::ShowChild => Box::new(show::ShowChild),
+            Op::ShowParent => Box::new(show::ShowParent),
+            Op::ShowRange => Box::new(show::ShowRange),
+            Op::ShowSinceBase => Box::new(show::ShowSinceBase),
             Op::ShowRefs => Box::new(show_refs::ShowRefs),
+            Op::CycleRefsSort => Box::new(show_refs::CycleRefsSort),
+            Op::Spinoff => Box::new(checkout::Spinoff),
+            Op::SpinoffFrom => Box::new(checkout::SpinoffFrom),
             Op::Stash => Box::new(stash::Stash),
             Op::StashApply => Box::new(stash::StashApply),
             Op::StashIndex => Box::new(stash::StashIndex),
@@ -150,10 +282,18 @@ impl Op {
             Op::StashDrop => Box::new(stash::StashDrop),
 
             Op::CommitFixup => Box::new(commit::CommitFixup),
+            Op::CommitAmendFixup => Box::new(commit::CommitAmendFixup),
+            Op::CommitRewordFixup => Box::new(commit::CommitRewordFixup),
+            Op::CommitReword => Box::new(commit::CommitReword),
             Op::CommitInstantFixup => Box::new(commit::CommitInstantFixup),
+            Op::ReviseReword => Box::new(commit::ReviseReword),
+            Op::ReviseFixup => Box::new(commit::ReviseFixup),
             Op::Discard => Box::new(discard::Discard),
             Op::LogOther => Box::new(log::LogOther),
+            Op::LogRangeDiff => Box::new(log::LogRangeDiff),
+            Op::LogSinceBase => Box::new(log::LogSinceBase),
             Op::RebaseAutosquash => Box::new(rebase::RebaseAutosquash),
+            Op::AutosquashFixups => Box::new(rebase::AutosquashFixups),
             Op::RebaseInteractive => Box::new(rebase::RebaseInteractive),
             Op::ResetSoft => Box::new(reset::ResetSoft),
             Op::ResetMixed => Box::new(reset::ResetMixed),
@@ -163,12 +303,135 @@ impl Op {
             Op::RevertCommit => Box::new(revert::RevertCommit),
             Op::Show => Box::new(show::Show),
             Op::Stage => Box::new(stage::Stage),
+            Op::StageEditedHunk => Box::new(stage::StageEditedHunk),
+            Op::StageInteractively => Box::new(stage_interactive::StageInteractively),
             Op::Unstage => Box::new(unstage::Unstage),
+            Op::RestoreFile => Box::new(restore::RestoreFile),
+            Op::RestoreFileStaged => Box::new(restore::RestoreFileStaged),
             Op::CopyHash => Box::new(copy_hash::CopyHash),
+            Op::CopyMessage => Box::new(copy_message::CopyMessage),
         }
     }
 }
 
+impl Op {
+    /// Whether this changes files, the index or what's checked out, which a bare repository has none of.
+    pub(crate) fn needs_worktree(&self) -> bool {
+        matches!(
+            self,
+            Op::Checkout
+                | Op::CheckoutNewBranch
+                | Op::CheckoutDetached
+                | Op::ReturnToBranch
+                | Op::CheckoutPrevious
+                | Op::Commit
+                | Op::CommitAmend
+                | Op::CommitFromClipboard
+                | Op::CommitWithPrefix
+                | Op::CommitQuick
+                | Op::CommitWip
+                | Op::SquashWip
+                | Op::CommitFixup
+                | Op::CommitAmendFixup
+                | Op::CommitRewordFixup
+                | Op::CommitReword
+                | Op::CommitInstantFixup
+                | Op::ReviseReword
+                | Op::ReviseFixup
+                | Op::Mergetool
+                | Op::PullFromPushRemote
+                | Op::PullFromUpstream
+                | Op::PullFromElsewhere
+                | Op::RebaseAbort
+                | Op::RebaseContinue
+                | Op::RebaseElsewhere
+                | Op::RebaseAutosquash
+                | Op::AutosquashFixups
+                | Op::RebaseInteractive
+                | Op::Spinoff
+                | Op::SpinoffFrom
+                | Op::Stash
+                | Op::StashApply
+                | Op::StashIndex
+                | Op::StashWorktree
+                | Op::StashKeepIndex
+                | Op::StashPop
+                | Op::StashDrop
+                | Op::ResetMixed
+                | Op::ResetHard
+                | Op::RevertAbort
+                | Op::RevertContinue
+                | Op::RevertCommit
+                | Op::Stage
+                | Op::StageEditedHunk
+                | Op::StageInteractively
+                | Op::Unstage
+                | Op::RestoreFile
+                | Op::RestoreFileStaged
+                | Op::SetDiffBase
+        )
+    }
+
+    /// Whether this works on screens that aren't of a repository, like the workspace: moving around and
+    /// opening one. Help is the only menu, as the others are all of ops on the repository.
+    pub(crate) fn works_without_repo(&self) -> bool {
+        matches!(
+            self,
+            Op::Quit
+                | Op::OpenMenu(Menu::Help)
+                | Op::Refresh
+                | Op::ToggleSection
+                | Op::MoveUp
+                | Op::MoveDown
+                | Op::MoveUpLine
+                | Op::MoveDownLine
+                | Op::MovePrevSection
+                | Op::MoveNextSection
+                | Op::MoveParentSection
+                | Op::HalfPageUp
+                | Op::HalfPageDown
+                | Op::Show
+                | Op::SwitchRepo
+                | Op::Filter
+                | Op::ClearFilter
+                | Op::ShowChangelog
+                | Op::ShowCmdLog
+                | Op::RecordMacro
+                | Op::ReplayMacro
+        )
+    }
+
+    /// Whether `RepeatLast` should run this again. Moving around, opening menus and the like don't count.
+    pub(crate) fn is_repeatable(&self) -> bool {
+        !matches!(
+            self,
+            Op::OpenMenu(_)
+                | Op::ToggleArg(_)
+                | Op::ToggleSection
+                | Op::ToggleWhitespaceHunks
+                | Op::ToggleExternalDiff
+                | Op::ToggleLineWrap
+                | Op::ToggleHunkBlame
+                | Op::CycleRefsSort
+                | Op::MoveUp
+                | Op::MoveDown
+                | Op::MoveUpLine
+                | Op::MoveDownLine
+                | Op::MovePrevSection
+                | Op::MoveNextSection
+                | Op::MoveParentSection
+                | Op::HalfPageUp
+                | Op::HalfPageDown
+                | Op::Refresh
+                | Op::HardRefresh
+                | Op::RepeatLast
+                | Op::RecordMacro
+                | Op::ReplayMacro
+                | Op::Quit
+        )
+    }
+}
+
 impl Display for Menu {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_str(match self {
@@ -181,6 +444,7 @@ impl Display for Menu {
             Menu::Pull => "Pull",
             Menu::Push => "Push",
             Menu::Rebase => "Rebase",
+            Menu::Remote => "Remote",
             Menu::Reset => "Reset",
             Menu::Revert => "Revert",
             Menu::Stash => "Stash",
@@ -193,8 +457,9 @@ pub(crate) fn create_y_n_prompt(mut action: Action, prompt: &'static str) -> Act
         if state.prompt.state.status().is_pending() {
             match state.prompt.state.value() {
                 "y" => {
-                    Rc::get_mut(&mut action).unwrap()(state, term)?;
+                    // Before the action, which may ask something else
                     state.prompt.reset(term)?;
+                    Rc::get_mut(&mut action).unwrap()(state, term)?;
                 }
                 "" => (),
                 _ => {
@@ -214,10 +479,63 @@ pub(crate) fn create_y_n_prompt(mut action: Action, prompt: &'static str) -> Act
             update_fn: update_fn.clone(),
         });
 
+        if state.repeating {
+            state.prompt.state.push('y');
+        }
+
         Ok(())
     })
 }
 
+/// Like `create_y_n_prompt`, but first showing what the action is about to affect.
+/// With nothing to preview, it's asked all the same.
+pub(crate) fn create_y_n_prompt_with_preview(
+    action: Action,
+    prompt: &'static str,
+    preview: Preview,
+) -> Action {
+    let mut action = Some(action);
+
+    Rc::new(move |state: &mut State, term: &mut Term| {
+        let action = action.take().expect("Action already taken");
+        let preview = preview(state)?;
+        if !preview.is_empty() {
+            state.prompt.preview = Some(preview);
+        }
+
+        Rc::get_mut(&mut create_y_n_prompt(action, prompt)).unwrap()(state, term)
+    })
+}
+
+/// Each file of a diff, followed by its hunks.
+pub(crate) fn diff_preview(config: &Config, deltas: &[Delta]) -> Vec<Line<'static>> {
+    deltas
+        .iter()
+        .flat_map(|delta| {
+            iter::once(Line::styled(
+                format!(
+                    "{}   {}",
+                    format!("{:?}", delta.status).to_lowercase(),
+                    delta.new_file.display()
+                ),
+                &config.style.file_header,
+            ))
+            .chain(
+                delta
+                    .hunks
+                    .iter()
+                    .flat_map(|hunk| hunk_preview(config, hunk)),
+            )
+        })
+        .collect()
+}
+
+pub(crate) fn hunk_preview(config: &Config, hunk: &Hunk) -> Vec<Line<'static>> {
+    iter::once(Line::styled(hunk.header.clone(), &config.style.hunk_header))
+        .chain(hunk.content.lines.iter().cloned())
+        .collect()
+}
+
 pub(crate) fn create_prompt(
     prompt: &'static str,
     on_success: fn(&mut State, &mut Term, &str) -> Res<()>,
@@ -268,7 +586,7 @@ pub(crate) fn set_prompt(
         prompt_text,
         update_fn: Rc::new(move |state, term| {
             if state.prompt.state.status().is_done() {
-                let input = state.prompt.state.value().to_string();
+                let input = state.prompt.input();
                 state.prompt.reset(term)?;
 
                 let default_value = default_fn(state);
@@ -289,9 +607,145 @@ pub(crate) fn set_prompt(
     });
 }
 
+pub(crate) fn create_picker_prompt(
+    prompt: &'static str,
+    candidates: fn(&State) -> Vec<String>,
+    on_success: fn(&mut State, &mut Term, &str) -> Res<()>,
+    create_default_value: fn(&State) -> Option<String>,
+    hide_menu: bool,
+) -> Action {
+    Rc::new(move |state: &mut State, _term: &mut Term| {
+        let candidates = candidates(state);
+        set_picker_prompt(
+            state,
+            prompt,
+            candidates,
+            Box::new(on_success),
+            Box::new(create_default_value),
+            hide_menu,
+        )
+    })
+}
+
+/// Like `set_prompt`, but with a fuzzy picker over `candidates`.
+/// Any default value is placed first, and is what an empty input is taken as.
+/// Without any candidates, it's a plain prompt.
+pub(crate) fn set_picker_prompt(
+    state: &mut State,
+    prompt: &'static str,
+    mut candidates: Vec<String>,
+    on_success: PromptAction,
+    default_fn: DefaultFn,
+    hide_menu: bool,
+) -> Res<()> {
+    if let Some(default) = default_fn(state) {
+        candidates.retain(|candidate| candidate != &default);
+        candidates.insert(0, default);
+    }
+
+    set_prompt(state, prompt, on_success, default_fn, hide_menu);
+    if !candidates.is_empty() {
+        state.prompt.picker = Some(PickerState::new(
+            candidates.into_iter().map(Cow::Owned).collect(),
+        ));
+    }
+    Ok(())
+}
+
+/// Like `set_picker_prompt`, but for choosing among `candidates` rather than typing something.
+/// The best match is always selected, the first candidate while nothing is typed.
+pub(crate) fn set_pick_only_prompt(
+    state: &mut State,
+    prompt: &'static str,
+    candidates: Vec<String>,
+    on_success: PromptAction,
+    hide_menu: bool,
+) -> Res<()> {
+    set_picker_prompt(
+        state,
+        prompt,
+        candidates,
+        on_success,
+        Box::new(|_| None),
+        hide_menu,
+    )?;
+
+    if let Some(picker) = &mut state.prompt.picker {
+        picker.set_pick_only();
+    }
+    Ok(())
+}
+
+/// How many of the latest commits are offered by `rev_candidates`.
+const RECENT_COMMIT_CANDIDATES: usize = 50;
+
+/// Revisions to pick from: local branches, remote branches, tags, then recent commits as "<short id> <summary>".
+/// Use `picked_rev` to get the revision back out of what was picked.
+pub(crate) fn rev_candidates(state: &State) -> Vec<String> {
+    let repo = state.repo();
+    let branches = [BranchType::Local, BranchType::Remote]
+        .into_iter()
+        .flat_map(|branch_type| repo.branches(Some(branch_type)).into_iter().flatten())
+        .filter_map(Result::ok)
+        .filter_map(|(branch, _)| branch.name().ok().flatten().map(str::to_string))
+        .filter(|name| !name.ends_with("/HEAD"));
+
+    let tags = repo.tag_names(None).into_iter().flat_map(|tags| {
+        tags.iter()
+            .flatten()
+            .map(str::to_string)
+            .collect::<Vec<_>>()
+    });
+
+    let commits = repo
+        .revwalk()
+        .and_then(|mut revwalk| revwalk.push_head().map(|()| revwalk))
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .take(RECENT_COMMIT_CANDIDATES)
+        .filter_map(|oid| repo.find_commit(oid).ok())
+        .filter_map(|commit| {
+            let short_id = commit.as_object().short_id().ok()?;
+            Some(format!(
+                "{} {}",
+                short_id.as_str()?,
+                commit.summary().unwrap_or_default()
+            ))
+        });
+
+    branches.chain(tags).chain(commits).collect()
+}
+
+pub(crate) fn remote_candidates(state: &State) -> Vec<String> {
+    state
+        .repo()
+        .remotes()
+        .map(|remotes| remotes.iter().flatten().map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+/// Remote-tracking branches, like "origin/main".
+pub(crate) fn remote_branch_candidates(state: &State) -> Vec<String> {
+    state
+        .repo()
+        .branches(Some(BranchType::Remote))
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .filter_map(|(branch, _)| branch.name().ok().flatten().map(str::to_string))
+        .filter(|name| !name.ends_with("/HEAD"))
+        .collect()
+}
+
+/// The revision of a candidate from `rev_candidates`, or of custom input.
+pub(crate) fn picked_rev(picked: &str) -> &str {
+    picked.split_whitespace().next().unwrap_or_default()
+}
+
 pub(crate) fn selected_rev(state: &State) -> Option<String> {
     match &state.screen().get_selected_item().target_data {
-        Some(TargetData::Branch(branch)) => Some(branch.to_owned()),
+        Some(TargetData::Branch(branch) | TargetData::Tag(branch)) => Some(branch.to_owned()),
         Some(TargetData::Commit(commit)) => Some(commit.to_owned()),
         _ => None,
     }
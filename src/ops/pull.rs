@@ -20,14 +20,14 @@ pub(crate) struct PullFromPushRemote;
 impl OpTrait for PullFromPushRemote {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
         Some(Rc::new(
-            |state: &mut State, term: &mut Term| match get_push_remote(&state.repo)? {
+            |state: &mut State, term: &mut Term| match get_push_remote(state.repo())? {
                 None => {
                     let mut prompt =
                         create_prompt("Set pushRemote then pull", set_push_remote_and_pull, true);
                     Rc::get_mut(&mut prompt).unwrap()(state, term)
                 }
                 Some(push_remote) => {
-                    let refspec = git::get_head(&state.repo)?;
+                    let refspec = git::get_head(state.repo())?;
                     pull(state, term, &[&push_remote, &refspec])
                 }
             },
@@ -35,7 +35,7 @@ impl OpTrait for PullFromPushRemote {
     }
 
     fn display(&self, state: &State) -> String {
-        match get_push_remote(&state.repo) {
+        match get_push_remote(state.repo()) {
             Ok(Some(remote)) => format!("from {}", remote),
             Ok(None) => "pushRemote, setting that".into(),
             Err(e) => format!("error: {}", e),
@@ -44,7 +44,7 @@ impl OpTrait for PullFromPushRemote {
 }
 
 fn set_push_remote_and_pull(state: &mut State, term: &mut Term, push_remote_name: &str) -> Res<()> {
-    let repo = state.repo.clone();
+    let repo = state.repo().clone();
     let push_remote = repo
         .find_remote(push_remote_name)
         .map_err(|_| "Invalid pushRemote")?;
@@ -60,7 +60,7 @@ pub(crate) struct PullFromUpstream;
 impl OpTrait for PullFromUpstream {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
         Some(Rc::new(
-            |state: &mut State, term: &mut Term| match get_upstream_components(&state.repo)? {
+            |state: &mut State, term: &mut Term| match get_upstream_components(state.repo())? {
                 None => {
                     let mut prompt =
                         create_prompt("Set upstream then pull", set_upstream_and_pull, true);
@@ -75,7 +75,7 @@ impl OpTrait for PullFromUpstream {
     }
 
     fn display(&self, state: &State) -> String {
-        match get_upstream_shortname(&state.repo) {
+        match get_upstream_shortname(state.repo()) {
             Ok(Some(upstream)) => format!("from {}", upstream),
             Ok(None) => "upstream, setting that".into(),
             Err(e) => format!("error: {}", e),
@@ -88,7 +88,7 @@ fn set_upstream_and_pull(state: &mut State, term: &mut Term, upstream_name: &str
     cmd.args(["branch", "--set-upstream-to", upstream_name]);
     state.run_cmd(term, &[], cmd)?;
 
-    let Some((remote, branch)) = get_upstream_components(&state.repo)? else {
+    let Some((remote, branch)) = get_upstream_components(state.repo())? else {
         return Ok(());
     };
 
@@ -1,15 +1,18 @@
-use super::{create_prompt, Action, OpTrait};
+use super::{create_prompt, create_y_n_prompt_with_preview, Action, OpTrait};
 use crate::git;
 use crate::git::remote::{
     get_push_remote, get_upstream_components, get_upstream_shortname, set_push_remote,
+    unpushed_commits,
 };
 use crate::{items::TargetData, menu::arg::Arg, state::State, term::Term, Res};
-use std::{process::Command, rc::Rc};
+use git2::{Oid, Repository};
+use ratatui::text::{Line, Span};
+use std::{iter, process::Command, rc::Rc};
 
 pub(crate) fn init_args() -> Vec<Arg> {
     vec![
         Arg::new_flag("--force-with-lease", "Force with lease", false),
-        Arg::new_flag("--force", "Force", false),
+        Arg::new_flag("--force", "Force", false).transient(),
         Arg::new_flag("--no-verify", "Disable hooks", false),
         Arg::new_flag("--dry-run", "Dry run", false),
     ]
@@ -19,14 +22,14 @@ pub(crate) struct PushToPushRemote;
 impl OpTrait for PushToPushRemote {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
         Some(Rc::new(
-            |state: &mut State, term: &mut Term| match get_push_remote(&state.repo)? {
+            |state: &mut State, term: &mut Term| match get_push_remote(state.repo())? {
                 None => {
                     let mut prompt =
                         create_prompt("Set pushRemote then push", set_push_remote_and_push, true);
                     Rc::get_mut(&mut prompt).unwrap()(state, term)
                 }
                 Some(push_remote) => {
-                    let head_ref = git::get_head(&state.repo)?;
+                    let head_ref = git::get_head(state.repo())?;
                     let refspec = format!("{0}:{0}", head_ref);
                     push(state, term, &[&push_remote, &refspec])
                 }
@@ -35,7 +38,7 @@ impl OpTrait for PushToPushRemote {
     }
 
     fn display(&self, state: &State) -> String {
-        match get_push_remote(&state.repo) {
+        match get_push_remote(state.repo()) {
             Ok(Some(remote)) => format!("to {}", remote),
             Ok(None) => "pushRemote, setting that".into(),
             Err(e) => format!("error: {}", e),
@@ -44,7 +47,7 @@ impl OpTrait for PushToPushRemote {
 }
 
 fn set_push_remote_and_push(state: &mut State, term: &mut Term, push_remote_name: &str) -> Res<()> {
-    let repo = state.repo.clone();
+    let repo = state.repo().clone();
     let push_remote = repo
         .find_remote(push_remote_name)
         .map_err(|_| "Invalid pushRemote")?;
@@ -52,7 +55,7 @@ fn set_push_remote_and_push(state: &mut State, term: &mut Term, push_remote_name
     // TODO Would be nice to have the command visible in the log. Resort to `git config`?
     set_push_remote(&repo, Some(&push_remote)).map_err(|_| "Could not set pushRemote config")?;
 
-    let head_ref = git::get_head(&state.repo)?;
+    let head_ref = git::get_head(state.repo())?;
     let refspec = format!("{0}:{0}", head_ref);
     push(state, term, &[push_remote_name, &refspec])
 }
@@ -61,7 +64,7 @@ pub(crate) struct PushToUpstream;
 impl OpTrait for PushToUpstream {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
         Some(Rc::new(
-            |state: &mut State, term: &mut Term| match get_upstream_components(&state.repo)? {
+            |state: &mut State, term: &mut Term| match get_upstream_components(state.repo())? {
                 None => {
                     let mut prompt =
                         create_prompt("Set upstream then push", set_upstream_and_push, true);
@@ -73,7 +76,7 @@ impl OpTrait for PushToUpstream {
     }
 
     fn display(&self, state: &State) -> String {
-        match get_upstream_shortname(&state.repo) {
+        match get_upstream_shortname(state.repo()) {
             Ok(Some(upstream)) => format!("to {}", upstream),
             Ok(None) => "upstream, setting that".into(),
             Err(e) => format!("error: {}", e),
@@ -86,7 +89,7 @@ fn set_upstream_and_push(state: &mut State, term: &mut Term, upstream_name: &str
     cmd.args(["branch", "--set-upstream-to", upstream_name]);
     state.run_cmd(term, &[], cmd)?;
 
-    let Some((remote, branch)) = get_upstream_components(&state.repo)? else {
+    let Some((remote, branch)) = get_upstream_components(state.repo())? else {
         return Ok(());
     };
 
@@ -109,18 +112,133 @@ fn push_elsewhere(state: &mut State, term: &mut Term, remote: &str) -> Res<()> {
 }
 
 fn push_head_to(state: &mut State, term: &mut Term, remote: &str, branch: &str) -> Res<()> {
-    let head_ref = git::get_head(&state.repo)?;
+    let head_ref = git::get_head(state.repo())?;
     let refspec = format!("{}:refs/heads/{}", head_ref, branch);
     push(state, term, &[remote, &refspec])
 }
 
 fn push(state: &mut State, term: &mut Term, extra_args: &[&str]) -> Res<()> {
-    let mut cmd = Command::new("git");
-    cmd.args(["push"]);
-    cmd.args(state.pending_menu.as_ref().unwrap().args());
-    cmd.args(extra_args);
+    let args = state.pending_menu.as_ref().unwrap().args();
+    let extra_args = extra_args
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect::<Vec<_>>();
+    let remote = extra_args[0].clone();
+    let dry_run = args.iter().any(|arg| arg == "--dry-run");
+    let force = args
+        .iter()
+        .any(|arg| arg == "--force" || arg.to_string_lossy().starts_with("--force-with-lease"));
+    // Without forcing, the remote rejects a push that would drop anything
+    let dropped_tip = remote_tip(state.repo(), &remote, extra_args.get(1))
+        .filter(|&tip| force && !is_pushed_over(state.repo(), tip));
+
+    let prompt = if dropped_tip.is_some() {
+        "Push, dropping commits from the remote?"
+    } else if force {
+        "Really force push?"
+    } else {
+        "Push these commits?"
+    };
+    let confirm = !dry_run && state.config.push.confirm.enabled;
 
     state.close_menu();
-    state.run_cmd_async(term, &[], cmd)?;
-    Ok(())
+
+    let mut action: Action = Rc::new(move |state: &mut State, term: &mut Term| {
+        let mut cmd = Command::new("git");
+        cmd.args(["push"]);
+        cmd.args(&args);
+        cmd.args(&extra_args);
+        state.run_cmd_async(term, &[], cmd)
+    });
+
+    if confirm {
+        action = create_y_n_prompt_with_preview(
+            action,
+            prompt,
+            Rc::new(move |state| {
+                let mut preview = commits_to_push(state, &remote)?;
+                if let Some(tip) = dropped_tip {
+                    preview.extend(commits_to_drop(state, tip)?);
+                }
+                Ok(preview)
+            }),
+        );
+    }
+
+    Rc::get_mut(&mut action).unwrap()(state, term)
+}
+
+/// The last fetched tip of the remote branch pushed to, by the destination of `refspec`,
+/// or the current branch's name without one.
+fn remote_tip(repo: &Repository, remote: &str, refspec: Option<&String>) -> Option<Oid> {
+    let branch = match refspec {
+        Some(refspec) => {
+            let (_, dst) = refspec.split_once(':')?;
+            dst.strip_prefix("refs/heads/").unwrap_or(dst).to_string()
+        }
+        None => repo.head().ok()?.shorthand()?.to_string(),
+    };
+
+    repo.refname_to_id(&format!("refs/remotes/{}/{}", remote, branch))
+        .ok()
+}
+
+/// Whether HEAD contains `tip`, so pushing it doesn't drop anything from the remote.
+fn is_pushed_over(repo: &Repository, tip: Oid) -> bool {
+    let Some(head) = repo.head().ok().and_then(|head| head.target()) else {
+        return true;
+    };
+
+    head == tip || repo.graph_descendant_of(head, tip).unwrap_or(false)
+}
+
+/// Commits on the remote branch that aren't in HEAD, which a force push would drop.
+fn commits_to_drop(state: &State, tip: Oid) -> Res<Vec<Line<'static>>> {
+    let style = &state.config.style;
+    let mut revwalk = state.repo().revwalk()?;
+    revwalk.push(tip)?;
+    revwalk.hide_head()?;
+
+    let commits = revwalk
+        .map(|oid| commit_line(state, oid?))
+        .collect::<Res<Vec<_>>>()?;
+
+    Ok(iter::once(Line::styled(
+        format!("Commits dropped from the remote ({})", commits.len()),
+        &style.section_header,
+    ))
+    .chain(commits)
+    .collect())
+}
+
+fn commit_line(state: &State, oid: Oid) -> Res<Line<'static>> {
+    let commit = state.repo().find_commit(oid)?;
+    let short_id = commit.as_object().short_id()?;
+    Ok(Line::from(vec![
+        Span::styled(
+            short_id.as_str().unwrap_or("").to_string(),
+            &state.config.style.hash,
+        ),
+        Span::raw(format!(" {}", commit.summary().unwrap_or(""))),
+    ]))
+}
+
+/// Listed before confirming a push with `push.confirm`, to catch a WIP commit going along.
+fn commits_to_push(state: &State, remote: &str) -> Res<Vec<Line<'static>>> {
+    let style = &state.config.style;
+    let commits = unpushed_commits(state.repo(), remote)?
+        .into_iter()
+        .map(|oid| commit_line(state, oid))
+        .collect::<Res<Vec<_>>>()?;
+
+    if commits.is_empty() {
+        return Ok(vec![]);
+    }
+
+    Ok(iter::once(Line::styled(
+        format!("Commits to push ({})", commits.len()),
+        &style.section_header,
+    ))
+    .chain(commits)
+    .collect())
 }
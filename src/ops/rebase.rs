@@ -1,5 +1,5 @@
-use super::{create_prompt_with_default, selected_rev, Action, OpTrait};
-use crate::{items::TargetData, menu::arg::Arg, state::State, term::Term, Res};
+use super::{create_picker_prompt, picked_rev, rev_candidates, selected_rev, Action, OpTrait};
+use crate::{git, items::TargetData, menu::arg::Arg, state::State, term::Term, Res};
 use std::{
     ffi::{OsStr, OsString},
     process::Command,
@@ -61,8 +61,9 @@ impl OpTrait for RebaseAbort {
 pub(crate) struct RebaseElsewhere;
 impl OpTrait for RebaseElsewhere {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
-        Some(create_prompt_with_default(
+        Some(create_picker_prompt(
             "Rebase onto",
+            rev_candidates,
             rebase_elsewhere,
             selected_rev,
             true,
@@ -78,7 +79,7 @@ fn rebase_elsewhere(state: &mut State, term: &mut Term, rev: &str) -> Res<()> {
     let mut cmd = Command::new("git");
     cmd.arg("rebase");
     cmd.args(state.pending_menu.as_ref().unwrap().args());
-    cmd.arg(rev);
+    cmd.arg(picked_rev(rev));
 
     state.close_menu();
     state.run_cmd_interactive(term, cmd)?;
@@ -89,7 +90,7 @@ pub(crate) struct RebaseInteractive;
 impl OpTrait for RebaseInteractive {
     fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
         let action = match target {
-            Some(TargetData::Commit(r) | TargetData::Branch(r)) => {
+            Some(TargetData::Commit(r) | TargetData::Branch(r) | TargetData::Tag(r)) => {
                 let rev = OsString::from(r);
                 Rc::new(move |state: &mut State, term: &mut Term| {
                     let args = state.pending_menu.as_ref().unwrap().args();
@@ -129,7 +130,7 @@ pub(crate) struct RebaseAutosquash;
 impl OpTrait for RebaseAutosquash {
     fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
         let action = match target {
-            Some(TargetData::Commit(r) | TargetData::Branch(r)) => {
+            Some(TargetData::Commit(r) | TargetData::Branch(r) | TargetData::Tag(r)) => {
                 let rev = OsString::from(r);
                 Rc::new(move |state: &mut State, term: &mut Term| {
                     let args = state.pending_menu.as_ref().unwrap().args();
@@ -151,6 +152,38 @@ impl OpTrait for RebaseAutosquash {
     }
 }
 
+pub(crate) struct AutosquashFixups;
+impl OpTrait for AutosquashFixups {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            state.close_menu();
+
+            let (base, fixups) = git::autosquash_commits(state.repo())?
+                .ok_or("The current branch has no upstream")?;
+            if fixups.is_empty() {
+                return Err("No fixup!, squash! or amend! commits to autosquash".into());
+            }
+
+            let mut cmd = Command::new("git");
+            cmd.args([
+                "rebase",
+                "-i",
+                "-q",
+                "--autostash",
+                "--keep-empty",
+                "--autosquash",
+            ]);
+            cmd.arg(base.to_string());
+            cmd.env("GIT_SEQUENCE_EDITOR", ":");
+            state.run_cmd(term, &[], cmd)
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Autosquash fixups".into()
+    }
+}
+
 fn rebase_autosquash_cmd(args: &[OsString], rev: &OsStr) -> Command {
     let mut cmd = Command::new("git");
     cmd.args(["rebase", "-i", "--autosquash", "--keep-empty"]);
@@ -0,0 +1,98 @@
+use super::{set_prompt, Action, OpTrait};
+use crate::{items::TargetData, state::State, term::Term, Res};
+use std::{process::Command, rc::Rc};
+
+pub(crate) struct SetRemoteUrl;
+impl OpTrait for SetRemoteUrl {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        set_url_prompt(target, "Set url", false)
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Set url".into()
+    }
+}
+
+pub(crate) struct SetRemotePushUrl;
+impl OpTrait for SetRemotePushUrl {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        set_url_prompt(target, "Set push url", true)
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Set push url".into()
+    }
+}
+
+fn set_url_prompt(target: Option<&TargetData>, prompt: &'static str, push: bool) -> Option<Action> {
+    let Some(TargetData::Remote(name)) = target else {
+        return None;
+    };
+
+    let name = name.clone();
+    Some(Rc::new(move |state: &mut State, _term: &mut Term| {
+        let name = name.clone();
+        set_prompt(
+            state,
+            prompt,
+            Box::new(move |state, term, url| set_url(state, term, &name, url, push)),
+            Box::new(|_| None),
+            true,
+        );
+        Ok(())
+    }))
+}
+
+fn set_url(state: &mut State, term: &mut Term, name: &str, url: &str, push: bool) -> Res<()> {
+    if url.is_empty() {
+        state.close_menu();
+        return Err("No url given".into());
+    }
+
+    let mut cmd = Command::new("git");
+    cmd.args(["remote", "set-url"]);
+    if push {
+        cmd.arg("--push");
+    }
+    cmd.args([name, url]);
+
+    state.close_menu();
+    state.run_cmd(term, &[], cmd)
+}
+
+/// Lists the urls of a remote, and which of its branches are fetched.
+pub(crate) fn show_details(name: String) -> Option<Action> {
+    Some(Rc::new(move |state: &mut State, _term: &mut Term| {
+        state.close_menu();
+
+        let (url, push_url) = {
+            let remote = state.repo().find_remote(&name)?;
+            let url = remote.url().unwrap_or_default().to_string();
+            let push_url = remote.pushurl().unwrap_or(&url).to_string();
+            (url, push_url)
+        };
+
+        let prefix = format!("{}/", name);
+        let tracked = state
+            .repo()
+            .references_glob(&format!("refs/remotes/{}/*", name))?
+            .filter_map(Result::ok)
+            .filter_map(|reference| reference.shorthand().map(str::to_string))
+            .filter_map(|shorthand| shorthand.strip_prefix(&prefix).map(str::to_string))
+            .filter(|branch| branch != "HEAD")
+            .collect::<Vec<_>>();
+
+        state.display_info(format!("{} fetch url: {}", name, url));
+        state.display_info(format!("{} push url: {}", name, push_url));
+        state.display_info(format!("{} tracked branches: {}", name, tracked.join(", ")));
+        Ok(())
+    }))
+}
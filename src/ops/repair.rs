@@ -0,0 +1,137 @@
+use super::{create_y_n_prompt_with_preview, Action, OpTrait, Preview};
+use crate::{items::TargetData, state::State, term::Term, Res};
+use git2::Repository;
+use ratatui::text::Line;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+/// A file left behind by a git process that didn't finish.
+struct Leftover {
+    path: PathBuf,
+    explanation: &'static str,
+}
+
+pub(crate) struct Repair;
+impl OpTrait for Repair {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        let preview: Preview = Rc::new(|state| {
+            Ok(find_leftovers(state.repo())
+                .into_iter()
+                .flat_map(|leftover| {
+                    [
+                        Line::styled(
+                            display_path(state.repo(), &leftover.path),
+                            &state.config.style.file_header,
+                        ),
+                        Line::raw(format!("  {}", leftover.explanation)),
+                    ]
+                })
+                .collect())
+        });
+
+        Some(Rc::new(move |state: &mut State, term: &mut Term| {
+            if find_leftovers(state.repo()).is_empty() {
+                state.close_menu();
+                return Err("Nothing to repair".into());
+            }
+
+            Rc::get_mut(&mut create_y_n_prompt_with_preview(
+                Rc::new(remove_leftovers),
+                "Remove these?",
+                Rc::clone(&preview),
+            ))
+            .unwrap()(state, term)
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Repair".into()
+    }
+}
+
+fn remove_leftovers(state: &mut State, _term: &mut Term) -> Res<()> {
+    state.close_menu();
+
+    let leftovers = find_leftovers(state.repo());
+    if leftovers.is_empty() {
+        return Err("Nothing to repair".into());
+    }
+
+    for leftover in leftovers {
+        let path = display_path(state.repo(), &leftover.path);
+        state.run_in_process(&format!("remove {}", path), |_state| {
+            if leftover.path.is_dir() {
+                fs::remove_dir_all(&leftover.path)
+            } else {
+                fs::remove_file(&leftover.path)
+            }
+            .map_err(Into::into)
+        })?;
+
+        state.display_info(format!("Removed {}", path));
+    }
+
+    state.screen_mut().update()
+}
+
+/// Relative to the worktree, as the git directory usually is in it.
+fn display_path(repo: &Repository, path: &Path) -> String {
+    repo.workdir()
+        .and_then(|workdir| path.strip_prefix(workdir).ok())
+        .unwrap_or(path)
+        .display()
+        .to_string()
+}
+
+fn find_leftovers(repo: &Repository) -> Vec<Leftover> {
+    let git_dir = repo.path();
+    let exists = |name: &str| git_dir.join(name).exists();
+    let mut leftovers = vec![];
+
+    if exists("index.lock") {
+        leftovers.push(Leftover {
+            path: git_dir.join("index.lock"),
+            explanation:
+                "Lock left by a git process that crashed, only remove it if no git is running",
+        });
+    }
+
+    // An ongoing rebase always has these, a crashed one may not.
+    // A stopped `git am` uses rebase-apply too, but without them.
+    let mut ongoing_rebase = false;
+    for rebase_dir in ["rebase-merge", "rebase-apply"] {
+        let dir = git_dir.join(rebase_dir);
+        let has = |name: &str| dir.join(name).exists();
+        let is_rebase = has("head-name") && has("onto");
+        let is_am = has("applying") || (has("next") && has("last"));
+        ongoing_rebase |= dir.is_dir() && (is_rebase || is_am);
+        if dir.is_dir() && !is_rebase && !is_am {
+            leftovers.push(Leftover {
+                path: dir,
+                explanation: "Incomplete rebase state, that can't be continued or aborted",
+            });
+        }
+    }
+
+    // Rebases stopped on a conflict and conflicted `merge --squash`es have one too
+    let in_use = [
+        "MERGE_HEAD",
+        "CHERRY_PICK_HEAD",
+        "REVERT_HEAD",
+        "REBASE_HEAD",
+        "SQUASH_MSG",
+    ];
+
+    if exists("MERGE_MSG") && !ongoing_rebase && !in_use.iter().any(|name| exists(name)) {
+        leftovers.push(Leftover {
+            path: git_dir.join("MERGE_MSG"),
+            explanation:
+                "Message of a merge that isn't in progress, would be offered to the next commit",
+        });
+    }
+
+    leftovers
+}
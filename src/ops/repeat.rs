@@ -0,0 +1,83 @@
+use super::{Action, OpTrait};
+use crate::{items::TargetData, state::State, term::Term};
+use std::rc::Rc;
+
+pub(crate) struct RepeatLast;
+impl OpTrait for RepeatLast {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            state.close_menu();
+            let op = state.last_op.clone().ok_or("Nothing to repeat yet")?;
+
+            state.repeating = true;
+            let result = state.handle_op(op, term);
+            state.repeating = false;
+            result
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Repeat last action".into()
+    }
+}
+
+pub(crate) struct RecordMacro;
+impl OpTrait for RecordMacro {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            state.close_menu();
+
+            match state.macro_recording.take() {
+                Some(mut keys) => {
+                    // Leave out the keys that stopped the recording
+                    keys.truncate(keys.len().saturating_sub(state.pending_keys.len()));
+                    state.display_info(format!("Recorded {} keys", keys.len()));
+                    state.recorded_macro = keys;
+                }
+                None => {
+                    state.macro_recording = Some(vec![]);
+                    state.display_info("Recording keys...".into());
+                }
+            }
+
+            Ok(())
+        }))
+    }
+
+    fn display(&self, state: &State) -> String {
+        if state.macro_recording.is_some() {
+            "Stop recording keys".into()
+        } else {
+            "Record keys".into()
+        }
+    }
+}
+
+pub(crate) struct ReplayMacro;
+impl OpTrait for ReplayMacro {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            state.close_menu();
+
+            if let Some(keys) = &mut state.macro_recording {
+                keys.truncate(keys.len().saturating_sub(state.pending_keys.len()));
+                return Err("Can't replay keys while recording them".into());
+            }
+
+            // Taken while replaying, so a replay within it has nothing to replay
+            let keys = std::mem::take(&mut state.recorded_macro);
+            if keys.is_empty() {
+                return Err("No keys recorded yet".into());
+            }
+
+            state.pending_keys.clear();
+            let result = state.replay_keys(term, &keys);
+            state.recorded_macro = keys;
+            result
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Replay recorded keys".into()
+    }
+}
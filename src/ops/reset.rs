@@ -1,6 +1,10 @@
-use super::{create_prompt_with_default, selected_rev, OpTrait};
-use crate::{items::TargetData, menu::arg::Arg, state::State, term::Term, Action, Res};
-use std::process::Command;
+use super::{
+    create_picker_prompt, create_y_n_prompt_with_preview, picked_rev, rev_candidates, selected_rev,
+    OpTrait,
+};
+use crate::{git, items::TargetData, menu::arg::Arg, state::State, term::Term, Action, Res};
+use ratatui::text::{Line, Span};
+use std::{process::Command, rc::Rc};
 
 pub(crate) fn init_args() -> Vec<Arg> {
     vec![]
@@ -9,8 +13,9 @@ pub(crate) fn init_args() -> Vec<Arg> {
 pub(crate) struct ResetSoft;
 impl OpTrait for ResetSoft {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
-        Some(create_prompt_with_default(
+        Some(create_picker_prompt(
             "Soft reset to",
+            rev_candidates,
             reset_soft,
             selected_rev,
             true,
@@ -26,7 +31,7 @@ fn reset_soft(state: &mut State, term: &mut Term, input: &str) -> Res<()> {
     let mut cmd = Command::new("git");
     cmd.args(["reset", "--soft"]);
     cmd.args(state.pending_menu.as_ref().unwrap().args());
-    cmd.arg(input);
+    cmd.arg(picked_rev(input));
 
     state.close_menu();
     state.run_cmd(term, &[], cmd)
@@ -35,8 +40,9 @@ fn reset_soft(state: &mut State, term: &mut Term, input: &str) -> Res<()> {
 pub(crate) struct ResetMixed;
 impl OpTrait for ResetMixed {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
-        Some(create_prompt_with_default(
+        Some(create_picker_prompt(
             "Mixed reset to",
+            rev_candidates,
             reset_mixed,
             selected_rev,
             true,
@@ -52,7 +58,7 @@ fn reset_mixed(state: &mut State, term: &mut Term, input: &str) -> Res<()> {
     let mut cmd = Command::new("git");
     cmd.args(["reset", "--mixed"]);
     cmd.args(state.pending_menu.as_ref().unwrap().args());
-    cmd.arg(input);
+    cmd.arg(picked_rev(input));
 
     state.close_menu();
     state.run_cmd(term, &[], cmd)
@@ -61,8 +67,9 @@ fn reset_mixed(state: &mut State, term: &mut Term, input: &str) -> Res<()> {
 pub(crate) struct ResetHard;
 impl OpTrait for ResetHard {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
-        Some(create_prompt_with_default(
+        Some(create_picker_prompt(
             "Hard reset to",
+            rev_candidates,
             reset_hard,
             selected_rev,
             true,
@@ -75,11 +82,64 @@ impl OpTrait for ResetHard {
 }
 
 fn reset_hard(state: &mut State, term: &mut Term, input: &str) -> Res<()> {
-    let mut cmd = Command::new("git");
-    cmd.args(["reset", "--hard"]);
-    cmd.args(state.pending_menu.as_ref().unwrap().args());
-    cmd.arg(input);
+    let args = state.pending_menu.as_ref().unwrap().args();
+    let target = picked_rev(input).to_string();
+
+    let action: Action = Rc::new(move |state: &mut State, term: &mut Term| {
+        let mut cmd = Command::new("git");
+        cmd.args(["reset", "--hard"]);
+        cmd.args(&args);
+        cmd.arg(&target);
+
+        state.run_cmd(term, &[], cmd)
+    });
+
+    let target = picked_rev(input).to_string();
+    let preview = Rc::new(move |state: &State| reset_hard_preview(state, &target));
 
     state.close_menu();
-    state.run_cmd(term, &[], cmd)
+    Rc::get_mut(&mut create_y_n_prompt_with_preview(
+        action,
+        "Really hard reset?",
+        preview,
+    ))
+    .unwrap()(state, term)
+}
+
+/// The commits that would leave the current branch, and all uncommitted changes.
+fn reset_hard_preview(state: &State, target: &str) -> Res<Vec<Line<'static>>> {
+    let repo = state.repo();
+    let style = &state.config.style;
+
+    let mut revwalk = repo.revwalk()?;
+    if revwalk.push_head().is_ok() {
+        revwalk.hide(repo.revparse_single(target)?.peel_to_commit()?.id())?;
+    }
+
+    let mut preview = revwalk
+        .map(|oid| -> Res<Line<'static>> {
+            let commit = repo.find_commit(oid?)?;
+            Ok(Line::from(vec![
+                Span::styled(
+                    commit
+                        .as_object()
+                        .short_id()?
+                        .as_str()
+                        .unwrap_or("")
+                        .to_string(),
+                    &style.hash,
+                ),
+                Span::raw(format!(" {}", commit.summary().unwrap_or(""))),
+            ]))
+        })
+        .collect::<Res<Vec<_>>>()?;
+
+    for diff in [
+        git::diff_staged(&state.config, repo, None)?,
+        git::diff_unstaged(&state.config, repo, None)?,
+    ] {
+        preview.extend(super::diff_preview(&state.config, &diff.deltas));
+    }
+
+    Ok(preview)
 }
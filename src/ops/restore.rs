@@ -0,0 +1,64 @@
+use super::{create_y_n_prompt, Action, OpTrait};
+use crate::{items::TargetData, state::State, term::Term};
+use std::{path::PathBuf, process::Command, rc::Rc};
+
+/// `git restore`: Puts the file back the way it is in the index, keeping what's staged, unlike `Discard`.
+pub(crate) struct RestoreFile;
+impl OpTrait for RestoreFile {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        Some(create_y_n_prompt(
+            restore(restored_paths(target)?, false),
+            "Really restore from the index?",
+        ))
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Restore file".into()
+    }
+}
+
+/// `git restore --staged`: Puts the file in the index back the way it is in HEAD, leaving the worktree be.
+pub(crate) struct RestoreFileStaged;
+impl OpTrait for RestoreFileStaged {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        Some(restore(restored_paths(target)?, true))
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Restore staged file".into()
+    }
+}
+
+/// Both sides of a rename, so that it's restored as a whole.
+fn restored_paths(target: Option<&TargetData>) -> Option<Vec<PathBuf>> {
+    match target {
+        Some(TargetData::Delta(delta)) if delta.old_file != delta.new_file => {
+            Some(vec![delta.old_file.clone(), delta.new_file.clone()])
+        }
+        Some(TargetData::Delta(delta)) => Some(vec![delta.new_file.clone()]),
+        _ => None,
+    }
+}
+
+fn restore(paths: Vec<PathBuf>, staged: bool) -> Action {
+    Rc::new(move |state: &mut State, term: &mut Term| {
+        let mut cmd = Command::new("git");
+        cmd.arg("restore");
+        if staged {
+            cmd.arg("--staged");
+        }
+        cmd.arg("--");
+        cmd.args(&paths);
+
+        state.close_menu();
+        state.run_cmd(term, &[], cmd)
+    })
+}
@@ -2,7 +2,7 @@ use std::{process::Command, rc::Rc};
 
 use crate::{items::TargetData, menu::arg::Arg, state::State, term::Term, Res};
 
-use super::{create_prompt_with_default, selected_rev, Action, OpTrait};
+use super::{create_picker_prompt, picked_rev, rev_candidates, selected_rev, Action, OpTrait};
 
 pub(crate) fn init_args() -> Vec<Arg> {
     vec![
@@ -52,8 +52,9 @@ impl OpTrait for RevertContinue {
 pub(crate) struct RevertCommit;
 impl OpTrait for RevertCommit {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
-        Some(create_prompt_with_default(
+        Some(create_picker_prompt(
             "Revert commit",
+            rev_candidates,
             revert_commit,
             selected_rev,
             true,
@@ -69,7 +70,7 @@ fn revert_commit(state: &mut State, term: &mut Term, input: &str) -> Res<()> {
     let mut cmd = Command::new("git");
     cmd.args(["revert"]);
     cmd.args(state.pending_menu.as_ref().unwrap().args());
-    cmd.arg(input);
+    cmd.arg(picked_rev(input));
 
     state.close_menu();
     state.run_cmd_interactive(term, cmd)
@@ -1,16 +1,39 @@
-use super::OpTrait;
-use crate::{items::TargetData, screen, state::State, Action};
-use std::{path::Path, process::Command, rc::Rc};
+use super::{
+    create_prompt_with_default, git_config, remote, selected_rev, set_pick_only_prompt,
+    switch_repo, OpTrait,
+};
+use crate::{
+    items::{self, TargetData},
+    review, screen,
+    state::State,
+    term::Term,
+    Action, Res,
+};
+use git2::{Oid, Repository};
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+    rc::Rc,
+};
 
 pub(crate) struct Show;
 impl OpTrait for Show {
     fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
         match target {
-            Some(TargetData::Commit(r) | TargetData::Branch(r)) => goto_show_screen(r.clone()),
-            Some(TargetData::File(u)) => editor(u.as_path(), None),
+            Some(TargetData::Commit(r) | TargetData::Branch(r) | TargetData::Tag(r)) => {
+                goto_show_screen(r.clone())
+            }
+            Some(TargetData::File(u) | TargetData::Unmerged(u)) => editor(u.as_path(), None),
             Some(TargetData::Delta(d)) => editor(d.new_file.as_path(), None),
             Some(TargetData::Hunk(h)) => editor(h.new_file.as_path(), Some(h.first_diff_line())),
+            Some(TargetData::HunkLine(h, i)) => {
+                editor(h.new_file.as_path(), Some(h.new_line_number(*i)))
+            }
             Some(TargetData::Stash { id: _, commit }) => goto_show_screen(commit.clone()),
+            Some(TargetData::Remote(name)) => remote::show_details(name.clone()),
+            Some(TargetData::FileStat(path)) => goto_file_diff(path.clone()),
+            Some(TargetData::Repo(path)) => switch_repo::open(path.clone()),
+            Some(TargetData::GitConfig(key)) => git_config::edit(key.clone()),
             _ => None,
         }
     }
@@ -29,7 +52,7 @@ fn goto_show_screen(r: String) -> Option<Action> {
         state.screens.push(
             screen::show::create(
                 Rc::clone(&state.config),
-                Rc::clone(&state.repo),
+                Rc::clone(state.repo()),
                 term.size()?,
                 r.clone(),
             )
@@ -39,10 +62,211 @@ fn goto_show_screen(r: String) -> Option<Action> {
     }))
 }
 
+/// Moves the cursor from a file's line in a diffstat down to its diff.
+fn goto_file_diff(path: PathBuf) -> Option<Action> {
+    Some(Rc::new(move |state, _term| {
+        state.close_menu();
+        let found = state.screen_mut().select_matching(true, |item| {
+            matches!(&item.target_data, Some(TargetData::Delta(delta)) if delta.new_file == path)
+        });
+
+        if !found {
+            return Err(format!("No diff of {}", path.to_string_lossy()).into());
+        }
+
+        Ok(())
+    }))
+}
+
+pub(crate) struct ShowRange;
+impl OpTrait for ShowRange {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_prompt_with_default(
+            "Diff range",
+            show_range,
+            default_range,
+            true,
+        ))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "diff range".into()
+    }
+}
+
+/// From the selected commit up until now.
+fn default_range(state: &State) -> Option<String> {
+    selected_rev(state).map(|rev| format!("{}..HEAD", rev))
+}
+
+fn show_range(state: &mut State, term: &mut Term, input: &str) -> Res<()> {
+    state.close_menu();
+    if !screen::show::is_range(input) {
+        return Err(format!("{} isn't a range like A..B", input).into());
+    }
+
+    let screen = screen::show::create_range(
+        Rc::clone(&state.config),
+        Rc::clone(state.repo()),
+        term.size()?,
+        input.to_string(),
+    )?;
+
+    state.screens.push(screen);
+    Ok(())
+}
+
+pub(crate) struct ShowSinceBase;
+impl OpTrait for ShowSinceBase {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            let since = super::log::required_branch_point(state)?;
+            let since = state.repo().find_object(since, None)?.short_id()?;
+            show_range(
+                state,
+                term,
+                &format!("{}..HEAD", since.as_str().unwrap_or_default()),
+            )
+        }))
+    }
+
+    fn display(&self, state: &State) -> String {
+        format!("diff since {}", super::log::base_branch_name(state))
+    }
+}
+
+pub(crate) struct ShowParent;
+impl OpTrait for ShowParent {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            let current = shown_commit(state)?;
+            let parents = state
+                .repo()
+                .find_commit(current)?
+                .parents()
+                .map(|parent| {
+                    let short_id = parent.as_object().short_id()?;
+                    let summary = parent.summary().unwrap_or("");
+                    Ok((
+                        parent.id().to_string(),
+                        format!("{} {}", short_id.as_str().unwrap_or(""), summary),
+                    ))
+                })
+                .collect::<Res<Vec<_>>>()?;
+
+            match &parents[..] {
+                [] => {
+                    state.close_menu();
+                    Err("Commit has no parent".into())
+                }
+                [(parent, _)] => show_parent(state, term, parent),
+                _ => set_pick_only_prompt(
+                    state,
+                    "Show parent",
+                    parents.into_iter().map(|(_, label)| label).collect(),
+                    Box::new(|state, term, picked| {
+                        let rev = picked.split_whitespace().next().unwrap_or("");
+                        let parent = state.repo().revparse_single(rev)?.peel_to_commit()?.id();
+                        show_parent(state, term, &parent.to_string())
+                    }),
+                    true,
+                ),
+            }
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Show parent commit".into()
+    }
+}
+
+fn show_parent(state: &mut State, term: &mut Term, parent: &str) -> Res<()> {
+    let mut history = state.screen().show_history.clone();
+    history.push(parent.to_string());
+    replace_show_screen(state, term, history)
+}
+
+pub(crate) struct ShowChild;
+impl OpTrait for ShowChild {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, term: &mut Term| {
+            let current = shown_commit(state)?;
+            let mut history = state.screen().show_history.clone();
+
+            if history.len() > 1 {
+                history.pop();
+            } else if let Some(child) = find_child(state.repo(), current)? {
+                history = vec![child.to_string()];
+            } else {
+                state.close_menu();
+                return Err("No child commit found on HEAD".into());
+            }
+
+            replace_show_screen(state, term, history)
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Show child commit".into()
+    }
+}
+
+fn shown_commit(state: &mut State) -> Res<Oid> {
+    match state.screen().show_history.last() {
+        Some(commit) => Ok(Oid::from_str(commit)?),
+        None => {
+            state.close_menu();
+            Err("Not showing a commit".into())
+        }
+    }
+}
+
+/// Children aren't recorded in git, so look for one among the ancestors of HEAD.
+/// Those of `commit` itself can't be, so the walk stops short of them.
+fn find_child(repo: &Repository, commit: Oid) -> Res<Option<Oid>> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push_head()?;
+    revwalk.hide(commit)?;
+
+    for oid in revwalk {
+        let oid = oid?;
+        if repo
+            .find_commit(oid)?
+            .parent_ids()
+            .any(|parent| parent == commit)
+        {
+            return Ok(Some(oid));
+        }
+    }
+
+    Ok(None)
+}
+
+fn replace_show_screen(state: &mut State, term: &mut Term, history: Vec<String>) -> Res<()> {
+    let commit = history.last().expect("Empty show history").clone();
+
+    state.close_menu();
+    let mut screen = screen::show::create(
+        Rc::clone(&state.config),
+        Rc::clone(state.repo()),
+        term.size()?,
+        commit,
+    )?;
+    screen.show_history = history;
+
+    state.screens.pop();
+    state.screens.push(screen);
+    Ok(())
+}
+
 fn editor(file: &Path, maybe_line: Option<u32>) -> Option<Action> {
     let file = file.to_str().unwrap().to_string();
 
     Some(Rc::new(move |state, term| {
+        if state.repo().is_bare() {
+            return Err("There are no files to open in a bare repository".into());
+        }
+
         const EDITOR_VARS: [&str; 3] = ["VISUAL", "EDITOR", "GIT_EDITOR"];
         let configured_editor = EDITOR_VARS
             .into_iter()
@@ -56,7 +280,20 @@ fn editor(file: &Path, maybe_line: Option<u32>) -> Option<Action> {
             .into());
         };
 
-        let cmd = parse_editor_command(&editor, &file, maybe_line);
+        let line_arg = &state.config.editor.line_arg;
+        let cmd = if state.config.general.relative_paths.enabled {
+            let launch_dir = &state.config.launch_dir;
+            let mut cmd = parse_editor_command(
+                &editor,
+                line_arg,
+                &items::relative_path(launch_dir, Path::new(&file)).to_string_lossy(),
+                maybe_line,
+            );
+            cmd.current_dir(state.repo().workdir().expect("No workdir").join(launch_dir));
+            cmd
+        } else {
+            parse_editor_command(&editor, line_arg, &file, maybe_line)
+        };
 
         state.close_menu();
         state
@@ -67,7 +304,13 @@ fn editor(file: &Path, maybe_line: Option<u32>) -> Option<Action> {
     }))
 }
 
-fn parse_editor_command(editor: &str, file: &str, maybe_line: Option<u32>) -> Command {
+/// `line_arg` is a template like `+{line} {file}`, if empty it's guessed from the editor.
+fn parse_editor_command(
+    editor: &str,
+    line_arg: &str,
+    file: &str,
+    maybe_line: Option<u32>,
+) -> Command {
     let args = &editor.split_whitespace().collect::<Vec<_>>();
     let mut cmd = Command::new(args[0]);
     cmd.args(&args[1..]);
@@ -75,7 +318,12 @@ fn parse_editor_command(editor: &str, file: &str, maybe_line: Option<u32>) -> Co
     let lower = args[0].to_lowercase();
 
     if let Some(line) = maybe_line {
-        if lower.ends_with("vi")
+        if !line_arg.is_empty() {
+            cmd.args(line_arg.split_whitespace().map(|arg| {
+                arg.replace("{line}", &line.to_string())
+                    .replace("{file}", file)
+            }));
+        } else if lower.ends_with("vi")
             || lower.ends_with("vim")
             || lower.ends_with("nvim")
             || lower.ends_with("nano")
@@ -90,17 +338,61 @@ fn parse_editor_command(editor: &str, file: &str, maybe_line: Option<u32>) -> Co
     cmd
 }
 
+pub(crate) struct ToggleReviewed;
+impl OpTrait for ToggleReviewed {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        let Some(TargetData::Delta(delta)) = target else {
+            return None;
+        };
+
+        let file = delta.new_file.clone();
+        Some(Rc::new(move |state: &mut State, _term: &mut Term| {
+            state.close_menu();
+
+            let Some(rev) = state.screen().show_history.last().cloned() else {
+                return Err("Only commits on the show screen can be reviewed".into());
+            };
+
+            review::toggle_reviewed(state.repo(), &rev, file.clone())?;
+            state.screen_mut().update()
+        }))
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Toggle reviewed".into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::ffi::OsStr;
 
     #[test]
     fn parse_editor_command_test() {
-        let cmd = super::parse_editor_command("/bin/nAnO -f", "README.md", Some(42));
+        let cmd = super::parse_editor_command("/bin/nAnO -f", "", "README.md", Some(42));
         assert_eq!(cmd.get_program(), OsStr::new("/bin/nAnO"));
         assert_eq!(
             &cmd.get_args().collect::<Vec<_>>(),
             &["-f", "+42", "README.md"]
         );
     }
+
+    #[test]
+    fn parse_editor_command_line_arg() {
+        let cmd = super::parse_editor_command(
+            "code --wait",
+            "--goto {file}:{line}",
+            "README.md",
+            Some(42),
+        );
+        assert_eq!(cmd.get_program(), OsStr::new("code"));
+        assert_eq!(
+            &cmd.get_args().collect::<Vec<_>>(),
+            &["--wait", "--goto", "README.md:42"]
+        );
+    }
 }
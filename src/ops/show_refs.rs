@@ -1,5 +1,11 @@
 use super::{Action, OpTrait};
-use crate::{items::TargetData, screen, state::State, term::Term};
+use crate::{
+    config::RefsSort,
+    items::TargetData,
+    screen::{self, ScreenKind},
+    state::State,
+    term::Term,
+};
 use std::rc::Rc;
 
 pub(crate) struct ShowRefs;
@@ -21,7 +27,36 @@ fn goto_refs_screen(state: &mut State) {
     let size = state.screens.last().unwrap().size;
     state.close_menu();
     state.screens.push(
-        screen::show_refs::create(Rc::clone(&state.config), Rc::clone(&state.repo), size)
-            .expect("Couldn't create screen"),
+        screen::show_refs::create(
+            Rc::clone(&state.config),
+            Rc::clone(state.repo()),
+            size,
+            Rc::clone(&state.refs_sort),
+        )
+        .expect("Couldn't create screen"),
     );
 }
+
+pub(crate) struct CycleRefsSort;
+impl OpTrait for CycleRefsSort {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            state.close_menu();
+            if state.screen().kind != Some(ScreenKind::Refs) {
+                return Err("Sorting is only done on the refs screen".into());
+            }
+
+            state.refs_sort.set(state.refs_sort.get().next());
+            state.screen_mut().update()
+        }))
+    }
+
+    fn display(&self, state: &State) -> String {
+        match state.refs_sort.get().next() {
+            RefsSort::Name => "Sort refs by name",
+            RefsSort::CommitterDate => "Sort refs by date",
+            RefsSort::Ahead => "Sort refs by ahead",
+        }
+        .into()
+    }
+}
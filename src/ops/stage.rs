@@ -1,12 +1,16 @@
-use super::OpTrait;
+use super::{commit::edit_in_git_dir, OpTrait};
 use crate::{
-    git::diff::{Hunk, PatchMode},
+    git::{
+        apply,
+        diff::{Hunk, PatchMode},
+    },
     items::TargetData,
     state::State,
     term::Term,
-    Action,
+    Action, Res,
 };
-use std::{ffi::OsString, process::Command, rc::Rc};
+use git2::FileMode;
+use std::{ffi::OsString, fs, path::PathBuf, process::Command, rc::Rc};
 
 pub(crate) struct Stage;
 impl OpTrait for Stage {
@@ -14,10 +18,11 @@ impl OpTrait for Stage {
         let action = match target.cloned() {
             Some(TargetData::AllUnstaged) => stage_unstaged(),
             Some(TargetData::AllUntracked(untracked)) => stage_untracked(untracked),
-            Some(TargetData::File(u)) => stage_file(u.into()),
+            Some(TargetData::File(u) | TargetData::Unmerged(u)) => stage_file(u.into()),
             Some(TargetData::Delta(d)) => stage_file(d.new_file.into()),
             Some(TargetData::Hunk(h)) => stage_patch(h),
             Some(TargetData::HunkLine(h, i)) => stage_line(h, i),
+            Some(TargetData::ModeChange { file, new_mode, .. }) => set_index_mode(file, new_mode),
             _ => return None,
         };
 
@@ -66,25 +71,110 @@ fn stage_file(file: OsString) -> Action {
 }
 
 fn stage_patch(h: Rc<Hunk>) -> Action {
-    Rc::new(move |state, term| {
-        let mut cmd = Command::new("git");
-        cmd.args(["apply", "--cached"]);
-
+    Rc::new(move |state, _term| {
         state.close_menu();
-        state.run_cmd(term, &h.format_patch().into_bytes(), cmd)
+        apply::apply_to_index(
+            state.repo(),
+            &h.new_file,
+            &h.encode_patch(h.format_patch()),
+            false,
+        )?;
+        state.screen_mut().update()
     })
 }
 
 fn stage_line(h: Rc<Hunk>, i: usize) -> Action {
+    Rc::new(move |state, _term| {
+        let patch = h.encode_patch(h.format_line_patch(i..(i + 1), PatchMode::Normal));
+
+        state.close_menu();
+        apply::apply_to_index(state.repo(), &h.new_file, &patch, false)?;
+        state.screen_mut().update()
+    })
+}
+
+/// Like `git add -p`'s `e`, has the user edit the hunk before it's staged.
+pub(crate) struct StageEditedHunk;
+impl OpTrait for StageEditedHunk {
+    fn get_action(&self, target: Option<&TargetData>) -> Option<Action> {
+        match target {
+            Some(TargetData::Hunk(h) | TargetData::HunkLine(h, _)) => {
+                let h = Rc::clone(h);
+                Some(Rc::new(move |state, term| {
+                    state.close_menu();
+                    stage_edited(state, term, h.encode_patch(h.format_patch()))
+                }))
+            }
+            _ => None,
+        }
+    }
+
+    fn is_target_op(&self) -> bool {
+        true
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Edit hunk, then stage".into()
+    }
+}
+
+const EDIT_HUNK_HELP: &str = "\
+# ---
+# To leave out a '-' line, make it a ' ' line (context).
+# To leave out a '+' line, delete it.
+# Lines starting with # will be removed.
+# Emptying the patch leaves everything unstaged.
+";
+
+/// Opens a hunk's `patch` in `.git/GITU_HUNK_EDIT.diff`, then checks and stages what it was edited into.
+pub(crate) fn stage_edited(state: &mut State, term: &mut Term, mut patch: Vec<u8>) -> Res<()> {
+    patch.extend_from_slice(EDIT_HUNK_HELP.as_bytes());
+    let path = edit_in_git_dir(state, term, "GITU_HUNK_EDIT.diff", &patch)?;
+    let full_path = state.repo().workdir().expect("No workdir").join(&path);
+
+    let edited = fs::read(&full_path)?
+        .split_inclusive(|&byte| byte == b'\n')
+        .filter(|line| !line.starts_with(b"#"))
+        .flatten()
+        .copied()
+        .collect::<Vec<_>>();
+
+    let has_changes = edited
+        .split(|&byte| byte == b'\n')
+        .skip_while(|line| !line.starts_with(b"@@"))
+        .any(|line| line.starts_with(b"+") || line.starts_with(b"-"));
+
+    if !has_changes {
+        return Err("Nothing was left to stage".into());
+    }
+
+    let mut check = Command::new("git");
+    check.args(["apply", "--cached", "--recount", "--check"]);
+    state.run_cmd(term, &edited, check)?;
+
+    let mut apply = Command::new("git");
+    apply.args(["apply", "--cached", "--recount"]);
+    state.run_cmd(term, &edited, apply)
+}
+
+/// Sets only the mode of a file in the index, leaving its staged content as is.
+pub(crate) fn set_index_mode(file: PathBuf, mode: FileMode) -> Action {
     Rc::new(move |state, term| {
-        let mut cmd = Command::new("git");
-        cmd.args(["apply", "--cached", "--recount"]);
+        let Some(entry) = state.repo().index()?.get_path(&file, 0) else {
+            state.close_menu();
+            return Err(format!("{} is not in the index", file.to_string_lossy()).into());
+        };
 
-        let input = h
-            .format_line_patch(i..(i + 1), PatchMode::Normal)
-            .into_bytes();
+        let mut cmd = Command::new("git");
+        cmd.args(["update-index", "--cacheinfo"]);
+        cmd.arg(format!(
+            "{:o},{},{}",
+            u32::from(mode),
+            entry.id,
+            file.to_string_lossy()
+        ));
 
         state.close_menu();
-        state.run_cmd(term, &input, cmd)
+        state.run_cmd(term, &[], cmd)
     })
 }
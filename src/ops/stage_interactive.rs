@@ -0,0 +1,217 @@
+use super::{hunk_preview, stage::stage_edited, Action, OpTrait};
+use crate::{
+    git::{
+        self, apply,
+        diff::{Hunk, PatchMode},
+    },
+    items::TargetData,
+    prompt::PromptData,
+    state::State,
+    term::Term,
+    Res,
+};
+use ratatui::text::Line;
+use std::{cell::RefCell, collections::HashSet, iter, ops::Range, rc::Rc};
+use tui_prompts::State as _;
+
+/// Like `git add -p`, goes through the unstaged hunks one by one, asking whether to stage each.
+pub(crate) struct StageInteractively;
+impl OpTrait for StageInteractively {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(Rc::new(|state: &mut State, _term: &mut Term| {
+            state.close_menu();
+            let mut answers = Answers::default();
+            answers.total = units(state, &answers)?.len();
+            ask_next(state, Rc::new(RefCell::new(answers)))
+        }))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Stage hunk by hunk".into()
+    }
+}
+
+/// What's been answered so far, by the signatures of the hunks.
+/// The diff is read anew after each hunk is staged, so it's what they change that tells them apart.
+#[derive(Default)]
+struct Answers {
+    skipped: HashSet<String>,
+    split: HashSet<String>,
+    /// How many hunks have been answered, staged ones included, as those are gone from the diff.
+    answered: usize,
+    /// Counted when the walk starts, and grown as hunks are split.
+    total: usize,
+}
+
+/// A hunk, or a part of it with a single run of changed lines if it's been split.
+struct Unit {
+    hunk: Rc<Hunk>,
+    lines: Option<Range<usize>>,
+}
+
+impl Unit {
+    fn range(&self) -> Range<usize> {
+        self.lines
+            .clone()
+            .unwrap_or(0..self.hunk.content.lines.len())
+    }
+
+    fn signature(&self) -> String {
+        signature(&self.hunk, self.range())
+    }
+
+    fn patch(&self) -> Vec<u8> {
+        let patch = match &self.lines {
+            Some(lines) => self
+                .hunk
+                .format_line_patch(lines.clone(), PatchMode::Normal),
+            None => self.hunk.format_patch(),
+        };
+
+        self.hunk.encode_patch(patch)
+    }
+
+    fn preview(&self, state: &State) -> Vec<Line<'static>> {
+        let config = &state.config;
+        let file = Line::styled(
+            self.hunk.new_file.display().to_string(),
+            &config.style.file_header,
+        );
+
+        match &self.lines {
+            Some(lines) => iter::once(file)
+                .chain(iter::once(Line::styled(
+                    self.hunk.header.clone(),
+                    &config.style.hunk_header,
+                )))
+                .chain(self.hunk.content.lines[lines.clone()].iter().cloned())
+                .collect(),
+            None => iter::once(file)
+                .chain(hunk_preview(config, &self.hunk))
+                .collect(),
+        }
+    }
+}
+
+fn signature(hunk: &Hunk, range: Range<usize>) -> String {
+    iter::once(hunk.new_file.to_string_lossy().to_string())
+        .chain(
+            hunk.content.lines[range]
+                .iter()
+                .map(|line| line.to_string())
+                .filter(|line| line.starts_with(['+', '-'])),
+        )
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The runs of changed lines in a hunk, with unchanged lines in between, that it could be split into.
+fn change_runs(hunk: &Hunk) -> Vec<Range<usize>> {
+    let mut runs: Vec<Range<usize>> = vec![];
+    for (i, line) in hunk.content.lines.iter().enumerate() {
+        if !line.to_string().starts_with(['+', '-']) {
+            continue;
+        }
+
+        match runs.last_mut() {
+            Some(run) if run.end == i => run.end = i + 1,
+            _ => runs.push(i..(i + 1)),
+        }
+    }
+
+    runs
+}
+
+fn units(state: &State, answers: &Answers) -> Res<Vec<Unit>> {
+    let diff = git::diff_unstaged(&state.config, state.repo(), None)?;
+
+    Ok(diff
+        .deltas
+        .iter()
+        .flat_map(|delta| &delta.hunks)
+        .flat_map(|hunk| {
+            let runs = change_runs(hunk);
+            let is_split = runs.len() > 1
+                && runs
+                    .iter()
+                    .any(|run| answers.split.contains(&signature(hunk, run.clone())));
+
+            if is_split {
+                runs.into_iter()
+                    .map(|run| Unit {
+                        hunk: Rc::clone(hunk),
+                        lines: Some(run),
+                    })
+                    .collect()
+            } else {
+                vec![Unit {
+                    hunk: Rc::clone(hunk),
+                    lines: None,
+                }]
+            }
+        })
+        .collect())
+}
+
+fn ask_next(state: &mut State, answers: Rc<RefCell<Answers>>) -> Res<()> {
+    let Some(unit) = units(state, &answers.borrow())?
+        .into_iter()
+        .find(|unit| !answers.borrow().skipped.contains(&unit.signature()))
+    else {
+        state.display_info("No more hunks to stage".into());
+        return Ok(());
+    };
+
+    let number = answers.borrow().answered + 1;
+    let total = answers.borrow().total.max(number);
+    state.prompt.preview = Some(unit.preview(state));
+    state.prompt.set(PromptData {
+        prompt_text: format!("Stage this hunk ({number}/{total})? (y, n, s, e or q)").into(),
+        update_fn: Rc::new(move |state: &mut State, term: &mut Term| {
+            if !state.prompt.state.status().is_pending() {
+                return Ok(());
+            }
+
+            let answer = state.prompt.state.value().to_string();
+            if answer.is_empty() {
+                return Ok(());
+            }
+
+            state.prompt.reset(term)?;
+            match answer.as_str() {
+                "y" => {
+                    apply::apply_to_index(state.repo(), &unit.hunk.new_file, &unit.patch(), false)?;
+                    answers.borrow_mut().answered += 1;
+                    state.screen_mut().update()?;
+                }
+                "n" => {
+                    let mut answers = answers.borrow_mut();
+                    answers.skipped.insert(unit.signature());
+                    answers.answered += 1;
+                }
+                "s" => {
+                    let runs = change_runs(&unit.hunk);
+                    if unit.lines.is_some() || runs.len() < 2 {
+                        state.display_error("This hunk can't be split any further".into());
+                    } else {
+                        let mut answers = answers.borrow_mut();
+                        answers.total += runs.len() - 1;
+                        answers
+                            .split
+                            .extend(runs.into_iter().map(|run| signature(&unit.hunk, run)));
+                    }
+                }
+                "e" => match stage_edited(state, term, unit.patch()) {
+                    Ok(()) => answers.borrow_mut().answered += 1,
+                    Err(error) => state.display_error(error.to_string()),
+                },
+                "q" => return Ok(()),
+                _ => state.display_error(format!("Unknown answer: {answer}")),
+            }
+
+            ask_next(state, Rc::clone(&answers))
+        }),
+    });
+
+    Ok(())
+}
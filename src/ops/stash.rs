@@ -1,7 +1,7 @@
 use super::{create_prompt, create_prompt_with_default, Action, OpTrait};
-use crate::{items::TargetData, menu::arg::Arg, state::State, term::Term, Res};
+use crate::{git::stash, items::TargetData, menu::arg::Arg, state::State, term::Term, Res};
 use git2::{Repository, Status, StatusOptions};
-use std::{process::Command, rc::Rc};
+use std::{ffi::OsString, process::Command, rc::Rc};
 
 pub(crate) fn init_args() -> Vec<Arg> {
     vec![
@@ -10,6 +10,19 @@ pub(crate) fn init_args() -> Vec<Arg> {
     ]
 }
 
+/// The args of the menu, but for `--include-untracked` when `--all` is on.
+/// Git only heeds whichever comes last, which would leave out ignored files.
+fn push_args(state: &State) -> Vec<OsString> {
+    let args = state.pending_menu.as_ref().unwrap().args();
+    if args.iter().any(|arg| arg == "--all") {
+        args.into_iter()
+            .filter(|arg| arg != "--include-untracked")
+            .collect()
+    } else {
+        args
+    }
+}
+
 pub(crate) struct Stash;
 impl OpTrait for Stash {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
@@ -24,7 +37,7 @@ impl OpTrait for Stash {
 fn stash_push(state: &mut State, term: &mut Term, input: &str) -> Res<()> {
     let mut cmd = Command::new("git");
     cmd.args(["stash", "push"]);
-    cmd.args(state.pending_menu.as_ref().unwrap().args());
+    cmd.args(push_args(state));
     if !input.is_empty() {
         cmd.args(["--message", input]);
     }
@@ -62,7 +75,7 @@ pub(crate) struct StashWorktree;
 impl OpTrait for StashWorktree {
     fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
         Some(Rc::new(|state: &mut State, term: &mut Term| -> Res<()> {
-            if is_working_tree_empty(&state.repo)? {
+            if is_working_tree_empty(state.repo())? {
                 state.close_menu();
                 return Err("Cannot stash: working tree is empty".into());
             }
@@ -99,11 +112,11 @@ fn is_working_tree_empty(repo: &Repository) -> Res<bool> {
 }
 
 fn stash_worktree(state: &mut State, term: &mut Term, input: &str) -> Res<()> {
-    let need_to_stash_index = is_something_staged(&state.repo)?;
+    let need_to_stash_index = is_something_staged(state.repo())?;
 
     let mut cmd = Command::new("git");
     cmd.args(["stash", "push"]);
-    cmd.args(state.pending_menu.as_ref().unwrap().args());
+    cmd.args(push_args(state));
 
     state.close_menu();
 
@@ -159,7 +172,7 @@ impl OpTrait for StashKeepIndex {
 fn stash_push_keep_index(state: &mut State, term: &mut Term, input: &str) -> Res<()> {
     let mut cmd = Command::new("git");
     cmd.args(["stash", "push", "--keep-index"]);
-    cmd.args(state.pending_menu.as_ref().unwrap().args());
+    cmd.args(push_args(state));
     if !input.is_empty() {
         cmd.args(["--message", input]);
     }
@@ -186,13 +199,13 @@ impl OpTrait for StashPop {
 }
 
 fn stash_pop(state: &mut State, term: &mut Term, input: &str) -> Res<()> {
-    let mut cmd = Command::new("git");
-    cmd.args(["stash", "pop", "-q"]);
-    cmd.arg(input);
-
-    state.close_menu();
-    state.run_cmd(term, &[], cmd)?;
-    Ok(())
+    apply_checked(
+        state,
+        term,
+        "pop",
+        input,
+        "Stash won't apply cleanly, pop anyway?",
+    )
 }
 
 pub(crate) struct StashApply;
@@ -212,13 +225,53 @@ impl OpTrait for StashApply {
 }
 
 fn stash_apply(state: &mut State, term: &mut Term, input: &str) -> Res<()> {
-    let mut cmd = Command::new("git");
-    cmd.args(["stash", "apply", "-q"]);
-    cmd.arg(input);
+    apply_checked(
+        state,
+        term,
+        "apply",
+        input,
+        "Stash won't apply cleanly, apply anyway?",
+    )
+}
+
+/// Runs `git stash <subcommand>`, but first previews whether it would apply cleanly.
+/// If not, the affected files are listed and confirmation is asked for.
+fn apply_checked(
+    state: &mut State,
+    term: &mut Term,
+    subcommand: &'static str,
+    input: &str,
+    confirm_prompt: &'static str,
+) -> Res<()> {
+    let stash_rev = match input.parse::<usize>() {
+        Ok(index) => format!("stash@{{{}}}", index),
+        Err(_) => input.to_string(),
+    };
+
+    let check = stash::check_apply(state.repo(), &stash_rev)?;
+    let input = input.to_string();
+    let mut action: Action = Rc::new(move |state: &mut State, term: &mut Term| {
+        let mut cmd = Command::new("git");
+        cmd.args(["stash", subcommand, "-q"]);
+        cmd.arg(&input);
+
+        state.close_menu();
+        state.run_cmd(term, &[], cmd)
+    });
+
+    if check.is_clean() {
+        return Rc::get_mut(&mut action).unwrap()(state, term);
+    }
+
+    for file in &check.conflicts {
+        state.display_info(format!("Would conflict: {}", file));
+    }
+    for file in &check.overwritten {
+        state.display_info(format!("Local changes would be overwritten: {}", file));
+    }
 
     state.close_menu();
-    state.run_cmd(term, &[], cmd)?;
-    Ok(())
+    Rc::get_mut(&mut super::create_y_n_prompt(action, confirm_prompt)).unwrap()(state, term)
 }
 
 pub(crate) struct StashDrop;
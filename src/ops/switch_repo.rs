@@ -0,0 +1,55 @@
+use super::{create_picker_prompt, Action, OpTrait};
+use crate::{items::TargetData, state::State, term::Term, Res};
+use std::{
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+pub(crate) struct SwitchRepo;
+impl OpTrait for SwitchRepo {
+    fn get_action(&self, _target: Option<&TargetData>) -> Option<Action> {
+        Some(create_picker_prompt(
+            "Switch to repository",
+            recent_repo_candidates,
+            switch_repo,
+            |_| None,
+            true,
+        ))
+    }
+
+    fn display(&self, _state: &State) -> String {
+        "Switch repository".into()
+    }
+}
+
+/// The recent repositories other than the open one.
+fn recent_repo_candidates(state: &State) -> Vec<String> {
+    let current = state.recent_repos.first();
+    state
+        .recent_repos
+        .iter()
+        .filter(|repo| Some(*repo) != current)
+        .map(|repo| repo.display().to_string())
+        .collect()
+}
+
+/// Opens the repository picked on the workspace screen, which is kept below to go back to.
+pub(crate) fn open(path: PathBuf) -> Option<Action> {
+    Some(Rc::new(move |state: &mut State, term: &mut Term| {
+        state.close_menu();
+        let (repo, config) = crate::open_at(&path)?;
+        let workspace = state.screens.pop().expect("No screen");
+        if let Err(error) = state.switch_repo(repo, config, term.size()?) {
+            state.screens.push(workspace);
+            return Err(error);
+        }
+
+        state.screens.insert(0, workspace);
+        Ok(())
+    }))
+}
+
+fn switch_repo(state: &mut State, term: &mut Term, path: &str) -> Res<()> {
+    let (repo, config) = crate::open_at(Path::new(path))?;
+    state.switch_repo(repo, config, term.size()?)
+}
@@ -1,6 +1,12 @@
 use super::OpTrait;
-use crate::{git::diff::PatchMode, items::TargetData, state::State, term::Term, Action};
-use std::{ffi::OsString, process::Command, rc::Rc};
+use crate::{
+    git::{apply, diff::PatchMode},
+    items::TargetData,
+    state::State,
+    term::Term,
+    Action,
+};
+use std::{ffi::OsString, path::PathBuf, process::Command, rc::Rc};
 
 pub(crate) struct Unstage;
 impl OpTrait for Unstage {
@@ -8,11 +14,16 @@ impl OpTrait for Unstage {
         let action = match target.cloned() {
             Some(TargetData::AllStaged) => unstage_staged(),
             Some(TargetData::Delta(d)) => unstage_file(d.new_file.into()),
-            Some(TargetData::Hunk(h)) => unstage_patch(h.format_patch().into_bytes()),
-            Some(TargetData::HunkLine(h, i)) => unstage_line(
-                h.format_line_patch(i..(i + 1), PatchMode::Reverse)
-                    .into_bytes(),
+            Some(TargetData::Hunk(h)) => {
+                unstage_patch(h.new_file.clone(), h.encode_patch(h.format_patch()))
+            }
+            Some(TargetData::HunkLine(h, i)) => unstage_patch(
+                h.new_file.clone(),
+                h.encode_patch(h.format_line_patch(i..(i + 1), PatchMode::Reverse)),
             ),
+            Some(TargetData::ModeChange { file, old_mode, .. }) => {
+                super::stage::set_index_mode(file, old_mode)
+            }
             _ => return None,
         };
 
@@ -48,22 +59,10 @@ fn unstage_file(file: OsString) -> Action {
     })
 }
 
-fn unstage_patch(input: Vec<u8>) -> Action {
-    Rc::new(move |state: &mut State, term: &mut Term| {
-        let mut cmd = Command::new("git");
-        cmd.args(["apply", "--cached", "--reverse"]);
-
-        state.close_menu();
-        state.run_cmd(term, &input, cmd)
-    })
-}
-
-fn unstage_line(input: Vec<u8>) -> Action {
-    Rc::new(move |state: &mut State, term: &mut Term| {
-        let mut cmd = Command::new("git");
-        cmd.args(["apply", "--cached", "--reverse", "--recount"]);
-
+fn unstage_patch(file: PathBuf, patch: Vec<u8>) -> Action {
+    Rc::new(move |state: &mut State, _term: &mut Term| {
         state.close_menu();
-        state.run_cmd(term, &input, cmd)
+        apply::apply_to_index(state.repo(), &file, &patch, true)?;
+        state.screen_mut().update()
     })
 }
@@ -0,0 +1,179 @@
+use std::borrow::Cow;
+
+/// Max amount of candidates shown at once.
+pub(crate) const MAX_VISIBLE: usize = 10;
+
+/// A list of candidates narrowed down by fuzzy matching against the prompt input.
+/// Nothing is selected until the user moves onto a candidate, so what they typed is used as-is.
+/// Unless it's pick-only, then the best match always is.
+pub(crate) struct PickerState {
+    candidates: Vec<Cow<'static, str>>,
+    matches: Vec<usize>,
+    cursor: Option<usize>,
+    pick_only: bool,
+}
+
+impl PickerState {
+    pub(crate) fn new(candidates: Vec<Cow<'static, str>>) -> Self {
+        let mut picker = Self {
+            candidates,
+            matches: vec![],
+            cursor: None,
+            pick_only: false,
+        };
+
+        picker.update_filter("");
+        picker
+    }
+
+    pub(crate) fn update_filter(&mut self, query: &str) {
+        let mut scored = self
+            .candidates
+            .iter()
+            .enumerate()
+            .filter_map(|(i, candidate)| fuzzy_score(query, candidate).map(|score| (i, score)))
+            .collect::<Vec<_>>();
+
+        // Stable sort keeps the original order among equally good matches
+        scored.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+
+        self.matches = scored.into_iter().map(|(i, _)| i).collect();
+        self.cursor = (self.pick_only && !self.matches.is_empty()).then_some(0);
+    }
+
+    pub(crate) fn set_pick_only(&mut self) {
+        self.pick_only = true;
+        self.cursor = (!self.matches.is_empty()).then_some(0);
+    }
+
+    pub(crate) fn select_next(&mut self) {
+        let next = self.cursor.map_or(0, |cursor| cursor + 1);
+        if next < self.matches.len() {
+            self.cursor = Some(next);
+        }
+    }
+
+    /// Moving up past the first match goes back to using the input as typed, unless pick-only.
+    pub(crate) fn select_previous(&mut self) {
+        self.cursor = match self.cursor {
+            Some(0) if self.pick_only => Some(0),
+            cursor => cursor.and_then(|cursor| cursor.checked_sub(1)),
+        };
+    }
+
+    pub(crate) fn selected(&self) -> Option<&str> {
+        self.matches
+            .get(self.cursor?)
+            .map(|&i| self.candidates[i].as_ref())
+    }
+
+    /// The window of matches to display, paired with whether each is selected.
+    pub(crate) fn visible(&self) -> impl Iterator<Item = (&str, bool)> {
+        let start = self
+            .cursor
+            .map_or(0, |cursor| cursor + 1)
+            .saturating_sub(MAX_VISIBLE);
+
+        self.matches
+            .iter()
+            .enumerate()
+            .skip(start)
+            .take(MAX_VISIBLE)
+            .map(|(i, &candidate_i)| {
+                (
+                    self.candidates[candidate_i].as_ref(),
+                    Some(i) == self.cursor,
+                )
+            })
+    }
+}
+
+/// Matches `query` as a case-insensitive subsequence of `candidate`.
+/// Consecutive matches and matches near the start score higher.
+fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    let mut score = 0;
+    let mut previous_match = None;
+    let mut candidate_chars = candidate.chars().flat_map(char::to_lowercase).enumerate();
+
+    for query_char in query.chars().flat_map(char::to_lowercase) {
+        let (i, _) = candidate_chars.find(|&(_, c)| c == query_char)?;
+
+        score += match previous_match {
+            Some(previous) if previous + 1 == i => 10,
+            Some(_) => 1,
+            None => 5 - (i as i64).min(5),
+        };
+
+        previous_match = Some(i);
+    }
+
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PickerState;
+
+    fn picker(candidates: &[&'static str]) -> PickerState {
+        PickerState::new(candidates.iter().map(|&c| c.into()).collect())
+    }
+
+    #[test]
+    fn empty_query_keeps_order() {
+        let picker = picker(&["main", "feature", "origin/main"]);
+        let visible = picker.visible().map(|(c, _)| c).collect::<Vec<_>>();
+        assert_eq!(visible, ["main", "feature", "origin/main"]);
+        assert_eq!(picker.selected(), None);
+    }
+
+    #[test]
+    fn filters_by_subsequence() {
+        let mut picker = picker(&["main", "feature", "origin/main"]);
+        picker.update_filter("fea");
+        picker.select_next();
+        assert_eq!(picker.selected(), Some("feature"));
+        assert_eq!(picker.visible().count(), 1);
+    }
+
+    #[test]
+    fn prefers_consecutive_matches() {
+        let mut picker = picker(&["my-awesome-install", "main"]);
+        picker.update_filter("MAIN");
+        picker.select_next();
+        assert_eq!(picker.selected(), Some("main"));
+    }
+
+    #[test]
+    fn no_match() {
+        let mut picker = picker(&["main"]);
+        picker.update_filter("x");
+        picker.select_next();
+        assert_eq!(picker.selected(), None);
+    }
+
+    #[test]
+    fn move_selection() {
+        let mut picker = picker(&["a", "b"]);
+        picker.select_next();
+        assert_eq!(picker.selected(), Some("a"));
+        picker.select_next();
+        picker.select_next();
+        assert_eq!(picker.selected(), Some("b"));
+        picker.select_previous();
+        picker.select_previous();
+        assert_eq!(picker.selected(), None);
+    }
+
+    #[test]
+    fn pick_only_selects_best_match() {
+        let mut picker = picker(&[":sparkles:", ":bug:"]);
+        picker.set_pick_only();
+        assert_eq!(picker.selected(), Some(":sparkles:"));
+        picker.update_filter("bug");
+        assert_eq!(picker.selected(), Some(":bug:"));
+        picker.select_previous();
+        assert_eq!(picker.selected(), Some(":bug:"));
+        picker.update_filter("x");
+        assert_eq!(picker.selected(), None);
+    }
+}
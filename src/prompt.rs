@@ -1,6 +1,6 @@
 use super::Res;
-use crate::ops::Action;
-use ratatui::{backend::Backend, Terminal};
+use crate::{ops::Action, picker::PickerState};
+use ratatui::{backend::Backend, text::Line, Terminal};
 use std::borrow::Cow;
 use tui_prompts::{State as _, TextState};
 
@@ -12,6 +12,9 @@ pub(crate) struct PromptData {
 pub(crate) struct Prompt {
     pub(crate) data: Option<PromptData>,
     pub(crate) state: TextState<'static>,
+    pub(crate) picker: Option<PickerState>,
+    /// What a confirmation is about to affect, e.g. the changes a discard would lose.
+    pub(crate) preview: Option<Vec<Line<'static>>>,
 }
 
 impl Prompt {
@@ -19,6 +22,8 @@ impl Prompt {
         Prompt {
             data: None,
             state: TextState::new(),
+            picker: None,
+            preview: None,
         }
     }
 
@@ -27,9 +32,37 @@ impl Prompt {
         self.state.focus();
     }
 
+    /// The candidate the user moved onto if there is one, otherwise the text as entered.
+    pub(crate) fn input(&self) -> String {
+        self.picker
+            .as_ref()
+            .and_then(PickerState::selected)
+            .unwrap_or(self.state.value())
+            .to_string()
+    }
+
+    /// Inserts text at the cursor, flattening it onto a single line.
+    pub(crate) fn paste(&mut self, text: &str) {
+        text.trim_end()
+            .chars()
+            .filter(|&c| c != '\r')
+            .map(|c| if c == '\n' { ' ' } else { c })
+            .for_each(|c| self.state.push(c));
+
+        self.update_picker();
+    }
+
+    pub(crate) fn update_picker(&mut self) {
+        if let Some(picker) = &mut self.picker {
+            picker.update_filter(self.state.value());
+        }
+    }
+
     pub(crate) fn reset<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> Res<()> {
         self.data = None;
         self.state = TextState::new();
+        self.picker = None;
+        self.preview = None;
         terminal.hide_cursor()?;
         Ok(())
     }
@@ -0,0 +1,46 @@
+use crate::Res;
+use etcetera::{choose_base_strategy, BaseStrategy};
+use git2::Repository;
+use std::{fs, path::PathBuf};
+
+/// How many are remembered, the least recently opened being forgotten first.
+const MAX_REPOS: usize = 20;
+
+/// The repositories opened lately, most recent first, to switch between with `SwitchRepo`.
+pub(crate) fn load() -> Vec<PathBuf> {
+    fs::read_to_string(path())
+        .map(|content| content.lines().map(PathBuf::from).collect())
+        .unwrap_or_default()
+}
+
+pub(crate) fn save(repos: &[PathBuf]) -> Res<()> {
+    let path = path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let content = repos
+        .iter()
+        .map(|repo| format!("{}\n", repo.display()))
+        .collect::<String>();
+
+    fs::write(path, content)?;
+    Ok(())
+}
+
+/// Puts `repo` first among the recent ones, by its worktree or git dir if it's bare.
+pub(crate) fn record(repos: &mut Vec<PathBuf>, repo: &Repository) {
+    let dir = repo.workdir().unwrap_or(repo.path());
+    let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+
+    repos.retain(|recent| recent != &dir);
+    repos.insert(0, dir);
+    repos.truncate(MAX_REPOS);
+}
+
+fn path() -> PathBuf {
+    choose_base_strategy()
+        .expect("Unable to find the data directory!")
+        .data_dir()
+        .join("gitu/recent_repos")
+}
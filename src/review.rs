@@ -0,0 +1,43 @@
+use crate::Res;
+use git2::Repository;
+use std::{collections::BTreeSet, fs, path::PathBuf};
+
+/// Files marked as reviewed, kept per revision under `.git/gitu/reviewed/` to last across sessions.
+pub(crate) fn reviewed_files(repo: &Repository, rev: &str) -> BTreeSet<PathBuf> {
+    fs::read_to_string(reviewed_path(repo, rev))
+        .map(|content| content.lines().map(PathBuf::from).collect())
+        .unwrap_or_default()
+}
+
+/// Marks the file as reviewed, or unmarks it if it already was. Returns whether it now is.
+pub(crate) fn toggle_reviewed(repo: &Repository, rev: &str, file: PathBuf) -> Res<bool> {
+    let mut reviewed = reviewed_files(repo, rev);
+    let is_reviewed = if reviewed.remove(&file) {
+        false
+    } else {
+        reviewed.insert(file);
+        true
+    };
+
+    let path = reviewed_path(repo, rev);
+    if reviewed.is_empty() {
+        fs::remove_file(path)?;
+        return Ok(is_reviewed);
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let content = reviewed
+        .iter()
+        .map(|file| format!("{}\n", file.to_string_lossy()))
+        .collect::<String>();
+
+    fs::write(path, content)?;
+    Ok(is_reviewed)
+}
+
+fn reviewed_path(repo: &Repository, rev: &str) -> PathBuf {
+    repo.path().join("gitu/reviewed").join(rev)
+}
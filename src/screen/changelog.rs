@@ -0,0 +1,127 @@
+use std::{fs, path::PathBuf, rc::Rc};
+
+use super::Screen;
+use crate::{
+    config::{Config, StyleConfigEntry},
+    items::Item,
+    Res,
+};
+use etcetera::{choose_base_strategy, BaseStrategy};
+use ratatui::{
+    layout::Size,
+    text::{Line, Span},
+};
+
+const CHANGELOG_ENTRY: &str = include_str!("../../.recent-changelog-entry");
+
+pub(crate) fn create(config: Rc<Config>, size: Size) -> Res<Screen> {
+    Screen::new(
+        Rc::clone(&config),
+        size,
+        Box::new(move || Ok(create_items(&config, CHANGELOG_ENTRY))),
+    )
+}
+
+fn create_items(config: &Config, changelog: &str) -> Vec<Item> {
+    let style = &config.style;
+
+    changelog
+        .lines()
+        .map(|line| {
+            if let Some(version) = line.strip_prefix("## ") {
+                Item {
+                    id: "changelog".into(),
+                    display: Line::styled(version.to_string(), &style.section_header),
+                    section: true,
+                    depth: 0,
+                    ..Default::default()
+                }
+            } else if let Some(heading) = line.strip_prefix("### ") {
+                Item {
+                    id: heading.to_string().into(),
+                    display: Line::styled(heading.to_string(), &style.section_header),
+                    section: true,
+                    depth: 1,
+                    ..Default::default()
+                }
+            } else {
+                Item {
+                    display: highlight_keys(line, &style.hotkey),
+                    depth: 2,
+                    unselectable: true,
+                    ..Default::default()
+                }
+            }
+        })
+        .collect()
+}
+
+/// Highlights keys mentioned like `Bind 'Pu' to ...`.
+fn highlight_keys(line: &str, hotkey: &StyleConfigEntry) -> Line<'static> {
+    Line::from(
+        line.split('\'')
+            .enumerate()
+            .map(|(i, part)| {
+                if i % 2 == 1 {
+                    Span::styled(part.to_string(), hotkey)
+                } else {
+                    Span::raw(part.to_string())
+                }
+            })
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// Records the running version as seen, returning whether it wasn't already.
+pub(crate) fn mark_version_seen() -> Res<bool> {
+    let path = seen_version_path();
+    let version = env!("CARGO_PKG_VERSION");
+
+    if fs::read_to_string(&path).is_ok_and(|seen| seen.trim() == version) {
+        return Ok(false);
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, version)?;
+    Ok(true)
+}
+
+fn seen_version_path() -> PathBuf {
+    choose_base_strategy()
+        .expect("Unable to find the data directory!")
+        .data_dir()
+        .join("gitu/seen_version")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::init_test_config;
+
+    use super::create_items;
+
+    #[test]
+    fn highlights_new_bindings() {
+        let config = init_test_config().unwrap();
+        let items = create_items(
+            &config,
+            "## [0.26.0] - 2024-10-24\n\n### Configuration\n\n- Bind 'Pu' to new action: `push_to_upstream`",
+        );
+
+        let sections = items
+            .iter()
+            .filter(|item| item.section)
+            .map(|item| item.display.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(sections, ["[0.26.0] - 2024-10-24", "Configuration"]);
+
+        let binding = &items.last().unwrap().display;
+        assert_eq!(binding.spans[1].content, "Pu");
+        assert_eq!(binding.spans[1].style, (&config.style.hotkey).into());
+        assert_eq!(
+            binding.to_string(),
+            "- Bind Pu to new action: `push_to_upstream`"
+        );
+    }
+}
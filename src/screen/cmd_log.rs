@@ -0,0 +1,51 @@
+use std::{iter, rc::Rc};
+
+use super::Screen;
+use crate::{
+    cmd_log::{self, CmdLogHistory},
+    config::Config,
+    items::Item,
+    Res,
+};
+use ratatui::{layout::Size, text::Line};
+
+/// Everything logged this session, the latest first. Each command can be collapsed to hide its output.
+pub(crate) fn create(config: Rc<Config>, size: Size, history: CmdLogHistory) -> Res<Screen> {
+    Screen::new(
+        Rc::clone(&config),
+        size,
+        Box::new(move || {
+            let history = history.borrow();
+            if history.is_empty() {
+                return Ok(vec![Item {
+                    display: Line::raw("Nothing has been run yet"),
+                    ..Default::default()
+                }]);
+            }
+
+            Ok(history
+                .iter()
+                .rev()
+                .flat_map(|(number, entry)| {
+                    let mut lines = cmd_log::format_log_entry(&config, entry).into_iter();
+                    let header = lines.next().unwrap_or_default();
+
+                    iter::once(Item {
+                        id: format!("cmd_log_{}", number).into(),
+                        display: header,
+                        section: true,
+                        depth: 0,
+                        ..Default::default()
+                    })
+                    .chain(lines.map(|line| Item {
+                        display: line,
+                        depth: 1,
+                        unselectable: true,
+                        ..Default::default()
+                    }))
+                    .collect::<Vec<_>>()
+                })
+                .collect())
+        }),
+    )
+}
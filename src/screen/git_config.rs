@@ -0,0 +1,85 @@
+use super::Screen;
+use crate::{
+    config::Config,
+    items::{Item, TargetData},
+    Res,
+};
+use git2::{ConfigLevel, Repository};
+use ratatui::{
+    layout::Size,
+    text::{Line, Span},
+};
+use std::{iter, rc::Rc};
+
+/// The settings worth going over on a new machine, or in a new repository.
+const KEYS: &[&str] = &[
+    "user.name",
+    "user.email",
+    "user.signingKey",
+    "commit.gpgSign",
+    "core.editor",
+    "init.defaultBranch",
+    "pull.rebase",
+    "push.default",
+    "push.autoSetupRemote",
+    "fetch.prune",
+    "rebase.autoStash",
+    "rebase.autoSquash",
+    "merge.conflictStyle",
+];
+
+/// Lists the values of `KEYS` and where each is set, to be changed with `Show` or unset with `Discard`.
+pub(crate) fn create(config: Rc<Config>, repo: Rc<Repository>, size: Size) -> Res<Screen> {
+    Screen::new(
+        Rc::clone(&config),
+        size,
+        Box::new(move || {
+            let style = &config.style;
+            let git_config = repo.config()?.snapshot()?;
+            let key_width = KEYS.iter().map(|key| key.len()).max().unwrap_or(0);
+
+            Ok(iter::once(Item {
+                id: "git_config".into(),
+                display: Line::styled("Git config", &style.section_header),
+                section: true,
+                depth: 0,
+                ..Default::default()
+            })
+            .chain(KEYS.iter().map(|&key| {
+                let entry = git_config.get_entry(key).ok();
+                let value = entry
+                    .as_ref()
+                    .and_then(|entry| entry.value())
+                    .map(str::to_string);
+
+                let mut spans = vec![Span::raw(format!("{:<key_width$} ", key))];
+                match (value, entry.as_ref().map(|entry| entry.level())) {
+                    (Some(value), Some(level)) => {
+                        spans.push(Span::styled(value, &style.hash));
+                        spans.push(Span::raw(format!(" ({})", scope(level))));
+                    }
+                    _ => spans.push(Span::raw("unset")),
+                }
+
+                Item {
+                    id: key.to_string().into(),
+                    display: Line::from(spans),
+                    depth: 1,
+                    target_data: Some(TargetData::GitConfig(key.to_string())),
+                    ..Default::default()
+                }
+            }))
+            .collect())
+        }),
+    )
+}
+
+/// Where the value is set, as shown next to it.
+pub(crate) fn scope(level: ConfigLevel) -> &'static str {
+    match level {
+        ConfigLevel::Local | ConfigLevel::App | ConfigLevel::Highest => "local",
+        ConfigLevel::Worktree => "worktree",
+        ConfigLevel::Global | ConfigLevel::XDG => "global",
+        ConfigLevel::System | ConfigLevel::ProgramData => "system",
+    }
+}
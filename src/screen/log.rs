@@ -1,21 +1,242 @@
-use super::Screen;
-use crate::{config::Config, items::log, Res};
+use super::{Screen, ScreenKind};
+use crate::{
+    config::Config,
+    file_watcher::Changes,
+    git::signature::{signature_statuses, SignatureStatus},
+    items::{self, log, Item, LogWalk, TargetData},
+    locale::format_count,
+    Res,
+};
 use git2::{Oid, Repository};
-use ratatui::layout::Size;
+use ratatui::{
+    layout::Size,
+    text::{Line, Span},
+};
 use regex::Regex;
-use std::rc::Rc;
+use std::{
+    cell::{Cell, RefCell},
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn create(
     config: Rc<Config>,
     repo: Rc<Repository>,
     size: Size,
     limit: usize,
     rev: Option<Oid>,
+    since: Option<Oid>,
     msg_regex: Option<Regex>,
+    walk: LogWalk,
 ) -> Res<Screen> {
-    Screen::new(
+    let kind = ScreenKind::Log {
+        limit,
+        rev: rev.map(|rev| rev.to_string()),
+        since: since.map(|since| since.to_string()),
+        msg_regex: msg_regex.as_ref().map(|regex| regex.as_str().to_string()),
+        walk,
+    };
+
+    // Commits don't change, so each is only verified once
+    let signatures = RefCell::new(HashMap::new());
+    // By the commit counted from, as counting them all can take a while
+    let total = RefCell::new(None);
+    let limit = Rc::new(Cell::new(limit));
+    let screen_limit = Rc::clone(&limit);
+
+    let mut screen = Screen::new(
         Rc::clone(&config),
         size,
-        Box::new(move || log(&config, &repo, limit, rev, msg_regex.clone())),
-    )
+        Box::new(move || {
+            let limit = limit.get();
+            let mut items = log(&config, &repo, limit, rev, since, msg_regex.clone(), walk)?;
+            if config.general.log_signatures.enabled {
+                items = add_signatures(&config, &repo, &mut signatures.borrow_mut(), items)?;
+            }
+
+            if items.len() == limit {
+                let (total, more) =
+                    count_commits(&repo, rev, since, msg_regex.as_ref(), walk, &total)?;
+                if more || total > limit {
+                    items.push(items::blank_line());
+                    items.push(Item {
+                        display: Line::raw(format!(
+                            "Showing {} of {}{} commits",
+                            format_count(&config, limit),
+                            format_count(&config, total),
+                            if more { "+" } else { "" }
+                        )),
+                        unselectable: true,
+                        ..Default::default()
+                    });
+                }
+            }
+
+            Ok(items)
+        }),
+    )?;
+    screen.log_limit = Some(screen_limit);
+    screen.watched = Changes {
+        refs: true,
+        ..Default::default()
+    };
+    screen.kind = Some(kind);
+    Ok(screen)
+}
+
+/// Counting stops after walking this many commits, as a long history takes a while to walk.
+const MAX_WALKED_COMMITS: usize = 10_000;
+
+/// How many commits the log would list without a limit, and whether there are more than that,
+/// as counting stops after `MAX_WALKED_COMMITS`. Kept in `cache` until HEAD / `rev` moves.
+fn count_commits(
+    repo: &Repository,
+    rev: Option<Oid>,
+    since: Option<Oid>,
+    msg_regex: Option<&Regex>,
+    walk: LogWalk,
+    cache: &RefCell<Option<(Oid, (usize, bool))>>,
+) -> Res<(usize, bool)> {
+    // With --all, any ref moving can change the count, which this doesn't tell
+    let tip = match rev {
+        Some(rev) => rev,
+        None => repo.head()?.peel_to_commit()?.id(),
+    };
+
+    if let Some((counted_tip, total)) = *cache.borrow() {
+        if counted_tip == tip {
+            return Ok(total);
+        }
+    }
+
+    let Some(revwalk) = items::log_revwalk(repo, rev, since, walk)? else {
+        return Ok((0, false));
+    };
+
+    let decorated = decorated_commits(repo, walk)?;
+    let mut total = 0;
+    let mut more = false;
+    for (walked, oid) in revwalk.enumerate() {
+        // Filtered commits are walked too, so a few decorated or matching ones can take the whole history
+        if walked == MAX_WALKED_COMMITS {
+            more = true;
+            break;
+        }
+
+        if is_listed(repo, oid?, decorated.as_ref(), msg_regex)? {
+            total += 1;
+        }
+    }
+
+    *cache.borrow_mut() = Some((tip, (total, more)));
+    Ok((total, more))
+}
+
+/// Where the first commit with a message matching `search` would be listed in the log of `kind`, after the first
+/// `skip` ones. Like `git log --grep`, the whole history is walked for it.
+pub(crate) fn find_match(
+    repo: &Repository,
+    kind: &ScreenKind,
+    skip: usize,
+    search: &Regex,
+) -> Res<Option<usize>> {
+    let ScreenKind::Log {
+        rev,
+        since,
+        msg_regex,
+        walk,
+        ..
+    } = kind
+    else {
+        return Ok(None);
+    };
+
+    let rev = rev.as_deref().map(Oid::from_str).transpose()?;
+    let since = since.as_deref().map(Oid::from_str).transpose()?;
+    let msg_regex = msg_regex.as_deref().map(Regex::new).transpose()?;
+    let Some(revwalk) = items::log_revwalk(repo, rev, since, *walk)? else {
+        return Ok(None);
+    };
+
+    let decorated = decorated_commits(repo, *walk)?;
+    let mut listed = 0;
+    for oid in revwalk {
+        let oid = oid?;
+        if !is_listed(repo, oid, decorated.as_ref(), msg_regex.as_ref())? {
+            continue;
+        }
+
+        if listed >= skip && search.is_match(repo.find_commit(oid)?.message().unwrap_or("")) {
+            return Ok(Some(listed));
+        }
+
+        listed += 1;
+    }
+
+    Ok(None)
+}
+
+/// The commits shown with `walk.simplify_by_decoration`, `None` when they all are.
+fn decorated_commits(repo: &Repository, walk: LogWalk) -> Res<Option<HashSet<Oid>>> {
+    Ok(if walk.simplify_by_decoration {
+        Some(items::decorated_commits(repo)?)
+    } else {
+        None
+    })
+}
+
+/// Whether a walked commit is listed, rather than filtered out of the log.
+fn is_listed(
+    repo: &Repository,
+    oid: Oid,
+    decorated: Option<&HashSet<Oid>>,
+    msg_regex: Option<&Regex>,
+) -> Res<bool> {
+    if decorated.is_some_and(|decorated| !decorated.contains(&oid)) {
+        return Ok(false);
+    }
+
+    Ok(match msg_regex {
+        Some(re) => re.is_match(repo.find_commit(oid)?.message().unwrap_or("")),
+        None => true,
+    })
+}
+
+/// Puts an indicator of each commit's signature status after its hash.
+fn add_signatures(
+    config: &Config,
+    repo: &Repository,
+    signatures: &mut HashMap<String, SignatureStatus>,
+    mut items: Vec<Item>,
+) -> Res<Vec<Item>> {
+    let unverified = items
+        .iter()
+        .filter_map(|item| match &item.target_data {
+            Some(TargetData::Commit(hash)) if !signatures.contains_key(hash) => Some(hash.clone()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let dir = repo.workdir().unwrap_or(repo.path());
+    signatures.extend(signature_statuses(dir, &unverified)?);
+
+    let style = &config.style;
+    for item in &mut items {
+        let Some(TargetData::Commit(hash)) = &item.target_data else {
+            continue;
+        };
+
+        let indicator = match signatures.get(hash) {
+            Some(SignatureStatus::Good) => Span::styled("✓", &style.signature_good),
+            Some(SignatureStatus::Bad) => Span::styled("✗", &style.signature_bad),
+            Some(SignatureStatus::Unknown) => Span::styled("?", &style.signature_unknown),
+            Some(SignatureStatus::Unsigned) | None => Span::raw(" "),
+        };
+
+        item.display.spans.insert(1, Span::raw(" "));
+        item.display.spans.insert(2, indicator);
+    }
+
+    Ok(items)
 }
@@ -1,14 +1,38 @@
 use ratatui::prelude::*;
-
-use crate::{config::Config, items::TargetData, Res};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::Config,
+    external_diff,
+    file_watcher::Changes,
+    git::{
+        blame::LineBlame,
+        diff::{Hunk, HunkDefinitions},
+    },
+    items::{self, LogWalk, TargetData},
+    Res,
+};
 
 use super::Item;
-use std::{borrow::Cow, collections::HashSet, rc::Rc};
-
+use std::{
+    borrow::Cow,
+    cell::{Cell, RefCell},
+    collections::HashSet,
+    iter,
+    rc::Rc,
+};
+
+pub(crate) mod changelog;
+pub(crate) mod cmd_log;
+pub(crate) mod git_config;
 pub(crate) mod log;
+pub(crate) mod op_log;
+pub(crate) mod range_diff;
 pub(crate) mod show;
 pub(crate) mod show_refs;
 pub(crate) mod status;
+pub(crate) mod workspace;
 
 const BOTTOM_CONTEXT_LINES: usize = 2;
 
@@ -19,8 +43,43 @@ pub(crate) enum NavMode {
     IncludeHunkLines,
 }
 
+/// Which screen it is and what it was opened with, enough to open it again in a later session.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "screen", rename_all = "snake_case")]
+pub(crate) enum ScreenKind {
+    Status,
+    Log {
+        limit: usize,
+        rev: Option<String>,
+        #[serde(default)]
+        since: Option<String>,
+        msg_regex: Option<String>,
+        #[serde(default)]
+        walk: LogWalk,
+    },
+    Show {
+        reference: String,
+    },
+    ShowRange {
+        range: String,
+    },
+    Refs,
+}
+
+/// Where the cursor is and which sections are collapsed, as a `cursor_anchor` and section ids.
+#[cfg(feature = "session")]
+#[derive(Default, Debug, Serialize, Deserialize)]
+pub(crate) struct View {
+    cursor: Vec<(Cow<'static, str>, usize, usize)>,
+    collapsed: Vec<Cow<'static, str>>,
+}
+
 pub(crate) struct Screen {
     pub(crate) size: Size,
+    /// Set on screens that can be restored in a later session.
+    pub(crate) kind: Option<ScreenKind>,
+    /// Set on screens of no repository in particular, like the workspace, where ops on one are disabled.
+    pub(crate) without_repo: bool,
     cursor: usize,
     scroll: usize,
     config: Rc<Config>,
@@ -28,6 +87,31 @@ pub(crate) struct Screen {
     items: Vec<Item>,
     line_index: Vec<usize>,
     collapsed: HashSet<Cow<'static, str>>,
+    /// On show screens: the commits walked through via parent navigation, ending with the one shown.
+    pub(crate) show_history: Vec<String>,
+    /// On screens that can be filtered: the query, read by `refresh_items`.
+    pub(crate) filter: Option<Rc<RefCell<String>>>,
+    /// The last commit message search, repeated to find the next match.
+    pub(crate) search: Option<Regex>,
+    /// On log screens: how many commits are listed, read by `refresh_items`.
+    pub(crate) log_limit: Option<Rc<Cell<usize>>>,
+    /// On the status screen: what staged changes are compared to, HEAD if empty. Read by `refresh_items`.
+    pub(crate) diff_base: Option<Rc<RefCell<String>>>,
+    pub(crate) hide_whitespace_hunks: bool,
+    /// Whether diffs are rendered by `general.external_diff` rather than by Gitu.
+    pub(crate) external_diff: bool,
+    /// Whether hunk lines wider than the screen continue on the rows below, rather than being cut off.
+    pub(crate) wrap_lines: bool,
+    /// The hunk whose unchanged lines are annotated with who last changed them.
+    pub(crate) blame: Option<HunkBlame>,
+    /// The line under the mouse pointer.
+    hovered: Option<usize>,
+    /// Hunk lines selected by dragging the mouse, from where the drag started to where it is.
+    line_selection: Option<(usize, usize)>,
+    /// What the items are made from, refreshed when changed outside of Gitu.
+    pub(crate) watched: Changes,
+    /// On screens that rebuild only the sections affected by outside changes: what changed, read by `refresh_items`.
+    pub(crate) changed: Option<Rc<Cell<Changes>>>,
 }
 
 impl Screen {
@@ -48,11 +132,26 @@ impl Screen {
             cursor: 0,
             scroll: 0,
             size,
+            kind: None,
+            without_repo: false,
             config,
             refresh_items,
             items: vec![],
             line_index: vec![],
             collapsed,
+            show_history: vec![],
+            filter: None,
+            search: None,
+            diff_base: None,
+            log_limit: None,
+            hide_whitespace_hunks: false,
+            external_diff: false,
+            wrap_lines: false,
+            blame: None,
+            hovered: None,
+            line_selection: None,
+            watched: Changes::default(),
+            changed: None,
         };
 
         screen.update()?;
@@ -126,6 +225,27 @@ impl Screen {
         }
     }
 
+    /// Moves the cursor to the next shown item that matches (or the first one, if `from_top`).
+    /// Returns whether there was one.
+    pub(crate) fn select_matching(
+        &mut self,
+        from_top: bool,
+        matches: impl Fn(&Item) -> bool,
+    ) -> bool {
+        let start = if from_top { 0 } else { self.cursor + 1 };
+        let found = (start..self.line_index.len())
+            .find(|&line_i| matches(&self.items[self.line_index[line_i]]));
+
+        let Some(line_i) = found else {
+            return false;
+        };
+
+        self.cursor = line_i;
+        self.scroll_fit_end();
+        self.scroll_fit_start();
+        true
+    }
+
     pub(crate) fn find_next(&mut self, nav_mode: NavMode) -> usize {
         (self.cursor..self.line_index.len())
             .skip(1)
@@ -178,7 +298,7 @@ impl Screen {
                 .copied()
                 .enumerate()
                 .map(|(line, _)| (line + 1).saturating_sub(half_screen))
-                .last()
+                .next_back()
                 .unwrap_or(0),
         );
 
@@ -186,6 +306,87 @@ impl Screen {
         self.update_cursor(nav_mode);
     }
 
+    pub(crate) fn scroll_by(&mut self, lines: isize) {
+        self.scroll = self
+            .scroll
+            .saturating_add_signed(lines)
+            .min(self.line_index.len().saturating_sub(1));
+
+        let nav_mode = self.selected_item_nav_mode();
+        self.update_cursor(nav_mode);
+    }
+
+    /// The line shown at a row of the screen, if any.
+    fn line_at_row(&self, row: u16) -> Option<usize> {
+        let line_i = self.scroll + row as usize;
+        (row < self.size.height && line_i < self.line_index.len()).then_some(line_i)
+    }
+
+    /// Highlights the line under the mouse pointer, without moving the cursor.
+    pub(crate) fn hover(&mut self, row: u16) {
+        self.hovered = self.line_at_row(row);
+    }
+
+    /// Moves the cursor to the clicked line, and starts selecting hunk lines if it's one of them.
+    pub(crate) fn click(&mut self, row: u16) {
+        self.line_selection = None;
+        let Some(mut line_i) = self.line_at_row(row) else {
+            return;
+        };
+
+        while self.is_wrapped_continuation(line_i) {
+            line_i -= 1;
+        }
+
+        if !self.nav_filter(line_i, NavMode::IncludeHunkLines) {
+            return;
+        }
+
+        self.cursor = line_i;
+        if matches!(
+            self.at_line(line_i).target_data,
+            Some(TargetData::HunkLine(_, _))
+        ) {
+            self.line_selection = Some((line_i, line_i));
+        }
+    }
+
+    /// Extends the selected hunk lines to the row dragged to, as long as it's within the same hunk.
+    pub(crate) fn drag(&mut self, row: u16) {
+        let (Some((start, _)), Some(line_i)) = (self.line_selection, self.line_at_row(row)) else {
+            return;
+        };
+
+        let same_hunk = match (
+            &self.items[self.line_index[start]].target_data,
+            &self.items[self.line_index[line_i]].target_data,
+        ) {
+            (Some(TargetData::HunkLine(start_hunk, _)), Some(TargetData::HunkLine(hunk, _))) => {
+                Rc::ptr_eq(start_hunk, hunk)
+            }
+            _ => false,
+        };
+
+        if same_hunk {
+            self.line_selection = Some((start, line_i));
+        }
+    }
+
+    fn is_line_selected(&self, line_i: usize) -> bool {
+        self.line_selection
+            .is_some_and(|(start, end)| (start.min(end)..=start.max(end)).contains(&line_i))
+    }
+
+    /// Whether the line is the rest of the hunk line above it, put on a row of its own by `wrap_lines`.
+    fn is_wrapped_continuation(&self, line_i: usize) -> bool {
+        let hunk_line = |line_i: usize| match &self.items[self.line_index[line_i]].target_data {
+            Some(TargetData::HunkLine(hunk, i)) => Some((Rc::as_ptr(hunk), *i)),
+            _ => None,
+        };
+
+        line_i > 0 && hunk_line(line_i).is_some() && hunk_line(line_i) == hunk_line(line_i - 1)
+    }
+
     pub(crate) fn toggle_section(&mut self) {
         let selected = &self.items[self.line_index[self.cursor]];
 
@@ -200,14 +401,159 @@ impl Screen {
         self.update_line_index();
     }
 
+    /// Refreshes after `changes` were made outside of Gitu, rebuilding only what they affect where the screen can.
+    pub(crate) fn update_changed(&mut self, changes: Changes) -> Res<()> {
+        if let Some(changed) = &self.changed {
+            changed.set(changes);
+        }
+
+        self.update()
+    }
+
     pub(crate) fn update(&mut self) -> Res<()> {
         let nav_mode = self.selected_item_nav_mode();
-        self.items = (self.refresh_items)()?;
+        let anchor = self.cursor_anchor();
+        let mut items = (self.refresh_items)()?;
+        if self.hide_whitespace_hunks {
+            items = remove_whitespace_only_hunks(items);
+        }
+
+        if self.external_diff {
+            items = external_diff::replace_hunks(&self.config.general.external_diff, items)?;
+        }
+
+        if let Some(blame) = &self.blame {
+            annotate_blame(&self.config, &mut items, blame);
+        }
+
+        if self.wrap_lines {
+            items = wrap_hunk_lines(items, self.size.width);
+        }
+
+        self.items = items;
+
         self.update_line_index();
+        if self.restore_cursor(&anchor) {
+            self.scroll_fit_end();
+            self.scroll_fit_start();
+        }
         self.update_cursor(nav_mode);
         Ok(())
     }
 
+    /// Where the cursor is, as the ids of the selected item and the sections it's in, outermost first.
+    /// Each comes with its depth and where it is among its siblings, for when the id is gone after a refresh.
+    fn cursor_anchor(&self) -> Vec<(Cow<'static, str>, usize, usize)> {
+        if self.line_index.is_empty() {
+            return vec![];
+        }
+
+        let mut anchor = vec![];
+        let mut item_i = self.line_index[self.cursor];
+        loop {
+            let depth = self.items[item_i].depth;
+            let mut siblings = self.items[..item_i]
+                .iter()
+                .rev()
+                .take_while(|item| item.depth >= depth)
+                .filter(|item| item.depth == depth);
+
+            let ordinal = siblings.by_ref().count();
+            anchor.push((self.items[item_i].id.clone(), depth, ordinal));
+
+            match self.items[..item_i]
+                .iter()
+                .rposition(|item| item.depth < depth)
+            {
+                Some(parent_i) => item_i = parent_i,
+                None => break,
+            }
+        }
+
+        anchor.reverse();
+        anchor
+    }
+
+    /// Moves the cursor to the logically same item as in `anchor`: the item with the same id within the same sections.
+    /// Items that are gone are replaced by whatever took their place, like the next hunk once one is staged.
+    /// Returns false if not even the outermost section is left.
+    fn restore_cursor(&mut self, anchor: &[(Cow<'static, str>, usize, usize)]) -> bool {
+        let mut range = 0..self.items.len();
+        let mut found = None;
+        let mut parent_replaced = false;
+
+        for (level, (id, depth, ordinal)) in anchor.iter().enumerate() {
+            let siblings = range
+                .clone()
+                .filter(|&i| self.items[i].depth == *depth)
+                .collect::<Vec<_>>();
+
+            let by_id = (!id.is_empty())
+                .then(|| siblings.iter().find(|&&i| self.items[i].id == *id))
+                .flatten();
+
+            let item_i = match by_id {
+                Some(&i) => i,
+                None if level == 0 => return false,
+                None => match siblings.get(*ordinal).or(siblings.last()) {
+                    Some(&i) => i,
+                    None => break,
+                },
+            };
+
+            found = Some(item_i);
+            range = item_i + 1
+                ..(item_i + 1..self.items.len())
+                    .find(|&i| self.items[i].depth <= *depth)
+                    .unwrap_or(self.items.len());
+
+            // A replaced section, like a hunk that changed, is still looked into for the line at the same offset.
+            // Not deeper than that, what's in there has nothing to do with the original.
+            if parent_replaced {
+                break;
+            }
+            parent_replaced = by_id.is_none() && !id.is_empty();
+        }
+
+        let Some(item_i) = found else {
+            return false;
+        };
+
+        // If it's in a collapsed section, the section is shown instead
+        let Some(line_i) = self.line_index.iter().rposition(|&i| i <= item_i) else {
+            return false;
+        };
+
+        self.cursor = line_i;
+        true
+    }
+
+    #[cfg(feature = "session")]
+    pub(crate) fn view(&self) -> View {
+        let mut collapsed = self.collapsed.iter().cloned().collect::<Vec<_>>();
+        collapsed.sort();
+
+        View {
+            cursor: self.cursor_anchor(),
+            collapsed,
+        }
+    }
+
+    /// Collapses the sections and moves the cursor like they were in `view`, as far as they're still there.
+    #[cfg(feature = "session")]
+    pub(crate) fn restore_view(&mut self, view: &View) {
+        self.collapsed = view.collapsed.iter().cloned().collect();
+        self.update_line_index();
+
+        if self.restore_cursor(&view.cursor) {
+            self.scroll_fit_end();
+            self.scroll_fit_start();
+        }
+
+        let nav_mode = self.selected_item_nav_mode();
+        self.update_cursor(nav_mode);
+    }
+
     fn update_cursor(&mut self, nav_mode: NavMode) {
         self.clamp_cursor();
         if self.is_cursor_off_screen() {
@@ -230,6 +576,10 @@ impl Screen {
     }
 
     fn update_line_index(&mut self) {
+        // Lines may have moved
+        self.hovered = None;
+        self.line_selection = None;
+
         self.line_index = self
             .items
             .iter()
@@ -284,7 +634,47 @@ impl Screen {
         &self.items[self.line_index[self.cursor]]
     }
 
-    fn line_views(&self, area: Size) -> impl Iterator<Item = LineView> {
+    /// The top-level section the cursor is within.
+    pub(crate) fn get_selected_section(&self) -> Option<&Item> {
+        self.items[..=self.line_index[self.cursor]]
+            .iter()
+            .rev()
+            .find(|item| item.section && item.depth == 0)
+    }
+
+    /// The file and definitions the cursor is in, once the header of its hunk is scrolled out of view.
+    pub(crate) fn breadcrumbs(&self) -> Option<Line<'static>> {
+        if self.items.is_empty() {
+            return None;
+        }
+
+        let Some(TargetData::HunkLine(hunk, _)) = &self.get_selected_item().target_data else {
+            return None;
+        };
+
+        let header_line = self.line_index[..self.cursor].iter().rposition(|&i| {
+            matches!(&self.items[i].target_data, Some(TargetData::Hunk(header)) if Rc::ptr_eq(header, hunk))
+        })?;
+
+        if header_line >= self.scroll {
+            return None;
+        }
+
+        let style = &self.config.style;
+        let path = items::display_path(&self.config, &hunk.new_file);
+        Some(Line::from(
+            iter::once(Span::styled(path, &style.file_header))
+                .chain(hunk.definitions.get().iter().flat_map(|name| {
+                    [
+                        Span::raw(" › "),
+                        Span::styled(name.clone(), &style.hunk_header),
+                    ]
+                }))
+                .collect::<Vec<_>>(),
+        ))
+    }
+
+    fn line_views(&self, area: Size) -> impl Iterator<Item = LineView<'_>> {
         let scan_start = self.scroll.min(self.cursor);
         let scan_end = (self.scroll + area.height as usize).min(self.line_index.len());
         let scan_highlight_range = scan_start..(scan_end);
@@ -303,7 +693,7 @@ impl Screen {
                 Some(LineView {
                     item_index: *item_i,
                     item,
-                    display: &item.display,
+                    display: with_definitions(item),
                     highlighted: highlight_depth.is_some(),
                 })
             })
@@ -314,10 +704,22 @@ impl Screen {
 struct LineView<'a> {
     item_index: usize,
     item: &'a Item,
-    display: &'a Line<'a>,
+    display: Cow<'a, Line<'static>>,
     highlighted: bool,
 }
 
+/// Like the function context git shows, but as a path through nested definitions.
+fn with_definitions(item: &Item) -> Cow<'_, Line<'static>> {
+    match item.definitions.as_ref().map(HunkDefinitions::get) {
+        Some(names) if !names.is_empty() => {
+            let mut display = item.display.clone();
+            display.push_span(format!(" {}", names.join(" › ")));
+            Cow::Owned(display)
+        }
+        _ => Cow::Borrowed(&item.display),
+    }
+}
+
 impl Widget for &Screen {
     fn render(self, area: Rect, buf: &mut Buffer) {
         let style = &self.config.style;
@@ -344,7 +746,16 @@ impl Widget for &Screen {
                 }
             }
 
-            line.display.render(indented_line_area, buf);
+            let line_i = self.scroll + line_index;
+            if self.hovered == Some(line_i) {
+                buf.set_style(line_area, &style.mouse_hover);
+            }
+
+            if self.is_line_selected(line_i) {
+                buf.set_style(line_area, &style.mouse_selection);
+            }
+
+            line.display.as_ref().render(indented_line_area, buf);
             let overflow = line.display.width() > line_area.width as usize;
 
             if self.is_collapsed(line.item) && line.display.width() > 0 || overflow {
@@ -361,3 +772,146 @@ impl Widget for &Screen {
         }
     }
 }
+
+/// Drops hunks that only change whitespace, along with everything nested in them.
+fn remove_whitespace_only_hunks(items: Vec<Item>) -> Vec<Item> {
+    let mut hidden_depth = None;
+
+    items
+        .into_iter()
+        .filter(|item| {
+            if hidden_depth.is_some_and(|depth| item.depth > depth) {
+                return false;
+            }
+
+            hidden_depth = match &item.target_data {
+                Some(TargetData::Hunk(hunk)) if hunk.is_whitespace_only() => Some(item.depth),
+                _ => None,
+            };
+
+            hidden_depth.is_none()
+        })
+        .collect()
+}
+
+/// A hunk's blame, kept by its headers as the hunk itself is made anew on each refresh.
+pub(crate) struct HunkBlame {
+    pub(crate) file_header: String,
+    pub(crate) header: String,
+    pub(crate) lines: Vec<Option<LineBlame>>,
+}
+
+impl HunkBlame {
+    pub(crate) fn is_of(&self, hunk: &Hunk) -> bool {
+        self.file_header == hunk.file_header && self.header == hunk.header
+    }
+}
+
+/// Puts the commit and author in front of each of the blamed hunk's lines, in a column of their own.
+fn annotate_blame(config: &Config, items: &mut [Item], blame: &HunkBlame) {
+    const AUTHOR_WIDTH: usize = 12;
+
+    let id_width = blame
+        .lines
+        .iter()
+        .flatten()
+        .map(|line| line.short_id.len())
+        .max()
+        .unwrap_or(0);
+
+    for item in items.iter_mut() {
+        let Some(TargetData::HunkLine(hunk, i)) = &item.target_data else {
+            continue;
+        };
+
+        if !blame.is_of(hunk) {
+            continue;
+        }
+
+        let annotation = match blame.lines.get(*i).cloned().flatten() {
+            Some(line) => vec![
+                Span::styled(format!("{:<id_width$} ", line.short_id), &config.style.hash),
+                Span::raw(format!(
+                    "{:<AUTHOR_WIDTH$} ",
+                    line.author.chars().take(AUTHOR_WIDTH).collect::<String>()
+                )),
+            ],
+            None => vec![Span::raw(" ".repeat(id_width + AUTHOR_WIDTH + 2))],
+        };
+
+        item.display.spans.splice(0..0, annotation);
+    }
+}
+
+/// Splits hunk lines wider than `width` into rows, each continuation nested in the line it
+/// continues so that it's highlighted along with it. Continuations can't be selected themselves.
+fn wrap_hunk_lines(items: Vec<Item>, width: u16) -> Vec<Item> {
+    // One column goes to the cursor, and one to the continuation indicator
+    let Some(rest_width) = (width as usize).checked_sub(2).filter(|&w| w > 0) else {
+        return items;
+    };
+
+    let mut wrapped = vec![];
+    for item in items {
+        let is_hunk_line = matches!(item.target_data, Some(TargetData::HunkLine(..)));
+        if !is_hunk_line || item.display.width() <= rest_width + 1 {
+            wrapped.push(item);
+            continue;
+        }
+
+        let mut rows = split_line(&item.display, rest_width + 1, rest_width).into_iter();
+        let first = rows.next().unwrap_or_default();
+        let continuations = rows
+            .map(|row| Item {
+                display: Line::from_iter(
+                    iter::once(Span::styled("↪", Modifier::DIM)).chain(row.spans),
+                ),
+                depth: item.depth + 1,
+                unselectable: true,
+                ..item.clone()
+            })
+            .collect::<Vec<_>>();
+
+        wrapped.push(Item {
+            display: first,
+            ..item
+        });
+        wrapped.extend(continuations);
+    }
+
+    wrapped
+}
+
+/// Splits a line into rows of at most `first_width` columns for the first, and `rest_width` after.
+fn split_line(line: &Line<'static>, first_width: usize, rest_width: usize) -> Vec<Line<'static>> {
+    let mut rows = vec![Line::default()];
+    let mut row_width = 0;
+
+    for grapheme in line.styled_graphemes(Style::default()) {
+        let grapheme_width = Span::raw(grapheme.symbol).width();
+        let max_width = if rows.len() == 1 {
+            first_width
+        } else {
+            rest_width
+        };
+
+        if row_width > 0 && row_width + grapheme_width > max_width {
+            rows.push(Line::default());
+            row_width = 0;
+        }
+
+        let row = rows.last_mut().unwrap();
+        match row.spans.last_mut() {
+            Some(span) if span.style == grapheme.style => {
+                span.content.to_mut().push_str(grapheme.symbol)
+            }
+            _ => row
+                .spans
+                .push(Span::styled(grapheme.symbol.to_string(), grapheme.style)),
+        }
+
+        row_width += grapheme_width;
+    }
+
+    rows
+}
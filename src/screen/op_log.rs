@@ -0,0 +1,93 @@
+use super::Screen;
+use crate::{
+    config::Config,
+    git,
+    items::{self, Item},
+    op_log, Res,
+};
+use chrono::{DateTime, FixedOffset};
+use git2::Repository;
+use itertools::Itertools;
+use ratatui::{
+    layout::Size,
+    text::{Line, Span},
+};
+use std::{iter, rc::Rc};
+
+/// The commands recorded in `.git/gitu-oplog`, a section per session, the latest first.
+pub(crate) fn create(
+    config: Rc<Config>,
+    repo: Rc<Repository>,
+    size: Size,
+    session: DateTime<FixedOffset>,
+) -> Res<Screen> {
+    Screen::new(
+        Rc::clone(&config),
+        size,
+        Box::new(move || {
+            let style = &config.style;
+            let entries = op_log::load(&repo);
+            if entries.is_empty() {
+                return Ok(vec![Item {
+                    display: Line::raw("Nothing has been run yet"),
+                    ..Default::default()
+                }]);
+            }
+
+            let sessions = entries
+                .into_iter()
+                .rev()
+                .chunk_by(|entry| entry.session)
+                .into_iter()
+                .map(|(started, entries)| (started, entries.collect::<Vec<_>>()))
+                .collect::<Vec<_>>();
+
+            Ok(sessions
+                .into_iter()
+                .enumerate()
+                .flat_map(|(i, (started, entries))| {
+                    let header = if started == session {
+                        "This session".to_string()
+                    } else {
+                        format!("Session of {}", git::format_date(&config, &started))
+                    };
+
+                    (i > 0)
+                        .then(items::blank_line)
+                        .into_iter()
+                        .chain(iter::once(Item {
+                            id: format!("op_log_{}", started.to_rfc3339()).into(),
+                            display: Line::styled(header, &style.section_header),
+                            section: true,
+                            depth: 0,
+                            ..Default::default()
+                        }))
+                        .chain(entries.into_iter().map(|entry| {
+                            let mut spans = vec![
+                                Span::styled(
+                                    entry.started.format("%H:%M:%S").to_string(),
+                                    &style.hash,
+                                ),
+                                Span::raw(" "),
+                                Span::styled(entry.args, &style.command),
+                            ];
+
+                            match entry.exit_code {
+                                Some(0) => (),
+                                Some(code) => spans.push(Span::raw(format!(" (exit {})", code))),
+                                None => spans.push(Span::raw(" (killed)")),
+                            }
+
+                            Item {
+                                display: Line::from(spans),
+                                depth: 1,
+                                unselectable: true,
+                                ..Default::default()
+                            }
+                        }))
+                        .collect::<Vec<_>>()
+                })
+                .collect())
+        }),
+    )
+}
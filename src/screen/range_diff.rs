@@ -0,0 +1,109 @@
+use std::{iter, rc::Rc};
+
+use super::Screen;
+use crate::{
+    config::Config,
+    file_watcher::Changes,
+    git::range_diff::{self, CommitPair, PairStatus},
+    items::{Item, TargetData},
+    Res,
+};
+use git2::Repository;
+use ratatui::{
+    layout::Size,
+    text::{Line, Span},
+};
+
+pub(crate) fn create(
+    config: Rc<Config>,
+    repo: Rc<Repository>,
+    size: Size,
+    args: Vec<String>,
+) -> Res<Screen> {
+    let mut screen = Screen::new(
+        Rc::clone(&config),
+        size,
+        Box::new(move || {
+            let dir = repo.workdir().unwrap_or(repo.path());
+            let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+            let pairs = range_diff::range_diff(dir, &args)?;
+
+            if pairs.is_empty() {
+                return Ok(vec![Item {
+                    display: Line::raw("No commits found"),
+                    ..Default::default()
+                }]);
+            }
+
+            Ok(pairs
+                .into_iter()
+                .flat_map(|pair| create_pair_items(&config, pair))
+                .collect())
+        }),
+    )?;
+    screen.watched = Changes {
+        refs: true,
+        ..Default::default()
+    };
+    Ok(screen)
+}
+
+fn create_pair_items(config: &Config, pair: CommitPair) -> impl Iterator<Item = Item> {
+    let style = &config.style;
+    let id = format!(
+        "range_diff_{}_{}",
+        pair.old_hash.as_deref().unwrap_or("-"),
+        pair.new_hash.as_deref().unwrap_or("-")
+    );
+
+    let hash = |hash: &Option<String>| match hash {
+        Some(hash) => Span::styled(hash.clone(), &style.hash),
+        None => Span::raw("-".repeat(7)),
+    };
+
+    let status = match pair.status {
+        PairStatus::Equal => Span::raw("="),
+        PairStatus::Changed => Span::styled("!", &style.hunk_header),
+        PairStatus::Removed => Span::styled("<", &style.diff_highlight.tag_old),
+        PairStatus::Added => Span::styled(">", &style.diff_highlight.tag_new),
+    };
+
+    let body = pair
+        .body
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            let line_style = match line.chars().next() {
+                Some('-') => &style.diff_highlight.changed_old,
+                Some('+') => &style.diff_highlight.changed_new,
+                Some('@') => &style.hunk_header,
+                _ => &style.diff_highlight.unchanged_new,
+            };
+
+            Item {
+                id: format!("{id}/{i}").into(),
+                display: Line::styled(line.replace('\t', "    "), line_style),
+                depth: 1,
+                ..Default::default()
+            }
+        })
+        .collect::<Vec<_>>();
+
+    iter::once(Item {
+        id: id.clone().into(),
+        display: Line::from(vec![
+            hash(&pair.old_hash),
+            Span::raw(" "),
+            status,
+            Span::raw(" "),
+            hash(&pair.new_hash),
+            Span::raw(" "),
+            Span::raw(pair.subject.clone()),
+        ]),
+        section: !body.is_empty(),
+        depth: 0,
+        target_data: pair.new_hash.or(pair.old_hash).map(TargetData::Commit),
+        ..Default::default()
+    })
+    .chain(body)
+}
@@ -2,17 +2,24 @@ use std::{iter, rc::Rc};
 
 use crate::{
     config::Config,
-    git,
-    items::{self, Item},
-    Res,
+    file_watcher::Changes,
+    git::{
+        self,
+        diff::{BinarySide, Diff},
+        stash::StashParts,
+    },
+    items::{self, Item, TargetData},
+    locale::format_count,
+    review, Res,
 };
 use git2::Repository;
 use ratatui::{
     layout::Size,
-    text::{Line, Text},
+    style::{Modifier, Style},
+    text::{Line, Span, Text},
 };
 
-use super::Screen;
+use super::{Screen, ScreenKind};
 
 pub(crate) fn create(
     config: Rc<Config>,
@@ -20,16 +27,21 @@ pub(crate) fn create(
     size: Size,
     reference: String,
 ) -> Res<Screen> {
-    Screen::new(
+    let commit = repo.revparse_single(&reference)?.peel_to_commit()?.id();
+    // The commit rather than what pointed to it, which may point elsewhere by the time it's restored
+    let kind = ScreenKind::Show {
+        reference: commit.to_string(),
+    };
+
+    let mut screen = Screen::new(
         Rc::clone(&config),
         size,
         Box::new(move || {
             let style = &config.style;
-            let commit = git::show_summary(repo.as_ref(), &reference)?;
-            let show = git::show(&config, repo.as_ref(), &reference)?;
+            let commit = git::show_summary(&config, repo.as_ref(), &reference)?;
             let details = Text::from(commit.details).lines;
 
-            Ok(iter::once(Item {
+            let header = iter::once(Item {
                 id: format!("commit_section_{}", commit.hash).into(),
                 display: Line::styled(format!("commit {}", commit.hash), &style.section_header),
                 section: true,
@@ -42,15 +54,291 @@ pub(crate) fn create(
                 depth: 1,
                 unselectable: true,
                 ..Default::default()
+            }));
+
+            if let Some(parts) = git::stash::split(&config, repo.as_ref(), &reference)? {
+                return Ok(header.chain(stash_parts(&config, &parts)).collect());
+            }
+
+            let show = git::show(&config, repo.as_ref(), &reference)?;
+            Ok(header
+                .chain(diff_stat(&config, &show))
+                .chain([items::blank_line()])
+                .chain(mark_reviewed(
+                    &repo,
+                    &commit.hash,
+                    items::fold_unchanged_lines(
+                        &config,
+                        items::create_diff_items(Rc::clone(&config), &show, &0, false),
+                        config.general.fold_unchanged_lines,
+                    ),
+                ))
+                .chain(review_progress(&repo, &commit.hash, show.deltas.len()))
+                .collect())
+        }),
+    )?;
+
+    screen.show_history.push(commit.to_string());
+    screen.kind = Some(kind);
+    Ok(screen)
+}
+
+/// A section for each part of a stash, rather than the combined diff of its commits.
+fn stash_parts(config: &Rc<Config>, parts: &StashParts) -> Vec<Item> {
+    [
+        ("staged_changes", "Staged changes", Some(&parts.staged)),
+        (
+            "unstaged_changes",
+            "Unstaged changes",
+            Some(&parts.unstaged),
+        ),
+        (
+            "untracked_files",
+            "Untracked files",
+            parts.untracked.as_ref(),
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(id, header, diff)| Some((id, header, diff.filter(|d| !d.deltas.is_empty())?)))
+    .flat_map(|(id, header, diff)| {
+        [
+            items::blank_line(),
+            Item {
+                id: format!("stash_{}", id).into(),
+                display: Line::from(vec![
+                    Span::styled(header, &config.style.section_header),
+                    format!(" ({})", diff.deltas.len()).into(),
+                ]),
+                section: true,
+                depth: 0,
+                ..Default::default()
+            },
+        ]
+        .into_iter()
+        .chain(items::fold_unchanged_lines(
+            config,
+            items::create_diff_items(Rc::clone(config), diff, &1, false),
+            config.general.fold_unchanged_lines,
+        ))
+    })
+    .collect()
+}
+
+/// The combined diff of a range of commits, like `git diff A..B`.
+pub(crate) fn create_range(
+    config: Rc<Config>,
+    repo: Rc<Repository>,
+    size: Size,
+    range: String,
+) -> Res<Screen> {
+    let kind = ScreenKind::ShowRange {
+        range: range.clone(),
+    };
+
+    let mut screen = Screen::new(
+        Rc::clone(&config),
+        size,
+        Box::new(move || {
+            let style = &config.style;
+            let diff = git::diff_range(&config, repo.as_ref(), &range)?;
+
+            Ok(iter::once(Item {
+                id: format!("range_section_{}", range).into(),
+                display: Line::styled(format!("range {}", range), &style.section_header),
+                section: true,
+                depth: 0,
+                ..Default::default()
+            })
+            .chain(iter::once(Item {
+                id: format!("range_{}", range).into(),
+                display: Line::raw(match diff.deltas.len() {
+                    1 => "1 file changed".to_string(),
+                    count => format!("{} files changed", format_count(&config, count)),
+                }),
+                depth: 1,
+                unselectable: true,
+                ..Default::default()
             }))
             .chain([items::blank_line()])
-            .chain(items::create_diff_items(
-                Rc::clone(&config),
-                &show,
-                &0,
-                false,
+            .chain(items::fold_unchanged_lines(
+                &config,
+                items::create_diff_items(Rc::clone(&config), &diff, &0, false),
+                config.general.fold_unchanged_lines,
             ))
             .collect())
         }),
-    )
+    )?;
+    screen.watched = Changes {
+        refs: true,
+        ..Default::default()
+    };
+    screen.kind = Some(kind);
+    Ok(screen)
+}
+
+/// Whether to show a range of commits rather than a single one, like `gitu show A..B`.
+pub(crate) fn is_range(reference: &str) -> bool {
+    reference.contains("..")
+}
+
+/// Widest the `+++--` bar of a file gets, the others are scaled down relative to it.
+const STAT_BAR_WIDTH: usize = 40;
+
+/// Like `git show --stat`: a line per file with how many lines changed, then the totals.
+fn diff_stat(config: &Config, diff: &Diff) -> Vec<Item> {
+    let style = &config.style.diff_highlight;
+    let changes = diff
+        .deltas
+        .iter()
+        .map(|delta| {
+            let count = |prefix: &str| {
+                delta
+                    .hunks
+                    .iter()
+                    .flat_map(|hunk| &hunk.content.lines)
+                    .filter(|line| {
+                        line.spans
+                            .first()
+                            .is_some_and(|s| s.content.contains(prefix))
+                    })
+                    .count()
+            };
+
+            (delta, count("+"), count("-"))
+        })
+        .collect::<Vec<_>>();
+
+    let paths = changes
+        .iter()
+        .map(|(delta, ..)| match delta.status {
+            git2::Delta::Renamed => format!(
+                "{} => {}",
+                items::display_path(config, &delta.old_file),
+                items::display_path(config, &delta.new_file)
+            ),
+            _ => items::display_path(config, &delta.new_file),
+        })
+        .collect::<Vec<_>>();
+
+    let path_width = paths
+        .iter()
+        .map(|path| path.chars().count())
+        .max()
+        .unwrap_or(0);
+    let most_changes = changes
+        .iter()
+        .map(|(_, insertions, deletions)| insertions + deletions)
+        .max()
+        .unwrap_or(0);
+
+    let count_width = format_count(config, most_changes).chars().count();
+    let scale = |lines: usize| {
+        if most_changes <= STAT_BAR_WIDTH || lines == 0 {
+            lines
+        } else {
+            (lines * STAT_BAR_WIDTH / most_changes).max(1)
+        }
+    };
+
+    let mut items = vec![Item {
+        id: "diff_stat_blank".into(),
+        depth: 1,
+        unselectable: true,
+        ..Default::default()
+    }];
+
+    for ((delta, insertions, deletions), path) in changes.iter().zip(paths) {
+        let stat = match &delta.binary {
+            Some(binary) => {
+                let size = |side: &Option<BinarySide>| side.as_ref().map_or(0, |side| side.size);
+                vec![Span::raw(format!(
+                    "Bin {} -> {} bytes",
+                    format_count(config, size(&binary.old)),
+                    format_count(config, size(&binary.new))
+                ))]
+            }
+            None => vec![
+                Span::raw(format!(
+                    "{:>count_width$} ",
+                    format_count(config, insertions + deletions)
+                )),
+                Span::styled("+".repeat(scale(*insertions)), &style.tag_new),
+                Span::styled("-".repeat(scale(*deletions)), &style.tag_old),
+            ],
+        };
+
+        items.push(Item {
+            id: format!("diff_stat_{}", path).into(),
+            display: Line::from_iter(
+                [Span::raw(format!("{:path_width$} | ", path))]
+                    .into_iter()
+                    .chain(stat),
+            ),
+            depth: 1,
+            target_data: Some(TargetData::FileStat(delta.new_file.clone())),
+            ..Default::default()
+        });
+    }
+
+    let insertions = changes.iter().map(|(_, insertions, _)| insertions).sum();
+    let deletions = changes.iter().map(|(_, _, deletions)| deletions).sum();
+    let plural = |count: usize, one: &str, many: &str| {
+        let count_text = format_count(config, count);
+        format!("{} {}", count_text, if count == 1 { one } else { many })
+    };
+
+    let mut summary = vec![plural(changes.len(), "file changed", "files changed")];
+    if insertions > 0 {
+        summary.push(plural(insertions, "insertion(+)", "insertions(+)"));
+    }
+    if deletions > 0 {
+        summary.push(plural(deletions, "deletion(-)", "deletions(-)"));
+    }
+
+    items.push(Item {
+        id: "diff_stat_summary".into(),
+        display: Line::raw(summary.join(", ")),
+        depth: 1,
+        unselectable: true,
+        ..Default::default()
+    });
+
+    items
+}
+
+/// Shown below the diff once a file has been marked as reviewed.
+/// Kept at the bottom so that toggling it doesn't shift the cursor.
+fn review_progress(repo: &Repository, rev: &str, file_count: usize) -> Vec<Item> {
+    let reviewed = review::reviewed_files(repo, rev).len();
+    if reviewed == 0 {
+        return vec![];
+    }
+
+    vec![
+        items::blank_line(),
+        Item {
+            id: "review_progress".into(),
+            display: Line::raw(format!("Reviewed {}/{} files", reviewed, file_count)),
+            unselectable: true,
+            ..Default::default()
+        },
+    ]
+}
+
+fn mark_reviewed(repo: &Repository, rev: &str, items: Vec<Item>) -> Vec<Item> {
+    let reviewed = review::reviewed_files(repo, rev);
+
+    items
+        .into_iter()
+        .map(|item| match &item.target_data {
+            Some(TargetData::Delta(delta)) if reviewed.contains(&delta.new_file) => Item {
+                display: item
+                    .display
+                    .clone()
+                    .patch_style(Style::new().add_modifier(Modifier::DIM)),
+                ..item
+            },
+            _ => item,
+        })
+        .collect()
 }
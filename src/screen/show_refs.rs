@@ -1,61 +1,139 @@
 use std::{
+    cell::{Cell, RefCell},
+    cmp::Reverse,
     collections::{btree_map::Entry, BTreeMap},
-    iter,
+    fs, iter,
+    path::PathBuf,
     rc::Rc,
 };
 
-use super::Screen;
+use super::{Screen, ScreenKind};
 use crate::{
-    config::{Config, StyleConfigEntry},
+    config::{Config, RefsSort, StyleConfigEntry},
+    file_watcher::Changes,
     items::{self, Item, TargetData},
+    locale::format_count,
     Res,
 };
-use git2::{Reference, Repository};
+use etcetera::{choose_base_strategy, BaseStrategy};
+use git2::{Oid, Reference, Repository};
 use ratatui::{
     layout::Size,
     text::{Line, Span},
 };
 
-pub(crate) fn create(config: Rc<Config>, repo: Rc<Repository>, size: Size) -> Res<Screen> {
-    Screen::new(
+/// The order last picked with `CycleRefsSort`, if it's been saved.
+pub(crate) fn load_sort() -> Option<RefsSort> {
+    let saved = fs::read_to_string(sort_path()).ok()?;
+    [RefsSort::Name, RefsSort::CommitterDate, RefsSort::Ahead]
+        .into_iter()
+        .find(|sort| sort.name() == saved.trim())
+}
+
+pub(crate) fn save_sort(sort: RefsSort) -> Res<()> {
+    let path = sort_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    fs::write(path, sort.name())?;
+    Ok(())
+}
+
+fn sort_path() -> PathBuf {
+    choose_base_strategy()
+        .expect("Unable to find the data directory!")
+        .data_dir()
+        .join("gitu/refs_sort")
+}
+
+pub(crate) fn create(
+    config: Rc<Config>,
+    repo: Rc<Repository>,
+    size: Size,
+    sort: Rc<Cell<RefsSort>>,
+) -> Res<Screen> {
+    let filter = Rc::new(RefCell::new(String::new()));
+    let query = Rc::clone(&filter);
+
+    let mut screen = Screen::new(
         Rc::clone(&config),
         size,
         Box::new(move || {
             let style = &config.style;
+            let base = base_commit(&repo, &config.general.base_branch);
+            let query = query.borrow().to_lowercase();
+            let sort = sort.get();
+            let header = match sort {
+                RefsSort::Name => "Branches".to_string(),
+                RefsSort::CommitterDate => "Branches (most recent first)".to_string(),
+                RefsSort::Ahead => "Branches (most ahead of upstream first)".to_string(),
+            };
 
             Ok(iter::once(Item {
                 id: "local_branches".into(),
-                display: Line::styled("Branches".to_string(), &style.section_header),
+                display: Line::styled(header, &style.section_header),
                 section: true,
                 depth: 0,
                 ..Default::default()
             })
             .chain(create_references_section(
+                &config,
                 &repo,
                 Reference::is_branch,
                 &style.branch,
+                base,
+                &query,
+                sort,
             )?)
             .chain(create_remotes_sections(
+                &config,
                 &repo,
                 &style.section_header,
                 &style.remote,
+                base,
+                &query,
+                sort,
             )?)
             .chain(create_tags_section(
+                &config,
                 &repo,
                 &style.section_header,
                 &style.tag,
+                &query,
+                sort,
             )?)
             .collect())
         }),
-    )
+    )?;
+
+    screen.filter = Some(filter);
+    screen.watched = Changes {
+        refs: true,
+        ..Default::default()
+    };
+    screen.kind = Some(ScreenKind::Refs);
+    Ok(screen)
 }
 
 fn create_remotes_sections<'a>(
+    config: &'a Config,
     repo: &'a Repository,
     header_style: &'a StyleConfigEntry,
     item_style: &'a StyleConfigEntry,
+    base: Option<Oid>,
+    query: &str,
+    sort: RefsSort,
 ) -> Res<impl Iterator<Item = Item> + 'a> {
-    let all_remotes = create_references_section(repo, Reference::is_remote, item_style)?;
+    let all_remotes = create_references_section(
+        config,
+        repo,
+        Reference::is_remote,
+        item_style,
+        base,
+        query,
+        sort,
+    )?;
     let mut remotes = BTreeMap::new();
     for remote in all_remotes {
         let name = String::from_utf8_lossy(&repo.branch_remote_name(&remote.id)?).to_string();
@@ -75,10 +153,11 @@ fn create_remotes_sections<'a>(
         vec![
             items::blank_line(),
             Item {
-                id: name.into(),
+                id: name.clone().into(),
                 display: Line::styled(header, header_style),
                 section: true,
                 depth: 0,
+                target_data: Some(TargetData::Remote(name)),
                 ..Default::default()
             },
         ]
@@ -88,11 +167,22 @@ fn create_remotes_sections<'a>(
 }
 
 fn create_tags_section<'a>(
+    config: &'a Config,
     repo: &'a Repository,
     header_style: &'a StyleConfigEntry,
     item_style: &'a StyleConfigEntry,
+    query: &str,
+    sort: RefsSort,
 ) -> Res<impl Iterator<Item = Item> + 'a> {
-    let mut tags = create_references_section(repo, Reference::is_tag, item_style)?;
+    let mut tags = create_references_section(
+        config,
+        repo,
+        Reference::is_tag,
+        item_style,
+        None,
+        query,
+        sort,
+    )?;
     Ok(match tags.next() {
         Some(item) => vec![
             items::blank_line(),
@@ -112,31 +202,67 @@ fn create_tags_section<'a>(
 }
 
 fn create_references_section<'a, F>(
+    config: &'a Config,
     repo: &'a Repository,
     filter: F,
     style: &'a StyleConfigEntry,
+    base: Option<Oid>,
+    query: &str,
+    sort: RefsSort,
 ) -> Res<impl Iterator<Item = Item> + 'a>
 where
     F: FnMut(&Reference<'a>) -> bool + 'a,
 {
-    Ok(repo
+    let mut references = repo
         .references()?
         .filter_map(Result::ok)
         .filter(filter)
-        .map(move |reference| {
-            let shorthand = reference.shorthand().unwrap().to_owned();
+        .filter(|reference| {
+            reference
+                .shorthand()
+                .is_some_and(|shorthand| shorthand.to_lowercase().contains(query))
+        })
+        .collect::<Vec<_>>();
 
-            Item {
-                id: reference.name().unwrap().to_owned().into(),
-                display: Line::from(vec![
-                    create_prefix(repo, &reference),
-                    Span::styled(shorthand.clone(), style),
-                ]),
-                depth: 1,
-                target_data: Some(TargetData::Branch(shorthand)),
-                ..Default::default()
-            }
-        }))
+    references.sort_by(|a, b| a.name().cmp(&b.name()));
+    match sort {
+        RefsSort::Name => (),
+        RefsSort::CommitterDate => references.sort_by_cached_key(|reference| {
+            Reverse(
+                reference
+                    .peel_to_commit()
+                    .map(|commit| commit.committer().when().seconds())
+                    .ok(),
+            )
+        }),
+        RefsSort::Ahead => references.sort_by_cached_key(|reference| {
+            Reverse(upstream_ahead_behind(repo, reference).unwrap_or_default())
+        }),
+    }
+
+    Ok(references.into_iter().map(move |reference| {
+        let shorthand = reference.shorthand().unwrap().to_owned();
+        let counts = match sort {
+            RefsSort::Ahead => upstream_ahead_behind(repo, &reference),
+            _ => base_ahead_behind(repo, &reference, base),
+        };
+
+        Item {
+            id: reference.name().unwrap().to_owned().into(),
+            display: Line::from(vec![
+                create_prefix(repo, &reference),
+                Span::styled(shorthand.clone(), style),
+                create_ahead_behind(config, counts),
+            ]),
+            depth: 1,
+            target_data: Some(if reference.is_tag() {
+                TargetData::Tag(shorthand)
+            } else {
+                TargetData::Branch(shorthand)
+            }),
+            ..Default::default()
+        }
+    }))
 }
 
 fn create_prefix(repo: &Repository, reference: &Reference) -> Span<'static> {
@@ -154,3 +280,43 @@ fn create_prefix(repo: &Repository, reference: &Reference) -> Span<'static> {
         "  "
     })
 }
+
+fn base_commit(repo: &Repository, base_branch: &str) -> Option<Oid> {
+    if base_branch.is_empty() {
+        return None;
+    }
+
+    repo.revparse_single(base_branch)
+        .and_then(|object| object.peel_to_commit())
+        .map(|commit| commit.id())
+        .ok()
+}
+
+fn base_ahead_behind(
+    repo: &Repository,
+    reference: &Reference,
+    base: Option<Oid>,
+) -> Option<(usize, usize)> {
+    base.zip(reference.peel_to_commit().ok())
+        .and_then(|(base, commit)| repo.graph_ahead_behind(commit.id(), base).ok())
+}
+
+/// How far a local branch is ahead / behind the branch it tracks. Other refs have no upstream.
+fn upstream_ahead_behind(repo: &Repository, reference: &Reference) -> Option<(usize, usize)> {
+    let upstream = repo.branch_upstream_name(reference.name()?).ok()?;
+    let upstream = repo.refname_to_id(upstream.as_str()?).ok()?;
+    let commit = reference.peel_to_commit().ok()?;
+    repo.graph_ahead_behind(commit.id(), upstream).ok()
+}
+
+fn create_ahead_behind(config: &Config, counts: Option<(usize, usize)>) -> Span<'static> {
+    let count = |count| format_count(config, count);
+    let text = match counts {
+        Some((0, 0)) | None => String::new(),
+        Some((ahead, 0)) => format!(" ↑{}", count(ahead)),
+        Some((0, behind)) => format!(" ↓{}", count(behind)),
+        Some((ahead, behind)) => format!(" ↑{} ↓{}", count(ahead), count(behind)),
+    };
+
+    Span::raw(text)
+}
@@ -1,148 +1,532 @@
-use super::Screen;
+use super::{Screen, ScreenKind};
 use crate::{
-    config::Config,
-    git::{self, diff::Diff},
+    config::{Config, StatusSection},
+    file_watcher::Changes,
+    git::{self, conflicts::Conflicts, diff::Diff},
     git2_opts,
-    items::{self, Item, TargetData},
+    items::{self, Item, LogWalk, TargetData},
+    locale::format_count,
     Res,
 };
+use etcetera::{choose_base_strategy, BaseStrategy};
 use git2::Repository;
 use ratatui::{
     prelude::Size,
     text::{Line, Span},
 };
-use std::{path::PathBuf, rc::Rc};
+use regex::Regex;
+use std::{
+    cell::{Cell, RefCell},
+    fs, iter,
+    path::PathBuf,
+    rc::Rc,
+};
+
+/// How many stashes and recent commits are listed, adjustable while Gitu runs.
+pub(crate) struct ListLimits {
+    pub stashes: Cell<usize>,
+    pub recent_commits: Cell<usize>,
+}
+
+impl ListLimits {
+    /// Starts out from the config, or the limits last saved if `persist_list_limits` is enabled.
+    pub(crate) fn init(config: &Config) -> Self {
+        let general = &config.general;
+        let saved = general
+            .persist_list_limits
+            .enabled
+            .then(|| fs::read_to_string(list_limits_path()).ok())
+            .flatten()
+            .and_then(|saved| {
+                let (stashes, recent_commits) = saved.trim().split_once(' ')?;
+                Some((stashes.parse().ok()?, recent_commits.parse().ok()?))
+            });
+
+        let (stashes, recent_commits) =
+            saved.unwrap_or((general.stash_list_limit, general.recent_commits_limit));
+
+        Self {
+            stashes: Cell::new(stashes),
+            recent_commits: Cell::new(recent_commits),
+        }
+    }
+
+    pub(crate) fn save(&self) -> Res<()> {
+        let path = list_limits_path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::write(
+            path,
+            format!("{} {}", self.stashes.get(), self.recent_commits.get()),
+        )?;
+        Ok(())
+    }
+}
+
+fn list_limits_path() -> PathBuf {
+    choose_base_strategy()
+        .expect("Unable to find the data directory!")
+        .data_dir()
+        .join("gitu/list_limits")
+}
 
-pub(crate) fn create(config: Rc<Config>, repo: Rc<Repository>, size: Size) -> Res<Screen> {
-    Screen::new(
+pub(crate) fn create(
+    config: Rc<Config>,
+    repo: Rc<Repository>,
+    size: Size,
+    limits: Rc<ListLimits>,
+) -> Res<Screen> {
+    let filter = Rc::new(RefCell::new(String::new()));
+    let query = Rc::clone(&filter);
+    let diff_base = Rc::new(RefCell::new(String::new()));
+    let base = Rc::clone(&diff_base);
+
+    let changed = Rc::new(Cell::new(Changes::ALL));
+    let stale_since = Rc::clone(&changed);
+    // The items of each of `status_sections`, as last built
+    let built = RefCell::new(vec![
+        None::<Vec<Item>>;
+        config.general.status_sections.len()
+    ]);
+
+    let mut screen = Screen::new(
         Rc::clone(&config),
         size,
         Box::new(move || {
             let style = &config.style;
-            let statuses = repo.statuses(Some(&mut git2_opts::status(&repo)?))?;
+            let query = query.borrow();
+            let base = base.borrow();
+            let path_filter = path_filter(&query)?;
+            let mut built = built.borrow_mut();
 
-            let untracked_files = statuses
+            let changes = stale_since.replace(Changes::ALL);
+            let stale = config
+                .general
+                .status_sections
                 .iter()
-                .filter(|status| status.status().is_wt_new())
-                .map(|status| PathBuf::from(status.path().unwrap()))
+                .zip(built.iter())
+                .map(|(section, items)| items.is_none() || changes.intersects(&sources(section)))
                 .collect::<Vec<_>>();
+            let needs = |wanted: StatusSection| {
+                config
+                    .general
+                    .status_sections
+                    .iter()
+                    .zip(&stale)
+                    .any(|(&section, &stale)| stale && section == wanted)
+            };
 
-            let unmerged_files = statuses
-                .iter()
-                .filter(|status| status.status().is_conflicted())
-                .map(|status| PathBuf::from(status.path().unwrap()))
-                .collect::<Vec<_>>();
+            let (mut untracked_entries, mut untracked_files, mut unmerged_files) =
+                if needs(StatusSection::Untracked) || needs(StatusSection::Unmerged) {
+                    let statuses = repo.statuses(Some(&mut git2_opts::status(&repo)?))?;
 
-            let untracked = items_list(&config, untracked_files.clone());
-            let unmerged = items_list(&config, unmerged_files);
+                    // Like `git status`, directories with nothing tracked in them are listed as one entry
+                    let untracked_entries = statuses
+                        .iter()
+                        .filter(|status| status.status().is_wt_new())
+                        .map(|status| PathBuf::from(status.path().unwrap()))
+                        .collect::<Vec<_>>();
 
-            let items = if let Some(rebase) = git::rebase_status(&repo)? {
-                vec![Item {
-                    id: "rebase_status".into(),
-                    display: Line::styled(
-                        format!("Rebasing {} onto {}", rebase.head_name, &rebase.onto),
-                        &style.section_header,
-                    ),
-                    ..Default::default()
-                }]
-                .into_iter()
-            } else if let Some(merge) = git::merge_status(&repo)? {
-                vec![Item {
-                    id: "merge_status".into(),
-                    display: Line::styled(
-                        format!("Merging {}", &merge.head),
-                        &style.section_header,
-                    ),
-                    ..Default::default()
-                }]
-                .into_iter()
-            } else if let Some(revert) = git::revert_status(&repo)? {
-                vec![Item {
-                    id: "revert_status".into(),
-                    display: Line::styled(
-                        format!("Reverting {}", &revert.head),
-                        &style.section_header,
-                    ),
-                    ..Default::default()
-                }]
-                .into_iter()
-            } else {
-                branch_status_items(&config, &repo)?.into_iter()
+                    let untracked_files = if path_filter.is_some()
+                        || untracked_entries
+                            .iter()
+                            .any(|entry| entry.to_string_lossy().ends_with('/'))
+                    {
+                        repo.statuses(Some(git2_opts::status(&repo)?.recurse_untracked_dirs(true)))?
+                            .iter()
+                            .filter(|status| status.status().is_wt_new())
+                            .map(|status| PathBuf::from(status.path().unwrap()))
+                            .collect::<Vec<_>>()
+                    } else {
+                        vec![]
+                    };
+
+                    let unmerged_files = statuses
+                        .iter()
+                        .filter(|status| status.status().is_conflicted())
+                        .map(|status| PathBuf::from(status.path().unwrap()))
+                        .collect::<Vec<_>>();
+
+                    (untracked_entries, untracked_files, unmerged_files)
+                } else {
+                    Default::default()
+                };
+
+            let base = Some(base.as_str()).filter(|base| !base.is_empty());
+            let mut unstaged = needs(StatusSection::UnstagedChanges)
+                .then(|| git::diff_unstaged(&config, repo.as_ref(), base))
+                .transpose()?;
+            let mut staged = needs(StatusSection::StagedChanges)
+                .then(|| git::diff_staged(&config, repo.as_ref(), base))
+                .transpose()?;
+
+            if let Some(path_filter) = &path_filter {
+                // Matching files are listed individually, rather than the directories they're in
+                untracked_files.retain(|file| path_filter.is_match(&file.to_string_lossy()));
+                untracked_entries = std::mem::take(&mut untracked_files);
+                unmerged_files.retain(|file| path_filter.is_match(&file.to_string_lossy()));
+
+                for diff in [&mut unstaged, &mut staged].into_iter().flatten() {
+                    diff.deltas.retain(|delta| {
+                        [&delta.old_file, &delta.new_file]
+                            .iter()
+                            .any(|file| path_filter.is_match(&file.to_string_lossy()))
+                    });
+                }
             }
-            .chain(if untracked.is_empty() {
-                vec![]
-            } else {
-                vec![
-                    items::blank_line(),
-                    Item {
-                        id: "untracked".into(),
-                        display: Line::styled("Untracked files", &style.section_header),
-                        section: true,
-                        depth: 0,
-                        target_data: Some(TargetData::AllUntracked(untracked_files)),
-                        ..Default::default()
-                    },
-                ]
-            })
-            .chain(untracked)
-            .chain(if unmerged.is_empty() {
-                vec![]
-            } else {
-                vec![
-                    items::blank_line(),
-                    Item {
-                        id: "unmerged".into(),
-                        display: Line::styled("Unmerged", &style.section_header),
-                        section: true,
-                        depth: 0,
-                        ..Default::default()
-                    },
-                ]
-            })
-            .chain(unmerged)
-            .chain(create_status_section_items(
-                Rc::clone(&config),
-                "unstaged_changes",
-                Some(TargetData::AllUnstaged),
-                &git::diff_unstaged(&config, repo.as_ref())?,
-            ))
-            .chain(create_status_section_items(
-                Rc::clone(&config),
-                "staged_changes",
-                Some(TargetData::AllStaged),
-                &git::diff_staged(&config, repo.as_ref())?,
-            ))
-            .chain(create_stash_list_section_items(
-                Rc::clone(&config),
-                repo.as_ref(),
-                "stashes",
-            ))
-            .chain(create_log_section_items(
-                Rc::clone(&config),
-                repo.as_ref(),
-                "recent_commits",
-            ))
-            .collect();
+
+            // Acting on a whole section would also affect the files hidden by the filter
+            let section_target = |target| path_filter.is_none().then_some(target);
+
+            let untracked = untracked_items(&config, &untracked_entries, &untracked_files);
+            let unmerged = unmerged_items(&config, unmerged_files);
+
+            let mut sections = vec![];
+            for (i, section) in config.general.status_sections.iter().enumerate() {
+                if !stale[i] {
+                    sections.extend(built[i].iter().flatten().cloned());
+                    continue;
+                }
+
+                let items = match section {
+                    StatusSection::BranchStatus => branch_or_operation_status(&config, &repo)?,
+                    StatusSection::Untracked if !untracked.is_empty() => [
+                        items::blank_line(),
+                        Item {
+                            id: "untracked".into(),
+                            display: Line::styled("Untracked files", &style.section_header),
+                            section: true,
+                            depth: 0,
+                            target_data: Some(TargetData::AllUntracked(untracked_entries.clone())),
+                            ..Default::default()
+                        },
+                    ]
+                    .into_iter()
+                    .chain(untracked.iter().cloned())
+                    .collect(),
+                    StatusSection::Unmerged if !unmerged.is_empty() => [
+                        items::blank_line(),
+                        Item {
+                            id: "unmerged".into(),
+                            display: Line::styled("Unmerged", &style.section_header),
+                            section: true,
+                            depth: 0,
+                            ..Default::default()
+                        },
+                    ]
+                    .into_iter()
+                    .chain(unmerged.iter().cloned())
+                    .collect(),
+                    StatusSection::Untracked | StatusSection::Unmerged => vec![],
+                    StatusSection::UnstagedChanges => unstaged
+                        .iter()
+                        .flat_map(|diff| {
+                            create_status_section_items(
+                                Rc::clone(&config),
+                                "unstaged_changes",
+                                section_target(TargetData::AllUnstaged),
+                                diff,
+                                base,
+                            )
+                        })
+                        .collect(),
+                    StatusSection::StagedChanges => staged
+                        .iter()
+                        .flat_map(|diff| {
+                            create_status_section_items(
+                                Rc::clone(&config),
+                                "staged_changes",
+                                section_target(TargetData::AllStaged),
+                                diff,
+                                base,
+                            )
+                        })
+                        .collect(),
+                    StatusSection::Fixups => fixup_section_items(&config, &repo)?,
+                    StatusSection::Stashes => create_stash_list_section_items(
+                        Rc::clone(&config),
+                        repo.as_ref(),
+                        "stashes",
+                        limits.stashes.get(),
+                    )
+                    .collect(),
+                    StatusSection::RecentCommits => create_log_section_items(
+                        Rc::clone(&config),
+                        repo.as_ref(),
+                        "recent_commits",
+                        limits.recent_commits.get(),
+                    )
+                    .collect(),
+                };
+
+                sections.extend(items.iter().cloned());
+                built[i] = Some(items);
+            }
+
+            // Sections are separated by a blank line, which isn't needed above the first one
+            let first_selectable = sections
+                .iter()
+                .position(|item| !item.unselectable)
+                .unwrap_or(sections.len());
+            if !config
+                .general
+                .status_sections
+                .starts_with(&[StatusSection::BranchStatus])
+            {
+                sections.drain(..first_selectable);
+            }
+
+            let items = filter_header(&config, &query)
+                .into_iter()
+                .chain(diff_base_header(&config, base))
+                .chain(sections)
+                .collect();
 
             Ok(items)
         }),
-    )
+    )?;
+
+    screen.filter = Some(filter);
+    screen.diff_base = Some(diff_base);
+    screen.watched = Changes::ALL;
+    screen.changed = Some(changed);
+    screen.kind = Some(ScreenKind::Status);
+    Ok(screen)
+}
+
+/// What a section is made from, so that it's only rebuilt when that changed outside of Gitu.
+fn sources(section: &StatusSection) -> Changes {
+    match section {
+        // The ongoing rebase step, and conflicts being resolved
+        StatusSection::BranchStatus => Changes {
+            refs: true,
+            index: true,
+            ..Default::default()
+        },
+        StatusSection::Untracked | StatusSection::UnstagedChanges => Changes {
+            index: true,
+            worktree: true,
+            ..Default::default()
+        },
+        StatusSection::Unmerged => Changes {
+            index: true,
+            ..Default::default()
+        },
+        StatusSection::StagedChanges => Changes {
+            refs: true,
+            index: true,
+            ..Default::default()
+        },
+        StatusSection::Fixups | StatusSection::Stashes | StatusSection::RecentCommits => Changes {
+            refs: true,
+            ..Default::default()
+        },
+    }
+}
+
+/// Matches paths against a glob if the query has any `*` or `?`, otherwise as a substring.
+fn path_filter(query: &str) -> Res<Option<Regex>> {
+    if query.is_empty() {
+        return Ok(None);
+    }
+
+    let pattern = if query.contains(['*', '?']) {
+        format!(
+            "^{}$",
+            regex::escape(query)
+                .replace("\\*", ".*")
+                .replace("\\?", ".")
+        )
+    } else {
+        regex::escape(query)
+    };
+
+    Ok(Some(Regex::new(&pattern)?))
+}
+
+fn filter_header(config: &Config, query: &str) -> Vec<Item> {
+    if query.is_empty() {
+        return vec![];
+    }
+
+    vec![
+        Item {
+            id: "filter".into(),
+            display: Line::styled(
+                format!("Showing files matching {}", query),
+                &config.style.section_header,
+            ),
+            unselectable: true,
+            ..Default::default()
+        },
+        items::blank_line(),
+    ]
+}
+
+fn diff_base_header(config: &Config, base: Option<&str>) -> Vec<Item> {
+    let Some(base) = base else {
+        return vec![];
+    };
+
+    vec![
+        Item {
+            id: "diff_base".into(),
+            display: Line::styled(
+                format!("Comparing changes to {}", base),
+                &config.style.section_header,
+            ),
+            unselectable: true,
+            ..Default::default()
+        },
+        items::blank_line(),
+    ]
 }
 
-fn items_list(config: &Config, files: Vec<PathBuf>) -> Vec<Item> {
+fn branch_or_operation_status(config: &Config, repo: &Repository) -> Res<Vec<Item>> {
+    let style = &config.style;
+    let operation = if let Some(rebase) = git::rebase_status(repo)? {
+        let step = rebase
+            .step
+            .map(|(step, end)| {
+                format!(
+                    ", step {} of {}",
+                    format_count(config, step),
+                    format_count(config, end)
+                )
+            })
+            .unwrap_or_default();
+
+        Item {
+            id: "rebase_status".into(),
+            display: Line::styled(
+                format!(
+                    "Rebasing {} onto {}{}",
+                    rebase.head_name, &rebase.onto, step
+                ),
+                &style.section_header,
+            ),
+            ..Default::default()
+        }
+    } else if let Some(merge) = git::merge_status(repo)? {
+        Item {
+            id: "merge_status".into(),
+            display: Line::styled(format!("Merging {}", &merge.head), &style.section_header),
+            ..Default::default()
+        }
+    } else if let Some(revert) = git::revert_status(repo)? {
+        Item {
+            id: "revert_status".into(),
+            display: Line::styled(format!("Reverting {}", &revert.head), &style.section_header),
+            ..Default::default()
+        }
+    } else {
+        let mut items = branch_status_items(config, repo)?;
+        items.extend(branch_point_item(config, repo));
+        return Ok(items);
+    };
+
+    Ok(iter::once(operation).chain(conflicts_item(repo)?).collect())
+}
+
+fn conflicts_item(repo: &Repository) -> Res<Option<Item>> {
+    let Some(Conflicts { resolved, total }) = git::conflicts::conflicts(repo)? else {
+        return Ok(None);
+    };
+
+    Ok(Some(Item {
+        id: "conflicts_status".into(),
+        display: Line::raw(format!(
+            "Resolved {} of {} conflicted {}",
+            resolved,
+            total,
+            if total == 1 { "file" } else { "files" }
+        )),
+        depth: 1,
+        unselectable: true,
+        ..Default::default()
+    }))
+}
+
+/// Where the branch went off `general.base_branch`, and how far it's come since.
+fn branch_point_item(config: &Config, repo: &Repository) -> Option<Item> {
+    let branch_point = git::branch_point(config, repo).ok().flatten()?;
+    let head = repo.head().ok()?.target()?;
+    let (commits, _) = repo.graph_ahead_behind(head, branch_point).ok()?;
+    let short_id = repo.find_object(branch_point, None).ok()?.short_id().ok()?;
+
+    Some(Item {
+        id: "branch_status".into(),
+        display: Line::from(vec![
+            Span::raw(format!("Branched off '{}' at ", config.general.base_branch)),
+            Span::styled(short_id.as_str()?.to_string(), &config.style.hash),
+            Span::raw(format!(
+                ", {} {} since.",
+                format_count(config, commits),
+                if commits == 1 { "commit" } else { "commits" }
+            )),
+        ]),
+        depth: 1,
+        unselectable: true,
+        ..Default::default()
+    })
+}
+
+fn unmerged_items(config: &Config, files: Vec<PathBuf>) -> Vec<Item> {
     let style = &config.style;
     files
         .into_iter()
         .map(|path| Item {
             id: path.to_string_lossy().to_string().into(),
-            display: Line::styled(path.to_string_lossy().to_string(), &style.file_header),
+            display: Line::styled(items::display_path(config, &path), &style.file_header),
             depth: 1,
-            target_data: Some(items::TargetData::File(path)),
+            target_data: Some(items::TargetData::Unmerged(path)),
             ..Default::default()
         })
         .collect::<Vec<_>>()
 }
 
+fn untracked_items(config: &Config, entries: &[PathBuf], files: &[PathBuf]) -> Vec<Item> {
+    let style = &config.style;
+
+    entries
+        .iter()
+        .flat_map(|entry| {
+            let entry_str = entry.to_string_lossy().to_string();
+            let is_dir = entry_str.ends_with('/');
+
+            let files_in_dir = files
+                .iter()
+                .filter(move |file| is_dir && file.starts_with(entry))
+                .map(move |file| Item {
+                    id: file.to_string_lossy().to_string().into(),
+                    display: Line::styled(
+                        file.strip_prefix(entry)
+                            .unwrap_or(file)
+                            .to_string_lossy()
+                            .to_string(),
+                        &style.file_header,
+                    ),
+                    depth: 2,
+                    target_data: Some(TargetData::File(file.clone())),
+                    ..Default::default()
+                });
+
+            iter::once(Item {
+                id: entry_str.clone().into(),
+                display: Line::styled(items::display_path(config, entry), &style.file_header),
+                section: is_dir,
+                default_collapsed: is_dir,
+                depth: 1,
+                target_data: Some(TargetData::File(entry.clone())),
+                ..Default::default()
+            })
+            .chain(files_in_dir)
+        })
+        .collect()
+}
+
 fn branch_status_items(config: &Config, repo: &Repository) -> Res<Vec<Item>> {
     let style = &config.style;
     let Ok(head) = repo.head() else {
@@ -155,6 +539,28 @@ fn branch_status_items(config: &Config, repo: &Repository) -> Res<Vec<Item>> {
         }]);
     };
 
+    if repo.head_detached()? {
+        return Ok(vec![
+            Item {
+                id: "branch_status".into(),
+                display: Line::styled(
+                    format!("HEAD detached {}", git::detached_from(repo)?),
+                    &style.section_header,
+                ),
+                section: true,
+                depth: 0,
+                ..Default::default()
+            },
+            Item {
+                id: "branch_status".into(),
+                display: "Commits made here are on no branch, create one to keep them.".into(),
+                depth: 1,
+                unselectable: true,
+                ..Default::default()
+            },
+        ]);
+    }
+
     let mut items = vec![Item {
         id: "branch_status".into(),
         display: Line::styled(
@@ -166,6 +572,16 @@ fn branch_status_items(config: &Config, repo: &Repository) -> Res<Vec<Item>> {
         ..Default::default()
     }];
 
+    if repo.is_shallow() {
+        items.push(Item {
+            id: "branch_status".into(),
+            display: "This is a shallow clone, older history is missing.".into(),
+            depth: 1,
+            unselectable: true,
+            ..Default::default()
+        });
+    }
+
     let Ok(upstream) = repo.branch_upstream_name(head.name().unwrap()) else {
         return Ok(items);
     };
@@ -199,15 +615,17 @@ fn branch_status_items(config: &Config, repo: &Repository) -> Res<Vec<Item>> {
         } else if ahead > 0 && behind == 0 {
             Line::raw(format!(
                 "Your branch is ahead of '{}' by {} commit.",
-                upstream_shortname, ahead
+                upstream_shortname,
+                format_count(config, ahead)
             ))
         } else if ahead == 0 && behind > 0 {
             Line::raw(format!(
                 "Your branch is behind '{}' by {} commit.",
-                upstream_shortname, behind
+                upstream_shortname,
+                format_count(config, behind)
             ))
         } else {
-            Line::raw(format!("Your branch and '{}' have diverged,\nand have {} and {} different commits each, respectively.", upstream_shortname, ahead, behind))
+            Line::raw(format!("Your branch and '{}' have diverged,\nand have {} and {} different commits each, respectively.", upstream_shortname, format_count(config, ahead), format_count(config, behind)))
         },
         depth: 1,
         unselectable: true,
@@ -217,11 +635,15 @@ fn branch_status_items(config: &Config, repo: &Repository) -> Res<Vec<Item>> {
     Ok(items)
 }
 
+/// Compared to a `base` other than the index / HEAD, nothing in the section can be staged or
+/// discarded, as the changes listed aren't those of the index or worktree. Discarding a file
+/// "added" since the base would delete it.
 fn create_status_section_items<'a>(
     config: Rc<Config>,
     snake_case_header: &str,
     header_data: Option<TargetData>,
     diff: &'a Diff,
+    base: Option<&'a str>,
 ) -> impl Iterator<Item = Item> + 'a {
     let style = &config.style;
     if diff.deltas.is_empty() {
@@ -242,16 +664,30 @@ fn create_status_section_items<'a>(
                         &style.section_header,
                     ),
                     format!(" ({})", diff.deltas.len()).into(),
+                    base.map(|base| format!(" compared to {}", base))
+                        .unwrap_or_default()
+                        .into(),
                 ]),
                 section: true,
                 depth: 0,
-                target_data: header_data,
+                target_data: header_data.filter(|_| base.is_none()),
                 ..Default::default()
             },
         ]
     }
     .into_iter()
-    .chain(items::create_diff_items(config, diff, &1, true))
+    .chain(
+        items::create_diff_items(config, diff, &1, true).map(move |item| {
+            if base.is_some() {
+                Item {
+                    target_data: None,
+                    ..item
+                }
+            } else {
+                item
+            }
+        }),
+    )
 }
 
 fn capitalize(str: &str) -> String {
@@ -264,8 +700,9 @@ fn create_stash_list_section_items<'a>(
     config: Rc<Config>,
     repo: &Repository,
     snake_case_header: &str,
+    limit: usize,
 ) -> impl Iterator<Item = Item> + 'a {
-    let stashes = items::stash_list(&config, repo, 10).unwrap();
+    let stashes = items::stash_list(&config, repo, limit).unwrap();
     if stashes.is_empty() {
         vec![]
     } else {
@@ -288,10 +725,55 @@ fn create_stash_list_section_items<'a>(
     .chain(stashes)
 }
 
+/// Lists the `fixup!` / `squash!` / `amend!` commits left to squash, which `autosquash_fixups` takes care of.
+fn fixup_section_items(config: &Config, repo: &Repository) -> Res<Vec<Item>> {
+    let style = &config.style;
+    let Some((_, fixups)) = git::autosquash_commits(repo)? else {
+        return Ok(vec![]);
+    };
+    if fixups.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let mut items = vec![
+        items::blank_line(),
+        Item {
+            id: "fixups".into(),
+            display: Line::from(vec![
+                Span::styled("Fixups to autosquash", &style.section_header),
+                format!(" ({})", fixups.len()).into(),
+            ]),
+            section: true,
+            depth: 0,
+            ..Default::default()
+        },
+    ];
+
+    for oid in fixups {
+        let commit = repo.find_commit(oid)?;
+        let short_id = commit.as_object().short_id()?.as_str().unwrap().to_string();
+
+        items.push(Item {
+            id: format!("fixups_{}", oid).into(),
+            display: Line::from(vec![
+                Span::styled(short_id, &style.hash),
+                Span::raw(" "),
+                commit.summary().unwrap_or("").to_string().into(),
+            ]),
+            depth: 1,
+            target_data: Some(TargetData::Commit(oid.to_string())),
+            ..Default::default()
+        });
+    }
+
+    Ok(items)
+}
+
 fn create_log_section_items<'a>(
     config: Rc<Config>,
     repo: &Repository,
     snake_case_header: &str,
+    limit: usize,
 ) -> impl Iterator<Item = Item> + 'a {
     let style = &config.style;
     [
@@ -313,5 +795,5 @@ fn create_log_section_items<'a>(
         },
     ]
     .into_iter()
-    .chain(items::log(&config, repo, 10, None, None).unwrap())
+    .chain(items::log(&config, repo, limit, None, None, None, LogWalk::default()).unwrap())
 }
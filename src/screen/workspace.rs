@@ -0,0 +1,111 @@
+use super::Screen;
+use crate::{
+    config::Config,
+    git::workspace,
+    items::{Item, TargetData},
+    locale::format_count,
+    Res,
+};
+use etcetera::home_dir;
+use ratatui::{
+    layout::Size,
+    text::{Line, Span},
+};
+use std::{iter, path::PathBuf, rc::Rc};
+
+/// The directories given to `--workspace`, or the configured `workspace_dirs` if none were.
+pub(crate) fn dirs(args: &[PathBuf], config: &Config) -> Vec<PathBuf> {
+    if !args.is_empty() {
+        return args.to_vec();
+    }
+
+    config
+        .general
+        .workspace_dirs
+        .iter()
+        .map(|dir| match (dir.strip_prefix("~/"), home_dir()) {
+            (Some(rest), Ok(home)) => home.join(rest),
+            _ => PathBuf::from(dir),
+        })
+        .collect()
+}
+
+/// Lists the repositories in `dirs`, each with its branch, how far it's ahead / behind its upstream,
+/// and whether it has uncommitted changes. Opening one switches to it.
+pub(crate) fn create(config: Rc<Config>, size: Size, dirs: Vec<PathBuf>) -> Res<Screen> {
+    let mut screen = Screen::new(
+        Rc::clone(&config),
+        size,
+        Box::new(move || {
+            let style = &config.style;
+            let repos = workspace::find_repos(&dirs);
+            let names = repos
+                .iter()
+                .map(|path| {
+                    path.file_name()
+                        .map(|name| name.to_string_lossy().to_string())
+                        .unwrap_or_else(|| path.display().to_string())
+                })
+                .collect::<Vec<_>>();
+
+            let name_width = names.iter().map(|name| name.chars().count()).max();
+
+            Ok(iter::once(Item {
+                id: "repositories".into(),
+                display: Line::styled(
+                    format!("Repositories ({})", repos.len()),
+                    &style.section_header,
+                ),
+                section: true,
+                depth: 0,
+                ..Default::default()
+            })
+            .chain(repos.into_iter().zip(names).map(|(path, name)| {
+                let mut spans = vec![Span::raw(format!(
+                    "{:<width$} ",
+                    name,
+                    width = name_width.unwrap_or(0)
+                ))];
+
+                match workspace::summarize(&path) {
+                    Ok(summary) => {
+                        spans.push(match summary.branch {
+                            Some(branch) => Span::styled(branch, &style.branch),
+                            None => Span::raw("(detached)"),
+                        });
+
+                        if let Some((ahead, behind)) = summary.ahead_behind {
+                            if ahead > 0 {
+                                spans
+                                    .push(Span::raw(format!(" ↑{}", format_count(&config, ahead))));
+                            }
+                            if behind > 0 {
+                                spans.push(Span::raw(format!(
+                                    " ↓{}",
+                                    format_count(&config, behind)
+                                )));
+                            }
+                        }
+
+                        if summary.dirty {
+                            spans.push(Span::raw(" uncommitted changes"));
+                        }
+                    }
+                    Err(error) => spans.push(Span::raw(format!("! {}", error))),
+                }
+
+                Item {
+                    id: path.display().to_string().into(),
+                    display: Line::from(spans),
+                    depth: 1,
+                    target_data: Some(TargetData::Repo(path)),
+                    ..Default::default()
+                }
+            }))
+            .collect())
+        }),
+    )?;
+
+    screen.without_repo = true;
+    Ok(screen)
+}
@@ -0,0 +1,140 @@
+use crate::{
+    menu::{Menu, SavedArgs},
+    screen::{self, Screen, ScreenKind, View},
+    state::State,
+    Res,
+};
+use ratatui::layout::Size;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, path::PathBuf, rc::Rc};
+
+/// Kept in the git dir, as it's only about this repository.
+const FILE_NAME: &str = "gitu-state.json";
+
+/// What's left open when quitting, to pick up where things were next time,
+/// see `general.restore_session` and `general.persist_menu_args`.
+#[derive(Default, Debug, Serialize, Deserialize)]
+pub(crate) struct Session {
+    #[serde(default)]
+    screens: Vec<SavedScreen>,
+    #[serde(default)]
+    menu_args: BTreeMap<Menu, SavedArgs>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SavedScreen {
+    #[serde(flatten)]
+    kind: ScreenKind,
+    #[serde(default)]
+    view: View,
+}
+
+/// Sessions are kept per repository, so there's none while no repository is open.
+fn path(state: &State) -> Option<PathBuf> {
+    Some(state.repo.as_ref()?.path().join(FILE_NAME))
+}
+
+/// Saves the open screens, up until one that can't be opened again, and the args menus were left with.
+/// Either is left out if turned off in the config.
+pub(crate) fn save(state: &State) -> Res<()> {
+    let Some(path) = path(state) else {
+        return Ok(());
+    };
+
+    let general = &state.config.general;
+    let session = Session {
+        screens: if general.restore_session.enabled {
+            state
+                .screens
+                .iter()
+                .map_while(|screen| {
+                    Some(SavedScreen {
+                        kind: screen.kind.clone()?,
+                        view: screen.view(),
+                    })
+                })
+                .collect()
+        } else {
+            vec![]
+        },
+        menu_args: if general.persist_menu_args.enabled {
+            state.menu_args.clone()
+        } else {
+            BTreeMap::new()
+        },
+    };
+
+    fs::write(path, serde_json::to_string_pretty(&session)?)?;
+    Ok(())
+}
+
+/// Opens the screens of the last session in place of the current ones, if there are any to open
+/// and `screens` is set. Menu args are picked up if `general.persist_menu_args` is on.
+pub(crate) fn restore(state: &mut State, size: Size, screens: bool) -> Res<()> {
+    let Some(path) = path(state).filter(|path| path.exists()) else {
+        return Ok(());
+    };
+
+    let session: Session = serde_json::from_str(&fs::read_to_string(path)?)?;
+    if state.config.general.persist_menu_args.enabled {
+        state.menu_args = session.menu_args;
+    }
+
+    if !screens {
+        return Ok(());
+    }
+
+    let mut screens = vec![];
+    for saved in &session.screens {
+        // Like a commit that's gone, what's on top of it is left closed too
+        let mut screen = match create(state, &saved.kind, size) {
+            Ok(screen) => screen,
+            Err(error) => {
+                log::warn!("Couldn't restore {:?}: {}", saved.kind, error);
+                break;
+            }
+        };
+
+        screen.restore_view(&saved.view);
+        screens.push(screen);
+    }
+
+    if !screens.is_empty() {
+        state.screens = screens;
+    }
+
+    Ok(())
+}
+
+fn create(state: &State, kind: &ScreenKind, size: Size) -> Res<Screen> {
+    let config = Rc::clone(&state.config);
+    let repo = Rc::clone(state.repo());
+
+    match kind {
+        ScreenKind::Status => {
+            screen::status::create(config, repo, size, Rc::clone(&state.list_limits))
+        }
+        ScreenKind::Log {
+            limit,
+            rev,
+            since,
+            msg_regex,
+            walk,
+        } => {
+            let rev = rev.as_deref().map(git2::Oid::from_str).transpose()?;
+            let since = since.as_deref().map(git2::Oid::from_str).transpose()?;
+            let msg_regex = msg_regex.as_deref().map(Regex::new).transpose()?;
+            screen::log::create(config, repo, size, *limit, rev, since, msg_regex, *walk)
+        }
+        ScreenKind::Show { reference } => {
+            screen::show::create(config, repo, size, reference.clone())
+        }
+        ScreenKind::ShowRange { range } => {
+            screen::show::create_range(config, repo, size, range.clone())
+        }
+        ScreenKind::Refs => {
+            screen::show_refs::create(config, repo, size, Rc::clone(&state.refs_sort))
+        }
+    }
+}
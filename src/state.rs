@@ -1,19 +1,29 @@
+use std::cell::Cell;
+use std::collections::BTreeMap;
 use std::error::Error;
 use std::io::Read;
 use std::ops::DerefMut;
+use std::path::PathBuf;
 use std::process::Child;
 use std::process::Command;
+use std::process::ExitStatus;
 use std::process::Stdio;
 use std::rc::Rc;
 use std::sync::Arc;
 use std::sync::RwLock;
+use std::time::{Duration, Instant};
 
 use arboard::Clipboard;
+use chrono::{DateTime, FixedOffset, Local, TimeDelta};
 use crossterm::event;
 use crossterm::event::Event;
 use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
 use crossterm::event::KeyEventKind;
 use crossterm::event::KeyModifiers;
+use crossterm::event::MouseButton;
+use crossterm::event::MouseEvent;
+use crossterm::event::MouseEventKind;
 use git2::Repository;
 use ratatui::layout::Size;
 use tui_prompts::State as _;
@@ -21,60 +31,153 @@ use tui_prompts::Status;
 
 use crate::bindings::Bindings;
 use crate::cli;
+use crate::cmd_log;
 use crate::cmd_log::CmdLog;
 use crate::cmd_log::CmdLogEntry;
 use crate::config::Config;
+use crate::config::InitialScreen;
+use crate::config::RefsSort;
+use crate::file_watcher::FileWatcher;
+use crate::items::LogWalk;
 use crate::menu::Menu;
 use crate::menu::PendingMenu;
+use crate::menu::SavedArgs;
+use crate::op_log;
+use crate::op_log::OpLogEntry;
+use crate::ops;
 use crate::ops::Op;
 use crate::prompt;
+use crate::recent_repos;
 use crate::screen;
+use crate::screen::status::ListLimits;
 use crate::screen::Screen;
 use crate::term::Term;
 use crate::ui;
 
 use super::Res;
 
+/// How far a turn of the mouse wheel scrolls.
+const MOUSE_SCROLL_LINES: isize = 3;
+
 pub(crate) struct State {
-    pub repo: Rc<Repository>,
+    /// None until a repository is picked on the workspace screen, if Gitu was started outside of one.
+    pub repo: Option<Rc<Repository>>,
     pub config: Rc<Config>,
     pub bindings: Bindings,
-    pending_keys: Vec<(KeyModifiers, KeyCode)>,
+    pub(crate) pending_keys: Vec<(KeyModifiers, KeyCode)>,
+    /// When the last of `pending_keys` was pressed, to show key hints a while after.
+    pending_keys_at: Option<Instant>,
+    /// Whether key hints were shown when last drawn, to draw again once that changes.
+    key_hints_drawn: bool,
     pub quit: bool,
     pub screens: Vec<Screen>,
     pub pending_menu: Option<PendingMenu>,
-    pub pending_cmd: Option<(Child, Arc<RwLock<CmdLogEntry>>)>,
+    pub pending_cmd: Option<(Child, Arc<RwLock<CmdLogEntry>>, Instant)>,
     enable_async_cmds: bool,
     pub current_cmd_log: CmdLog,
     pub prompt: prompt::Prompt,
     pub clipboard: Option<Clipboard>,
+    pub(crate) list_limits: Rc<ListLimits>,
+    pub(crate) refs_sort: Rc<Cell<RefsSort>>,
+    /// Tells the commands run this time apart from earlier ones in the op log.
+    pub(crate) session_started: DateTime<FixedOffset>,
+    /// The last op that did something, to be run again by `RepeatLast`.
+    pub(crate) last_op: Option<Op>,
+    /// Set while `RepeatLast` runs, so that confirmations are answered right away.
+    pub(crate) repeating: bool,
+    /// Keys pressed since recording a macro started, if it's being recorded.
+    pub(crate) macro_recording: Option<Vec<KeyEvent>>,
+    pub(crate) recorded_macro: Vec<KeyEvent>,
+    /// Tells about changes made outside of Gitu, if `general.refresh_on_file_change` is on.
+    pub(crate) file_watcher: Option<FileWatcher>,
+    /// The args each menu was last closed with, if `general.persist_menu_args` is on.
+    pub(crate) menu_args: BTreeMap<Menu, SavedArgs>,
+    /// Repositories opened lately, most recent first, to switch between.
+    pub(crate) recent_repos: Vec<PathBuf>,
 }
 
 impl State {
     pub fn create(
-        repo: Rc<Repository>,
+        repo: Option<Rc<Repository>>,
         size: Size,
         args: &cli::Args,
         config: Rc<Config>,
         enable_async_cmds: bool,
     ) -> Res<Self> {
-        let screens = match args.command {
-            Some(cli::Commands::Show { ref reference }) => {
-                vec![screen::show::create(
-                    Rc::clone(&config),
-                    Rc::clone(&repo),
-                    size,
-                    reference.clone(),
-                )?]
-            }
-            None => vec![screen::status::create(
+        let list_limits = Rc::new(ListLimits::init(&config));
+        let refs_sort = Rc::new(Cell::new(config.general.refs_sort));
+
+        let screens = if args.workspace.is_some() {
+            vec![screen::workspace::create(
                 Rc::clone(&config),
-                Rc::clone(&repo),
                 size,
-            )?],
+                screen::workspace::dirs(args.workspace.as_deref().unwrap_or_default(), &config),
+            )?]
+        } else {
+            let repo = repo.as_ref().ok_or("No repository open")?;
+            match args.command {
+                Some(cli::Commands::Show { ref reference, .. })
+                    if screen::show::is_range(reference) =>
+                {
+                    vec![screen::show::create_range(
+                        Rc::clone(&config),
+                        Rc::clone(repo),
+                        size,
+                        reference.clone(),
+                    )?]
+                }
+                Some(cli::Commands::Show { ref reference, .. }) => {
+                    vec![screen::show::create(
+                        Rc::clone(&config),
+                        Rc::clone(repo),
+                        size,
+                        reference.clone(),
+                    )?]
+                }
+                Some(cli::Commands::Log) => vec![create_initial_screen(
+                    InitialScreen::Log,
+                    &config,
+                    repo,
+                    size,
+                    &list_limits,
+                    &refs_sort,
+                )?],
+                Some(
+                    cli::Commands::Diff { .. }
+                    | cli::Commands::Status { .. }
+                    | cli::Commands::Init { .. }
+                    | cli::Commands::Clone { .. },
+                ) => {
+                    vec![create_initial_screen(
+                        InitialScreen::Status,
+                        &config,
+                        repo,
+                        size,
+                        &list_limits,
+                        &refs_sort,
+                    )?]
+                }
+                Some(cli::Commands::Refs) => vec![create_initial_screen(
+                    InitialScreen::Refs,
+                    &config,
+                    repo,
+                    size,
+                    &list_limits,
+                    &refs_sort,
+                )?],
+                None => vec![create_initial_screen(
+                    args.screen.unwrap_or(config.general.initial_screen),
+                    &config,
+                    repo,
+                    size,
+                    &list_limits,
+                    &refs_sort,
+                )?],
+            }
         };
 
-        let bindings = Bindings::from(&config.bindings);
+        let bindings = Bindings::from(&config.bindings.menus);
+        let current_cmd_log = CmdLog::new(config.general.cmd_log_limit);
         let pending_menu = root_menu(&config).map(PendingMenu::init);
 
         let clipboard = Clipboard::new()
@@ -86,14 +189,26 @@ impl State {
             config,
             bindings,
             pending_keys: vec![],
+            pending_keys_at: None,
+            key_hints_drawn: false,
             enable_async_cmds,
             quit: false,
             screens,
             pending_cmd: None,
             pending_menu,
-            current_cmd_log: CmdLog::new(),
+            current_cmd_log,
             prompt: prompt::Prompt::new(),
             clipboard,
+            list_limits,
+            refs_sort,
+            session_started: Local::now().fixed_offset(),
+            last_op: None,
+            repeating: false,
+            macro_recording: None,
+            recorded_macro: vec![],
+            file_watcher: None,
+            menu_args: BTreeMap::new(),
+            recent_repos: vec![],
         })
     }
 
@@ -103,18 +218,25 @@ impl State {
                 Event::Resize(w, h) => {
                     for screen in self.screens.iter_mut() {
                         screen.size = Size::new(w, h);
+                        if screen.wrap_lines {
+                            screen.update()?;
+                        }
                     }
                 }
                 Event::Key(key) => {
-                    if self.prompt.state.is_focused() {
-                        self.prompt.state.handle_key_event(key)
-                    } else if key.kind == KeyEventKind::Press {
-                        if self.pending_cmd.is_none() {
-                            self.current_cmd_log.clear();
+                    if let Some(recording) = &mut self.macro_recording {
+                        if key.kind == KeyEventKind::Press {
+                            recording.push(key);
                         }
-
-                        self.handle_key_input(term, key)?;
                     }
+
+                    self.handle_key(term, key)?;
+                }
+                Event::Paste(ref text) if self.prompt.state.is_focused() => {
+                    self.prompt.paste(text);
+                }
+                Event::Mouse(mouse) if !self.prompt.state.is_focused() => {
+                    self.handle_mouse_input(mouse);
                 }
                 _ => (),
             }
@@ -122,20 +244,104 @@ impl State {
             self.update_prompt(term)?;
         }
 
-        let handle_pending_cmd_result = self.handle_pending_cmd();
+        let handle_pending_cmd_result = self.handle_pending_cmd(term);
         let pending_cmd_done = self
             .handle_result(handle_pending_cmd_result)
             .unwrap_or(true);
 
-        let needs_redraw = !events.is_empty() || pending_cmd_done;
+        let file_changes_result = self.handle_file_changes();
+        let files_changed = self.handle_result(file_changes_result).unwrap_or(true);
+
+        let key_hints_changed = self.key_hints_visible() != self.key_hints_drawn;
+
+        let needs_redraw =
+            !events.is_empty() || pending_cmd_done || files_changed || key_hints_changed;
 
         if needs_redraw && self.screens.last_mut().is_some() {
             term.draw(|frame| ui::ui(frame, self))?;
+            self.key_hints_drawn = self.key_hints_visible();
+        }
+
+        Ok(())
+    }
+
+    /// Refreshes the screen if it shows something that was changed outside of Gitu. Returns `true` if it did.
+    fn handle_file_changes(&mut self) -> Res<bool> {
+        // Changes keep piling up, and are seen to once done typing
+        if self
+            .config
+            .general
+            .refresh_on_file_change
+            .pause_while_editing
+            && self.prompt.state.is_focused()
+        {
+            return Ok(false);
+        }
+
+        let Some(changes) = self
+            .file_watcher
+            .as_mut()
+            .zip(self.repo.as_ref())
+            .and_then(|(watcher, repo)| watcher.take_changes(repo))
+        else {
+            return Ok(false);
+        };
+
+        // Refreshed once the command is done anyway
+        if self.pending_cmd.is_some() {
+            return Ok(false);
+        }
+
+        // The screens below are refreshed when returned to
+        let Some(screen) = self.screens.last_mut() else {
+            return Ok(false);
+        };
+
+        if !screen.watched.intersects(&changes) {
+            return Ok(false);
+        }
+
+        log::debug!("Refreshing after files changed: {:?}", changes);
+        screen.update_changed(changes)?;
+        Ok(true)
+    }
+
+    fn handle_key(&mut self, term: &mut Term, key: KeyEvent) -> Res<()> {
+        if self.prompt.state.is_focused() {
+            self.handle_prompt_key_input(key);
+        } else if key.kind == KeyEventKind::Press {
+            if self.pending_cmd.is_none() {
+                self.current_cmd_log.clear();
+            }
+
+            self.handle_key_input(term, key)?;
         }
 
         Ok(())
     }
 
+    /// Feeds the keys through as if they were pressed, prompts included.
+    pub(crate) fn replay_keys(&mut self, term: &mut Term, keys: &[KeyEvent]) -> Res<()> {
+        for &key in keys {
+            self.handle_key(term, key)?;
+            self.update_prompt(term)?;
+        }
+
+        Ok(())
+    }
+
+    fn handle_mouse_input(&mut self, mouse: MouseEvent) {
+        let screen = self.screen_mut();
+        match mouse.kind {
+            MouseEventKind::Moved => screen.hover(mouse.row),
+            MouseEventKind::Down(MouseButton::Left) => screen.click(mouse.row),
+            MouseEventKind::Drag(MouseButton::Left) => screen.drag(mouse.row),
+            MouseEventKind::ScrollUp => screen.scroll_by(-MOUSE_SCROLL_LINES),
+            MouseEventKind::ScrollDown => screen.scroll_by(MOUSE_SCROLL_LINES),
+            _ => (),
+        }
+    }
+
     fn update_prompt(&mut self, term: &mut Term) -> Res<()> {
         if self.prompt.state.status() == Status::Aborted {
             self.unhide_menu();
@@ -145,7 +351,8 @@ impl State {
 
             match result {
                 Ok(()) => {
-                    if self.prompt.state.is_focused() {
+                    // The update may have replaced the prompt with a new one
+                    if self.prompt.state.is_focused() && self.prompt.data.is_none() {
                         self.prompt.data = Some(prompt_data);
                     }
                 }
@@ -158,13 +365,50 @@ impl State {
         Ok(())
     }
 
-    fn handle_key_input(&mut self, term: &mut Term, key: event::KeyEvent) -> Res<()> {
-        let menu = match &self.pending_menu {
-            None => Menu::Root,
-            Some(menu) if menu.menu == Menu::Help => Menu::Root,
-            Some(menu) => menu.menu,
+    fn handle_prompt_key_input(&mut self, key: event::KeyEvent) {
+        let is_paste = key.kind == KeyEventKind::Press
+            && key.modifiers == KeyModifiers::CONTROL
+            && matches!(key.code, KeyCode::Char('v') | KeyCode::Char('y'));
+
+        if let Some(picker) = &mut self.prompt.picker {
+            match (key.code, key.modifiers) {
+                (KeyCode::Up, _) | (KeyCode::Char('p'), KeyModifiers::CONTROL) => {
+                    picker.select_previous();
+                    return;
+                }
+                (KeyCode::Down | KeyCode::Tab, _) | (KeyCode::Char('n'), KeyModifiers::CONTROL) => {
+                    picker.select_next();
+                    return;
+                }
+                _ => (),
+            }
+        }
+
+        if !is_paste {
+            let previous = self.prompt.state.value().to_string();
+            self.prompt.state.handle_key_event(key);
+
+            // Keeps what was picked when e.g. enter is pressed
+            if self.prompt.state.value() != previous {
+                self.prompt.update_picker();
+            }
+            return;
+        }
+
+        let text = match &mut self.clipboard {
+            Some(cb) => cb.get_text().map_err(|e| e.to_string()),
+            None => Err("Clipboard not available".to_string()),
         };
 
+        match text {
+            Ok(text) => self.prompt.paste(&text),
+            Err(error) => self.display_error(error),
+        }
+    }
+
+    fn handle_key_input(&mut self, term: &mut Term, key: event::KeyEvent) -> Res<()> {
+        let menu = self.bindings_menu();
+
         self.pending_keys.push((key.modifiers, key.code));
         let matching_bindings = self
             .bindings
@@ -182,12 +426,50 @@ impl State {
             [_, ..] => (),
         }
 
+        self.pending_keys_at = Some(Instant::now());
         Ok(())
     }
 
+    /// Whether to list what the keys pressed so far can go on with, as they're not a binding by themselves.
+    pub(crate) fn key_hints_visible(&self) -> bool {
+        let key_hints = &self.config.general.key_hints;
+        key_hints.enabled
+            && !self.pending_keys.is_empty()
+            && self
+                .pending_keys_at
+                .is_some_and(|at| at.elapsed() >= Duration::from_millis(key_hints.delay_ms))
+    }
+
+    /// The menu keys are matched in, the root one also while help is shown.
+    pub(crate) fn bindings_menu(&self) -> Menu {
+        match &self.pending_menu {
+            None => Menu::Root,
+            Some(menu) if menu.menu == Menu::Help => Menu::Root,
+            Some(menu) => menu.menu,
+        }
+    }
+
     pub(crate) fn handle_op(&mut self, op: Op, term: &mut Term) -> Res<()> {
+        if self.screen().without_repo && !op.works_without_repo() {
+            self.close_menu();
+            self.handle_result::<()>(Err("Open a repository first".into()));
+            return Ok(());
+        }
+
+        if op.needs_worktree() && self.repo().is_bare() {
+            self.close_menu();
+            self.handle_result::<()>(Err(
+                "Can't do that in a bare repository, there's no worktree".into(),
+            ));
+            return Ok(());
+        }
+
         let target = self.screen().get_selected_item().target_data.as_ref();
         if let Some(mut action) = op.clone().implementation().get_action(target) {
+            if op.is_repeatable() {
+                self.last_op = Some(op);
+            }
+
             let result = Rc::get_mut(&mut action).unwrap()(self, term);
             self.handle_result(result);
         }
@@ -208,6 +490,12 @@ impl State {
     }
 
     pub fn close_menu(&mut self) {
+        if self.config.general.persist_menu_args.enabled {
+            if let Some(pending) = self.pending_menu.as_ref().filter(|m| m.menu != Menu::Root) {
+                self.menu_args.insert(pending.menu, pending.saved_args());
+            }
+        }
+
         self.pending_menu = root_menu(&self.config).map(PendingMenu::init)
     }
 
@@ -215,6 +503,66 @@ impl State {
         self.screens.last_mut().expect("No screen")
     }
 
+    /// Opens another repository in place of the current one, starting over at its initial screen.
+    pub(crate) fn switch_repo(&mut self, repo: Repository, config: Config, size: Size) -> Res<()> {
+        let config = Rc::new(config);
+        let list_limits = Rc::new(ListLimits::init(&config));
+        self.open_repo(Rc::new(repo), config, list_limits, size)
+    }
+
+    /// Opens the current repository anew, like `switch_repo` does, so that nothing cached in it goes stale.
+    /// The config and list limits are kept.
+    pub(crate) fn reopen_repo(&mut self, size: Size) -> Res<()> {
+        let repo = Repository::open(self.repo().workdir().unwrap_or(self.repo().path()))?;
+        self.open_repo(
+            Rc::new(repo),
+            Rc::clone(&self.config),
+            Rc::clone(&self.list_limits),
+            size,
+        )
+    }
+
+    fn open_repo(
+        &mut self,
+        repo: Rc<Repository>,
+        config: Rc<Config>,
+        list_limits: Rc<ListLimits>,
+        size: Size,
+    ) -> Res<()> {
+        let screen = create_initial_screen(
+            config.general.initial_screen,
+            &config,
+            &repo,
+            size,
+            &list_limits,
+            &self.refs_sort,
+        )?;
+
+        // Only watched outside of tests and `--print`, where one's running already,
+        // unless no repository was open to watch before
+        let first_repo = self.repo.is_none() && config.general.refresh_on_file_change.enabled;
+        if self.file_watcher.is_some() || first_repo {
+            self.file_watcher = FileWatcher::new(&repo)
+                .inspect_err(|e| log::warn!("Couldn't watch for file changes: {}", e))
+                .ok();
+        }
+
+        recent_repos::record(&mut self.recent_repos, &repo);
+        self.bindings = Bindings::from(&config.bindings.menus);
+        self.screens = vec![screen];
+        self.repo = Some(repo);
+        self.config = config;
+        self.list_limits = list_limits;
+        self.last_op = None;
+        Ok(())
+    }
+
+    /// The open repository, which there is unless only the workspace screen has been shown.
+    /// Ops on a repository are disabled there, so they can count on it.
+    pub(crate) fn repo(&self) -> &Rc<Repository> {
+        self.repo.as_ref().expect("No repository open")
+    }
+
     pub fn screen(&self) -> &Screen {
         self.screens.last().expect("No screen")
     }
@@ -234,7 +582,7 @@ impl State {
     pub fn run_cmd(&mut self, term: &mut Term, input: &[u8], cmd: Command) -> Res<()> {
         self.run_cmd_async(term, input, cmd)?;
         self.await_pending_cmd()?;
-        self.handle_pending_cmd()?;
+        self.handle_pending_cmd(term)?;
         Ok(())
     }
 
@@ -245,7 +593,9 @@ impl State {
             return Err("A command is already running".into());
         }
 
-        cmd.current_dir(self.repo.workdir().expect("No workdir"));
+        if cmd.get_current_dir().is_none() {
+            cmd.current_dir(self.repo().workdir().unwrap_or(self.repo().path()));
+        }
 
         cmd.stdin(Stdio::piped());
         cmd.stdout(Stdio::piped());
@@ -259,7 +609,7 @@ impl State {
         use std::io::Write;
         child.stdin.take().unwrap().write_all(input)?;
 
-        self.pending_cmd = Some((child, log_entry));
+        self.pending_cmd = Some((child, log_entry, Instant::now()));
 
         if !self.enable_async_cmds {
             self.await_pending_cmd()?;
@@ -269,15 +619,15 @@ impl State {
     }
 
     fn await_pending_cmd(&mut self) -> Res<()> {
-        if let Some((child, _)) = &mut self.pending_cmd {
+        if let Some((child, _, _)) = &mut self.pending_cmd {
             child.wait()?;
         }
         Ok(())
     }
 
     /// Handles any pending_cmd in State without blocking. Returns `true` if a cmd was handled.
-    pub fn handle_pending_cmd(&mut self) -> Res<bool> {
-        let Some((ref mut child, ref mut log_rwlock)) = self.pending_cmd else {
+    pub fn handle_pending_cmd(&mut self, term: &mut Term) -> Res<bool> {
+        let Some((ref mut child, ref mut log_rwlock, started)) = self.pending_cmd else {
             return Ok(false);
         };
 
@@ -288,19 +638,73 @@ impl State {
         log::debug!("pending cmd finished with {:?}", status);
 
         let result = write_child_output_to_log(log_rwlock, child, status);
+        let args = match &*log_rwlock.read().unwrap() {
+            CmdLogEntry::Cmd { args, .. } => args.to_string(),
+            _ => unreachable!("pending_cmd is always CmdLogEntry::Cmd variant"),
+        };
+
+        let notification = &self.config.general.cmd_notification;
+        if started.elapsed().as_secs() >= notification.after_seconds {
+            let message = if status.success() {
+                format!("Done: {}", args)
+            } else {
+                format!("Failed: {}", args)
+            };
+
+            if let Some(text) = cmd_log::notification(notification.mode, &message) {
+                term.backend_mut().write_raw(&text)?;
+            }
+        }
+
         self.pending_cmd = None;
+        self.record_op(&args, started, status.code());
         self.screen_mut().update()?;
         result?;
 
         Ok(true)
     }
 
+    /// Makes a change Gitu does itself rather than with a git command, recording it in the op
+    /// log all the same. It's recorded as exiting with 1 if it fails.
+    pub(crate) fn run_in_process<T>(
+        &self,
+        description: &str,
+        change: impl FnOnce(&State) -> Res<T>,
+    ) -> Res<T> {
+        let started = Instant::now();
+        let result = change(self);
+        self.record_op(
+            description,
+            started,
+            Some(if result.is_ok() { 0 } else { 1 }),
+        );
+        result
+    }
+
+    /// Records a finished command in the op log, to be looked back on with `ShowOpLog`.
+    fn record_op(&self, args: &str, started: Instant, exit_code: Option<i32>) {
+        let duration = started.elapsed();
+        let entry = OpLogEntry {
+            session: self.session_started,
+            started: Local::now().fixed_offset()
+                - TimeDelta::from_std(duration).unwrap_or_default(),
+            duration,
+            exit_code,
+            args: args.to_string(),
+        };
+
+        op_log::record(self.repo(), &entry)
+            .unwrap_or_else(|error| log::warn!("Couldn't record in the op log: {}", error));
+    }
+
     pub fn run_cmd_interactive(&mut self, term: &mut Term, mut cmd: Command) -> Res<()> {
         if self.pending_cmd.is_some() {
             return Err("A command is already running".into());
         }
 
-        cmd.current_dir(self.repo.workdir().expect("No workdir"));
+        if cmd.get_current_dir().is_none() {
+            cmd.current_dir(self.repo().workdir().unwrap_or(self.repo().path()));
+        }
 
         cmd.stdin(Stdio::piped());
 
@@ -308,9 +712,28 @@ impl State {
         // disable raw mode temporarily for the git command
         term.backend().disable_raw_mode()?;
 
+        // The mouse is the child's to use, like an editor would
+        let mouse_support = self.config.general.mouse_support.enabled;
+        if mouse_support {
+            term.backend_mut().disable_mouse_capture()?;
+        }
+
+        let started = Instant::now();
         let child = cmd.spawn()?;
 
         let out = child.wait_with_output()?;
+        self.record_op(&cmd_log::command_args(&cmd), started, out.status.code());
+
+        if self
+            .config
+            .general
+            .refresh_on_file_change
+            .pause_while_editing
+        {
+            if let Some(watcher) = &mut self.file_watcher {
+                watcher.discard();
+            }
+        }
         let out_utf8 = String::from_utf8(out.stderr.clone())
             .expect("Error turning command output to String")
             .into();
@@ -320,6 +743,10 @@ impl State {
         // restore the raw mode
         term.backend().enable_raw_mode()?;
 
+        if mouse_support {
+            term.backend_mut().enable_mouse_capture()?;
+        }
+
         // Prevents cursor flash when exiting editor
         term.hide_cursor()?;
 
@@ -356,6 +783,49 @@ impl State {
     }
 }
 
+fn create_initial_screen(
+    initial_screen: InitialScreen,
+    config: &Rc<Config>,
+    repo: &Rc<Repository>,
+    size: Size,
+    list_limits: &Rc<ListLimits>,
+    refs_sort: &Rc<Cell<RefsSort>>,
+) -> Res<Screen> {
+    match initial_screen {
+        // There's no worktree to show the status of
+        InitialScreen::Status if repo.is_bare() => create_initial_screen(
+            InitialScreen::Log,
+            config,
+            repo,
+            size,
+            list_limits,
+            refs_sort,
+        ),
+        InitialScreen::Status => screen::status::create(
+            Rc::clone(config),
+            Rc::clone(repo),
+            size,
+            Rc::clone(list_limits),
+        ),
+        InitialScreen::Log => screen::log::create(
+            Rc::clone(config),
+            Rc::clone(repo),
+            size,
+            ops::log::DEFAULT_LIMIT as usize,
+            None,
+            None,
+            None,
+            LogWalk::default(),
+        ),
+        InitialScreen::Refs => screen::show_refs::create(
+            Rc::clone(config),
+            Rc::clone(repo),
+            size,
+            Rc::clone(refs_sort),
+        ),
+    }
+}
+
 pub(crate) fn root_menu(config: &Config) -> Option<Menu> {
     if config.general.always_show_help.enabled {
         Some(Menu::Help)
@@ -367,7 +837,7 @@ pub(crate) fn root_menu(config: &Config) -> Option<Menu> {
 fn write_child_output_to_log(
     log_rwlock: &mut Arc<RwLock<CmdLogEntry>>,
     child: &mut Child,
-    status: std::process::ExitStatus,
+    status: ExitStatus,
 ) -> Result<(), Box<dyn Error>> {
     let mut log = log_rwlock.write().unwrap();
 
@@ -2,8 +2,14 @@ use crate::config::Config;
 
 use itertools::Itertools;
 use ratatui::style::Style;
-use std::{cell::RefCell, collections::HashMap, iter, ops::Range, path::Path};
-use tree_sitter::Language;
+use std::{
+    cell::{OnceCell, RefCell},
+    collections::HashMap,
+    iter,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+use tree_sitter::{Language, Node, Parser, Point, Tree};
 use tree_sitter_highlight::{Highlight, HighlightConfiguration, HighlightEvent, Highlighter};
 
 const HIGHLIGHT_NAMES: &[&str] = &[
@@ -203,6 +209,12 @@ thread_local! {
     pub static LANG_CONFIGS: RefCell<HashMap<Language, HighlightConfiguration>> = RefCell::new(HashMap::new());
 }
 
+/// Drops the highlighters and their compiled queries, so they're created anew.
+pub(crate) fn clear_cache() {
+    LANG_CONFIGS.with_borrow_mut(HashMap::clear);
+    HIGHLIGHTER.set(Highlighter::new());
+}
+
 pub(crate) fn highlight<'a>(
     config: &'a Config,
     path: &'a Path,
@@ -246,3 +258,143 @@ pub(crate) fn highlight<'a>(
         })
     })
 }
+
+/// Kinds of syntax nodes that count as definitions, across the supported languages.
+const DEFINITION_KINDS: &[&str] = &[
+    "class",
+    "class_declaration",
+    "class_definition",
+    "class_specifier",
+    "constructor_declaration",
+    "enum_item",
+    "function_declaration",
+    "function_definition",
+    "function_item",
+    "impl_item",
+    "interface_declaration",
+    "method",
+    "method_declaration",
+    "method_definition",
+    "mod_item",
+    "module",
+    "namespace_declaration",
+    "namespace_definition",
+    "object_definition",
+    "singleton_method",
+    "struct_item",
+    "struct_specifier",
+    "trait_definition",
+    "trait_item",
+];
+
+/// A file that's parsed the first time definitions are looked up in it, as most never are.
+#[derive(Debug)]
+pub(crate) struct LazyTree {
+    path: PathBuf,
+    content: String,
+    tree: OnceCell<Option<Tree>>,
+}
+
+impl LazyTree {
+    pub(crate) fn new(path: PathBuf, content: String) -> Self {
+        Self {
+            path,
+            content,
+            tree: OnceCell::new(),
+        }
+    }
+
+    /// Like `enclosing_definitions`, but none if the language isn't known.
+    pub(crate) fn enclosing_definitions(&self, row: usize) -> Vec<String> {
+        self.tree
+            .get_or_init(|| parse(&self.path, &self.content))
+            .as_ref()
+            .map(|tree| enclosing_definitions(tree, &self.content, row))
+            .unwrap_or_default()
+    }
+}
+
+pub(crate) fn parse(path: &Path, content: &str) -> Option<Tree> {
+    let lang = determine_lang(path)?;
+    let mut parser = Parser::new();
+    parser.set_language(lang).ok()?;
+    parser.parse(content, None)
+}
+
+/// Names of the definitions (functions, types, ...) enclosing a line, outermost first.
+/// Unlike git's regex-based function context, this also sees through nesting.
+pub(crate) fn enclosing_definitions(tree: &Tree, content: &str, row: usize) -> Vec<String> {
+    let point = Point::new(row, 0);
+    let mut node = tree.root_node().descendant_for_point_range(point, point);
+    let mut names = vec![];
+
+    while let Some(current) = node {
+        if DEFINITION_KINDS.contains(&current.kind()) {
+            if let Some(name) = definition_name(current, content) {
+                names.push(name);
+            }
+        }
+
+        node = current.parent();
+    }
+
+    names.reverse();
+    names
+}
+
+fn definition_name(node: Node, content: &str) -> Option<String> {
+    // C-like functions are named by a (possibly nested) declarator, Rust impls by their type
+    let name_node = node
+        .child_by_field_name("name")
+        .or_else(|| {
+            let mut declarator = node.child_by_field_name("declarator")?;
+            while let Some(inner) = declarator.child_by_field_name("declarator") {
+                declarator = inner;
+            }
+            Some(declarator)
+        })
+        .or_else(|| node.child_by_field_name("type"))?;
+
+    Some(name_node.utf8_text(content.as_bytes()).ok()?.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{enclosing_definitions, parse};
+
+    #[test]
+    fn rust_nested_definitions() {
+        let content = "struct A;\n\nimpl A {\n    fn b() {\n        let c = 1;\n    }\n}\n";
+        let tree = parse(Path::new("a.rs"), content).unwrap();
+
+        assert_eq!(enclosing_definitions(&tree, content, 0), ["A"]);
+        assert_eq!(enclosing_definitions(&tree, content, 4), ["A", "b"]);
+        assert_eq!(
+            enclosing_definitions(&tree, content, 1),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn c_function_declarator() {
+        let content = "static int *add(int a, int b) {\n  return a + b;\n}\n";
+        let tree = parse(Path::new("a.c"), content).unwrap();
+
+        assert_eq!(enclosing_definitions(&tree, content, 1), ["add"]);
+    }
+
+    #[test]
+    fn python_class_method() {
+        let content = "class A:\n    def b(self):\n        return 1\n";
+        let tree = parse(Path::new("a.py"), content).unwrap();
+
+        assert_eq!(enclosing_definitions(&tree, content, 2), ["A", "b"]);
+    }
+
+    #[test]
+    fn unknown_language() {
+        assert!(parse(Path::new("a.unknown"), "").is_none());
+    }
+}
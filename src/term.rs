@@ -1,5 +1,6 @@
 use crate::Res;
 use crossterm::{
+    event::{DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture},
     terminal::{
         disable_raw_mode, enable_raw_mode, is_raw_mode_enabled, EnterAlternateScreen,
         LeaveAlternateScreen,
@@ -22,7 +23,10 @@ pub type Term = Terminal<TermBackend>;
 
 pub fn alternate_screen<T, F: Fn() -> Res<T>>(fun: F) -> Res<T> {
     stderr().execute(EnterAlternateScreen)?;
+    stderr().execute(EnableBracketedPaste)?;
     let result = fun();
+    stderr().execute(DisableMouseCapture)?;
+    stderr().execute(DisableBracketedPaste)?;
     stderr().execute(LeaveAlternateScreen)?;
     result
 }
@@ -43,7 +47,14 @@ pub fn raw_mode<T, F: Fn() -> Res<T>>(fun: F) -> Res<T> {
     result
 }
 
+pub fn enable_mouse_capture() -> Res<()> {
+    stderr().execute(EnableMouseCapture)?;
+    Ok(())
+}
+
 pub fn cleanup_alternate_screen() {
+    print_err(stderr().execute(DisableMouseCapture));
+    print_err(stderr().execute(DisableBracketedPaste));
     print_err(stderr().execute(LeaveAlternateScreen));
 }
 
@@ -68,6 +79,19 @@ pub enum TermBackend {
     Test(TestBackend),
 }
 
+impl TermBackend {
+    /// Writes something that isn't drawn, like a bell.
+    pub(crate) fn write_raw(&mut self, text: &str) -> io::Result<()> {
+        match self {
+            TermBackend::Crossterm(t) => {
+                io::Write::write_all(t, text.as_bytes())?;
+                io::Write::flush(t)
+            }
+            TermBackend::Test(_) => Ok(()),
+        }
+    }
+}
+
 impl Backend for TermBackend {
     fn draw<'a, I>(&mut self, content: I) -> io::Result<()>
     where
@@ -144,6 +168,20 @@ impl TermBackend {
         }
     }
 
+    pub fn enable_mouse_capture(&mut self) -> io::Result<()> {
+        match self {
+            TermBackend::Crossterm(c) => c.execute(EnableMouseCapture).map(|_| ()),
+            TermBackend::Test(_) => Ok(()),
+        }
+    }
+
+    pub fn disable_mouse_capture(&mut self) -> io::Result<()> {
+        match self {
+            TermBackend::Crossterm(c) => c.execute(DisableMouseCapture).map(|_| ()),
+            TermBackend::Test(_) => Ok(()),
+        }
+    }
+
     pub fn enable_raw_mode(&self) -> io::Result<()> {
         match self {
             TermBackend::Crossterm(_) => enable_raw_mode(),
@@ -1,50 +1,94 @@
-use crate::menu::arg::{self, Arg};
-
-#[test]
-fn flag_operations() {
-    let mut arg = Arg::new_flag("--arg", "display", true);
-
-    assert!(!arg.expects_value());
-    assert!(arg.is_active());
-    assert_eq!(arg.default_as_string(), None);
-    assert_eq!(arg.get_cli_token(), "--arg".to_string());
-
-    arg.unset();
-    assert!(!arg.expects_value());
-    assert!(!arg.is_active());
-    assert_eq!(arg.default_as_string(), None);
-    assert_eq!(arg.get_cli_token(), "--arg".to_string());
-
-    assert_eq!(arg.set("").ok(), Some(()));
-    assert!(arg.is_active());
-}
-
-#[test]
-fn arg_operations() {
-    let mut arg = Arg::new_arg("--arg", "display", Some(|| 1u32), arg::positive_number);
-
-    assert!(arg.expects_value());
-    assert!(arg.is_active());
-    assert_eq!(arg.default_as_string(), Some("1".to_string()));
-    assert_eq!(arg.get_cli_token(), "--arg=1".to_string());
-
-    arg.unset();
-    assert!(arg.expects_value());
-    assert!(!arg.is_active());
-    assert_eq!(arg.default_as_string(), Some("1".to_string()));
-    assert_eq!(arg.get_cli_token(), "--arg".to_string());
-
-    assert_eq!(arg.set("").ok(), None);
-    assert!(!arg.is_active());
-
-    assert_eq!(arg.set("1").ok(), Some(()));
-    assert!(arg.is_active());
-}
-
-#[test]
-fn value_as_concrete_type() {
-    let arg = Arg::new_arg("--arg", "display", Some(|| 1u32), arg::positive_number);
-
-    assert_eq!(arg.value_as::<String>(), None);
-    assert_eq!(arg.value_as::<u32>(), Some(&1u32));
-}
+use crate::menu::arg::{self, Arg};
+
+#[test]
+fn flag_operations() {
+    let mut arg = Arg::new_flag("--arg", "display", true);
+
+    assert!(!arg.expects_value());
+    assert!(arg.is_active());
+    assert_eq!(arg.default_as_string(), None);
+    assert_eq!(arg.get_cli_token(), "--arg".to_string());
+
+    arg.unset();
+    assert!(!arg.expects_value());
+    assert!(!arg.is_active());
+    assert_eq!(arg.default_as_string(), None);
+    assert_eq!(arg.get_cli_token(), "--arg".to_string());
+
+    assert_eq!(arg.set("").ok(), Some(()));
+    assert!(arg.is_active());
+}
+
+#[test]
+fn arg_operations() {
+    let mut arg = Arg::new_arg("--arg", "display", Some(|| 1u32), arg::positive_number);
+
+    assert!(arg.expects_value());
+    assert!(arg.is_active());
+    assert_eq!(arg.default_as_string(), Some("1".to_string()));
+    assert_eq!(arg.get_cli_token(), "--arg=1".to_string());
+
+    arg.unset();
+    assert!(arg.expects_value());
+    assert!(!arg.is_active());
+    assert_eq!(arg.default_as_string(), Some("1".to_string()));
+    assert_eq!(arg.get_cli_token(), "--arg".to_string());
+
+    assert_eq!(arg.set("").ok(), None);
+    assert!(!arg.is_active());
+
+    assert_eq!(arg.set("1").ok(), Some(()));
+    assert!(arg.is_active());
+}
+
+#[test]
+fn value_as_concrete_type() {
+    let arg = Arg::new_arg("--arg", "display", Some(|| 1u32), arg::positive_number);
+
+    assert_eq!(arg.value_as::<String>(), None);
+    assert_eq!(arg.value_as::<u32>(), Some(&1u32));
+}
+
+#[test]
+fn saved_args_restore() {
+    use crate::menu::{Menu, PendingMenu};
+
+    let mut menu = PendingMenu::init(Menu::Log);
+    menu.args.get_mut("-n").unwrap().set("10").unwrap();
+    menu.args.get_mut("--grep").unwrap().set("fix").unwrap();
+    let mut saved = menu.saved_args();
+    saved.insert("--gone".into(), None);
+
+    let mut menu = PendingMenu::init(Menu::Log);
+    menu.restore_args(&saved);
+    assert_eq!(menu.args["-n"].value_as::<u32>(), Some(&10u32));
+    assert_eq!(
+        menu.args["--grep"].value_as_string(),
+        Some("fix".to_string())
+    );
+
+    saved.insert("-n".into(), None);
+    menu.restore_args(&saved);
+    assert!(!menu.args["-n"].is_active());
+}
+
+#[test]
+fn saved_args_leave_out_force() {
+    use crate::menu::{Menu, PendingMenu};
+
+    let mut menu = PendingMenu::init(Menu::Push);
+    menu.args.get_mut("--force").unwrap().set("").unwrap();
+    menu.args
+        .get_mut("--force-with-lease")
+        .unwrap()
+        .set("")
+        .unwrap();
+    let saved = menu.saved_args();
+    assert!(!saved.contains_key("--force"));
+
+    let mut menu = PendingMenu::init(Menu::Push);
+    menu.restore_args(&[("--force".to_string(), Some(String::new()))].into());
+    assert!(!menu.args["--force"].is_active());
+    menu.restore_args(&saved);
+    assert!(menu.args["--force-with-lease"].is_active());
+}
@@ -0,0 +1,52 @@
+use super::*;
+use crate::state::State;
+
+fn setup() -> (TestContext, State) {
+    let mut ctx = TestContext::setup_clone();
+    let mut state = ctx.init_state_at_path(ctx.remote_dir.path().to_path_buf());
+    state.update(&mut ctx.term, &[]).unwrap();
+    (ctx, state)
+}
+
+#[test]
+fn bare_opens_log() {
+    let (ctx, _state) = setup();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn bare_show_refs() {
+    let (mut ctx, mut state) = setup();
+    state.update(&mut ctx.term, &keys("Y")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn bare_show_commit() {
+    let (mut ctx, mut state) = setup();
+    state.update(&mut ctx.term, &keys("<enter>")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn bare_create_branch() {
+    let (mut ctx, mut state) = setup();
+    state
+        .update(&mut ctx.term, &keys("bnfeature<enter>Y"))
+        .unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn bare_checkout_disabled() {
+    let (mut ctx, mut state) = setup();
+    state.update(&mut ctx.term, &keys("bb")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn bare_discard_disabled() {
+    let (mut ctx, mut state) = setup();
+    state.update(&mut ctx.term, &keys("<enter>jjjjjK")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
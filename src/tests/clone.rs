@@ -0,0 +1,61 @@
+use super::*;
+use crate::clone::{clone, default_dir};
+use temp_dir::TempDir;
+
+#[test]
+fn clone_with_progress() {
+    let mut ctx = TestContext::setup_clone();
+    let target = TempDir::new().unwrap();
+    let url = format!("file://{}", ctx.remote_dir.path().display());
+
+    let path = clone(
+        &mut ctx.term,
+        &url,
+        Some(&target.path().join("cloned")),
+        || Ok(None),
+    )
+    .unwrap();
+
+    let repo = git2::Repository::open(path).unwrap();
+    assert_eq!(repo.head().unwrap().shorthand(), Some("main"));
+    assert!(ctx.redact_buffer().contains("Receiving objects: 100%"));
+}
+
+#[test]
+fn clone_fails() {
+    let mut ctx = TestContext::setup_init();
+    let target = TempDir::new().unwrap();
+
+    let error = clone(
+        &mut ctx.term,
+        "file:///nonexistent",
+        Some(target.path()),
+        || Ok(None),
+    )
+    .unwrap_err();
+    assert!(error.to_string().contains("fatal:"));
+}
+
+#[test]
+fn clone_stopped() {
+    let mut ctx = TestContext::setup_clone();
+    let target = TempDir::new().unwrap();
+    let url = format!("file://{}", ctx.remote_dir.path().display());
+    let dir = target.path().join("cloned");
+    let mut events = keys("q").into_iter();
+
+    let error = clone(&mut ctx.term, &url, Some(&dir), || Ok(events.next())).unwrap_err();
+    assert_eq!(error.to_string(), "Clone stopped");
+    assert!(!dir.exists());
+}
+
+#[test]
+fn clone_default_dir() {
+    assert_eq!(
+        default_dir("https://github.com/altsem/gitu.git"),
+        Some("gitu")
+    );
+    assert_eq!(default_dir("https://github.com/altsem/gitu/"), Some("gitu"));
+    assert_eq!(default_dir("git@github.com:gitu.git"), Some("gitu"));
+    assert_eq!(default_dir("/"), None);
+}
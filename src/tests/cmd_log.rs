@@ -0,0 +1,48 @@
+use super::*;
+use std::os::unix::fs::PermissionsExt;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    run(ctx.dir.path(), &["touch", "a", "b"]);
+    ctx
+}
+
+#[test]
+fn cmd_log() {
+    snapshot!(setup(), "jjss$");
+}
+
+#[test]
+fn cmd_log_nothing_run() {
+    snapshot!(setup(), "$");
+}
+
+#[test]
+fn cmd_log_long_output() {
+    let ctx = setup();
+    let hook = ctx.dir.child(".git/hooks/pre-commit");
+    fs::write(&hook, "#!/bin/sh\nseq 1 20\n").unwrap();
+    fs::set_permissions(&hook, fs::Permissions::from_mode(0o755)).unwrap();
+    run(ctx.dir.path(), &["git", "add", "a"]);
+    snapshot!(ctx, "Cadd a<enter>");
+}
+
+#[test]
+fn cmd_log_long_output_in_full() {
+    let ctx = setup();
+    let hook = ctx.dir.child(".git/hooks/pre-commit");
+    fs::write(&hook, "#!/bin/sh\nseq 1 20\n").unwrap();
+    fs::set_permissions(&hook, fs::Permissions::from_mode(0o755)).unwrap();
+    run(ctx.dir.path(), &["git", "add", "a"]);
+    snapshot!(ctx, "Cadd a<enter>$");
+}
+
+#[test]
+fn cmd_log_collapsed_output() {
+    let ctx = setup();
+    let hook = ctx.dir.child(".git/hooks/pre-commit");
+    fs::write(&hook, "#!/bin/sh\nseq 1 20\n").unwrap();
+    fs::set_permissions(&hook, fs::Permissions::from_mode(0o755)).unwrap();
+    run(ctx.dir.path(), &["git", "add", "a"]);
+    snapshot!(ctx, "Cadd a<enter>$<tab>");
+}
@@ -1,4 +1,5 @@
 use super::*;
+use std::os::unix::fs::PermissionsExt;
 
 #[test]
 fn commit_instant_fixup() {
@@ -32,3 +33,300 @@ fn commit_instant_fixup_stashes_changes_and_keeps_empty() {
 
     insta::assert_snapshot!(ctx.redact_buffer());
 }
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "file-a", "mistake\n");
+    commit(ctx.dir.path(), "file-b", "");
+    fs::write(ctx.dir.child("file-a"), "fixed\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "."]);
+    fs::write(ctx.dir.child("file-a"), "unstaged\n").unwrap();
+    ctx
+}
+
+#[test]
+fn revise_fixup() {
+    let mut ctx = setup();
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("jjjjjjjcx")).unwrap();
+
+    let repo = git2::Repository::open(ctx.dir.path()).unwrap();
+    let fixed = repo.revparse_single("HEAD~:file-a").unwrap();
+    assert_eq!(fixed.peel_to_blob().unwrap().content(), b"fixed\n");
+    assert_eq!(
+        fs::read_to_string(ctx.dir.child("file-a")).unwrap(),
+        "unstaged\n"
+    );
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn revise_reword() {
+    let mut ctx = setup();
+    run(ctx.dir.path(), &["git", "config", "core.editor", "true"]);
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("jjjjjjjcr")).unwrap();
+
+    let repo = git2::Repository::open(ctx.dir.path()).unwrap();
+    let reworded = repo.revparse_single("HEAD~").unwrap();
+    assert_eq!(
+        reworded.peel_to_commit().unwrap().message(),
+        Some("add file-a\n\nCommit body goes here\n")
+    );
+    assert_eq!(
+        fs::read_to_string(ctx.dir.child("file-a")).unwrap(),
+        "unstaged\n"
+    );
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn revise_reword_lint() {
+    let mut ctx = setup();
+    ctx.config().general.commit_lint.enabled = true;
+    ctx.config().general.commit_lint.max_subject_length = 5;
+    run(ctx.dir.path(), &["git", "config", "core.editor", "true"]);
+    snapshot!(ctx, "jjjjjjjcr");
+}
+
+#[test]
+fn revise_refuses_signing() {
+    let ctx = setup();
+    run(ctx.dir.path(), &["git", "config", "commit.gpgSign", "true"]);
+    snapshot!(ctx, "jjjjjjjcx");
+}
+
+#[test]
+fn commit_reword() {
+    let mut ctx = TestContext::setup_init();
+    let mut state = ctx.init_state();
+
+    commit(ctx.dir.path(), "first.txt", "");
+    commit(ctx.dir.path(), "second.txt", "");
+    commit(ctx.dir.path(), "third.txt", "");
+    run(ctx.dir.path(), &["git", "config", "core.editor", "true"]);
+    state.update(&mut ctx.term, &keys("gjjjcw")).unwrap();
+
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn commit_with_prefix_picker() {
+    snapshot!(TestContext::setup_clone(), "c:");
+}
+
+#[test]
+fn commit_with_prefix_picker_filtered() {
+    snapshot!(TestContext::setup_clone(), "c:bg");
+}
+
+#[test]
+fn commit_with_prefix_typed() {
+    let ctx = TestContext::setup_clone();
+    run(ctx.dir.path(), &["git", "config", "core.editor", "true"]);
+    fs::write(ctx.dir.child("new-file"), "hello\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "."]);
+    snapshot!(ctx, "c:bug<enter>");
+}
+
+#[test]
+fn commit_template() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.commit_template = "add new-file\n\nBody".into();
+    run(ctx.dir.path(), &["git", "config", "core.editor", "true"]);
+    fs::write(ctx.dir.child("new-file"), "hello\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "."]);
+    snapshot!(ctx, "cc");
+}
+
+#[test]
+fn commit_template_verbose() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.commit_template = "add new-file".into();
+    run(ctx.dir.path(), &["git", "config", "core.editor", "true"]);
+    fs::write(ctx.dir.child("new-file"), "hello\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "."]);
+    snapshot!(ctx, "c-vc");
+}
+
+#[test]
+fn commit_lint_asks_before_committing() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.commit_template = "a subject that is way too long\nbody".into();
+    ctx.config().general.commit_lint.enabled = true;
+    ctx.config().general.commit_lint.max_subject_length = 20;
+    run(ctx.dir.path(), &["git", "config", "core.editor", "true"]);
+    fs::write(ctx.dir.child("new-file"), "hello\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "."]);
+    snapshot!(ctx, "cc");
+}
+
+#[test]
+fn commit_lint_override() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.commit_template = "a subject that is way too long\nbody".into();
+    ctx.config().general.commit_lint.enabled = true;
+    ctx.config().general.commit_lint.max_subject_length = 20;
+    run(ctx.dir.path(), &["git", "config", "core.editor", "true"]);
+    fs::write(ctx.dir.child("new-file"), "hello\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "."]);
+    snapshot!(ctx, "ccy");
+}
+
+#[test]
+fn commit_message_comment_char() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.commit_template = "add new-file\n; a comment\n# not a comment".into();
+    run(ctx.dir.path(), &["git", "config", "core.editor", "true"]);
+    fs::write(ctx.dir.child("new-file"), "hello\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "."]);
+    run(ctx.dir.path(), &["git", "config", "core.commentChar", ";"]);
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("c-vc")).unwrap();
+
+    let repo = git2::Repository::open(ctx.dir.path()).unwrap();
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    assert_eq!(head.message(), Some("add new-file\n# not a comment\n"));
+}
+
+#[test]
+fn commit_amend_lint() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.commit_lint.enabled = true;
+    ctx.config().general.commit_lint.max_subject_length = 5;
+    run(ctx.dir.path(), &["git", "config", "core.editor", "true"]);
+    snapshot!(ctx, "ca");
+}
+
+#[test]
+fn commit_quick_prompt() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "tracked-file", "");
+    fs::write(ctx.dir.child("tracked-file"), "changed\n").unwrap();
+    fs::write(ctx.dir.child("untracked-file"), "").unwrap();
+    snapshot!(ctx, "C");
+}
+
+#[test]
+fn commit_quick() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "tracked-file", "");
+    fs::write(ctx.dir.child("tracked-file"), "changed\n").unwrap();
+    fs::write(ctx.dir.child("untracked-file"), "").unwrap();
+    snapshot!(ctx, "Cupdate tracked-file<enter>");
+}
+
+#[test]
+fn commit_quick_hook_colors() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "tracked-file", "");
+    fs::write(ctx.dir.child("tracked-file"), "changed\n").unwrap();
+    fs::write(ctx.dir.child("untracked-file"), "").unwrap();
+    let hook = ctx.dir.child(".git/hooks/pre-commit");
+    fs::write(
+        &hook,
+        "#!/bin/sh\nprintf '\\033[32mAll checks passed\\033[0m\\n'\n",
+    )
+    .unwrap();
+    fs::set_permissions(&hook, fs::Permissions::from_mode(0o755)).unwrap();
+    snapshot!(ctx, "Cupdate tracked-file<enter>");
+}
+
+#[test]
+fn commit_quick_empty_subject() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "tracked-file", "");
+    fs::write(ctx.dir.child("tracked-file"), "changed\n").unwrap();
+    fs::write(ctx.dir.child("untracked-file"), "").unwrap();
+    snapshot!(ctx, "C<enter>");
+}
+
+#[test]
+fn commit_wip() {
+    let ctx = TestContext::setup_clone();
+    fs::write(ctx.dir.child("initial-file"), "changed\n").unwrap();
+    fs::write(ctx.dir.child("untracked-file"), "").unwrap();
+    snapshot!(ctx, "cW");
+}
+
+#[test]
+fn squash_wip() {
+    let mut ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "file-1", "");
+    for file in ["file-2", "file-3"] {
+        fs::write(ctx.dir.child(file), "").unwrap();
+        run(ctx.dir.path(), &["git", "add", file]);
+        run(ctx.dir.path(), &["git", "commit", "-m", "WIP: checkpoint"]);
+    }
+    ctx.config().general.commit_template = "add file-2 and file-3".into();
+    run(ctx.dir.path(), &["git", "config", "core.editor", "true"]);
+    snapshot!(ctx, "cS");
+}
+
+#[test]
+fn squash_wip_aborted() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "file-1", "");
+    for file in ["file-2", "file-3"] {
+        fs::write(ctx.dir.child(file), "").unwrap();
+        run(ctx.dir.path(), &["git", "add", file]);
+        run(ctx.dir.path(), &["git", "commit", "-m", "WIP: checkpoint"]);
+    }
+    run(ctx.dir.path(), &["git", "config", "core.editor", "true"]);
+    snapshot!(ctx, "cS");
+}
+
+#[test]
+fn squash_wip_lint_declined() {
+    let mut ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "file-1", "");
+    for file in ["file-2", "file-3"] {
+        fs::write(ctx.dir.child(file), "").unwrap();
+        run(ctx.dir.path(), &["git", "add", file]);
+        run(ctx.dir.path(), &["git", "commit", "-m", "WIP: checkpoint"]);
+    }
+    ctx.config().general.commit_template = "a subject that is way too long".into();
+    ctx.config().general.commit_lint.enabled = true;
+    ctx.config().general.commit_lint.max_subject_length = 20;
+    run(ctx.dir.path(), &["git", "config", "core.editor", "true"]);
+    snapshot!(ctx, "cSn");
+}
+
+#[test]
+fn squash_wip_none() {
+    snapshot!(TestContext::setup_clone(), "cS");
+}
+
+#[test]
+fn commit_menu_key_hints() {
+    let mut ctx = TestContext::setup_init();
+    ctx.config().general.key_hints.delay_ms = 0;
+    snapshot!(ctx, "c-");
+}
+
+#[test]
+fn commit_amend_fixup() {
+    let mut ctx = setup();
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("jjjjjjjcA")).unwrap();
+
+    let repo = git2::Repository::open(ctx.dir.path()).unwrap();
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    assert_eq!(head.summary(), Some("amend! add file-a"));
+    let fixed = repo.revparse_single("HEAD:file-a").unwrap();
+    assert_eq!(fixed.peel_to_blob().unwrap().content(), b"fixed\n");
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn commit_reword_fixup() {
+    let mut ctx = setup();
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("jjjjjjjcR")).unwrap();
+
+    let repo = git2::Repository::open(ctx.dir.path()).unwrap();
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    assert_eq!(head.summary(), Some("amend! add file-a"));
+    assert_eq!(head.tree_id(), head.parent(0).unwrap().tree_id());
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
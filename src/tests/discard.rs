@@ -1,4 +1,5 @@
 use super::*;
+use crate::config::DiscardToTrash;
 
 #[test]
 pub(crate) fn discard_branch_confirm_prompt() {
@@ -28,6 +29,22 @@ pub(crate) fn discard_untracked_file() {
     snapshot!(ctx, "jjKy");
 }
 
+#[test]
+pub(crate) fn discard_untracked_file_to_backup() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.discard_to_trash = DiscardToTrash::Backup;
+    fs::write(ctx.dir.child("some-file"), "precious\n").unwrap();
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("jjKy")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+
+    let backups = fs::read_dir(state.repo().path().join("gitu-trash"))
+        .unwrap()
+        .map(|entry| fs::read_to_string(entry.unwrap().path().join("some-file")).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(backups, ["precious\n"]);
+}
+
 #[test]
 pub(crate) fn discard_untracked_staged_file() {
     let ctx = TestContext::setup_clone();
@@ -80,3 +97,29 @@ pub(crate) fn discard_staged_file() {
 //         .unwrap();
 //     insta::assert_snapshot!(ctx.redact_buffer());
 // }
+
+#[test]
+fn discard_untracked_dir() {
+    let ctx = TestContext::setup_init();
+    fs::create_dir_all(ctx.dir.child("new-dir/nested")).unwrap();
+    run(
+        ctx.dir.path(),
+        &["touch", "new-dir/file-a", "new-dir/nested/file-b"],
+    );
+    snapshot!(ctx, "jjKy");
+}
+
+#[test]
+pub(crate) fn discard_preview() {
+    let ctx = TestContext::setup_clone();
+    fs::write(ctx.dir.child("initial-file"), "changed\n").unwrap();
+    snapshot!(ctx, "jjK");
+}
+
+#[test]
+fn discard_preview_keeps_path_case() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "README.md", "hello\n");
+    fs::write(ctx.dir.child("README.md"), "changed\n").unwrap();
+    snapshot!(ctx, "jjK");
+}
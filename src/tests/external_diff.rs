@@ -0,0 +1,52 @@
+use super::*;
+use std::os::unix::fs::PermissionsExt;
+use temp_dir::TempDir;
+
+/// Prints the path, then the changes as `diff` sees them.
+const TOOL: &str = "#!/bin/sh\nprintf '\\033[1m%s\\033[0m\\n' \"$1\"\ndiff \"$2\" \"$5\"\n";
+
+fn setup() -> (TestContext, TempDir) {
+    let mut ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "file.txt", "one\ntwo\n");
+    fs::write(ctx.dir.child("file.txt"), "one\nthree\n").unwrap();
+
+    let tool_dir = TempDir::new().unwrap();
+    let tool = tool_dir.child("tool");
+    fs::write(&tool, TOOL).unwrap();
+    fs::set_permissions(&tool, fs::Permissions::from_mode(0o755)).unwrap();
+    ctx.config().general.external_diff = tool.to_string_lossy().into_owned();
+
+    (ctx, tool_dir)
+}
+
+#[test]
+fn external_diff() {
+    let (ctx, _tool_dir) = setup();
+    snapshot!(ctx, "=jj<tab>");
+}
+
+#[test]
+fn external_diff_toggled_off() {
+    let (ctx, _tool_dir) = setup();
+    snapshot!(ctx, "=jj<tab>=");
+}
+
+#[test]
+fn external_diff_stage() {
+    let (ctx, _tool_dir) = setup();
+    snapshot!(ctx, "=jj<tab>jjs");
+}
+
+#[test]
+fn external_diff_not_configured() {
+    snapshot!(TestContext::setup_clone(), "=");
+}
+
+#[test]
+fn external_diff_missing_tool() {
+    let mut ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "file.txt", "one\n");
+    fs::write(ctx.dir.child("file.txt"), "two\n").unwrap();
+    ctx.config().general.external_diff = "gitu-no-such-tool".into();
+    snapshot!(ctx, "=");
+}
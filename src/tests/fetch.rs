@@ -9,3 +9,51 @@ fn fetch_from_elsewhere_prompt() {
 fn fetch_from_elsewhere() {
     snapshot!(TestContext::setup_clone(), "feorigin<enter>");
 }
+
+#[test]
+fn fetch_remote_branch() {
+    let ctx = TestContext::setup_clone();
+    clone_and_commit(&ctx.remote_dir, "remote-file", "hello");
+    snapshot!(ctx, "frorigin/main<enter>");
+}
+
+#[test]
+fn fetch_refspec() {
+    let ctx = TestContext::setup_clone();
+    clone_and_commit(&ctx.remote_dir, "remote-file", "hello");
+    snapshot!(ctx, "frorigin main:fetched<enter>");
+}
+
+#[test]
+fn fetch_refspec_without_remote() {
+    snapshot!(TestContext::setup_clone(), "frnope<enter>");
+}
+
+#[test]
+fn shallow_status() {
+    let ctx = TestContext::setup_clone();
+    clone_and_commit(&ctx.remote_dir, "remote-file", "hello");
+    run(ctx.dir.path(), &["git", "fetch", "--depth=1"]);
+    snapshot!(ctx, "");
+}
+
+#[test]
+fn fetch_unshallow() {
+    let ctx = TestContext::setup_clone();
+    clone_and_commit(&ctx.remote_dir, "remote-file", "hello");
+    run(ctx.dir.path(), &["git", "fetch", "--depth=1"]);
+    snapshot!(ctx, "fu");
+}
+
+#[test]
+fn fetch_deepen() {
+    let ctx = TestContext::setup_clone();
+    clone_and_commit(&ctx.remote_dir, "remote-file", "hello");
+    run(ctx.dir.path(), &["git", "fetch", "--depth=1"]);
+    snapshot!(ctx, "fd1<enter>");
+}
+
+#[test]
+fn fetch_unshallow_not_shallow() {
+    snapshot!(TestContext::setup_clone(), "fu");
+}
@@ -0,0 +1,80 @@
+use super::*;
+use crate::{
+    file_watcher::{Changes, FileWatcher},
+    state::State,
+};
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+fn setup() -> (TestContext, State) {
+    let mut ctx = TestContext::setup_clone();
+    let mut state = ctx.init_state();
+    state.file_watcher = Some(FileWatcher::new(state.repo()).unwrap());
+    state.update(&mut ctx.term, &[]).unwrap();
+    (ctx, state)
+}
+
+/// Updates until the screen shows `text`, as the watcher reports changes only once they've settled.
+fn await_text(ctx: &mut TestContext, state: &mut State, text: &str) {
+    let started = Instant::now();
+    while !ctx.redact_buffer().contains(text) {
+        assert!(
+            started.elapsed() < Duration::from_secs(5),
+            "Never showed {:?}",
+            text
+        );
+        thread::sleep(Duration::from_millis(50));
+        state.update(&mut ctx.term, &[]).unwrap();
+    }
+}
+
+#[test]
+fn refresh_on_worktree_change() {
+    let (mut ctx, mut state) = setup();
+    fs::write(ctx.dir.child("edited.txt"), "hello\n").unwrap();
+    await_text(&mut ctx, &mut state, "edited.txt");
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn refresh_log_on_outside_commit() {
+    let (mut ctx, mut state) = setup();
+    state.update(&mut ctx.term, &keys("ll")).unwrap();
+    commit(ctx.dir.path(), "outside.txt", "");
+    await_text(&mut ctx, &mut state, "add outside.txt");
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn pause_while_prompting() {
+    let (mut ctx, mut state) = setup();
+    state.update(&mut ctx.term, &keys("bc")).unwrap();
+    fs::write(ctx.dir.child("edited.txt"), "hello\n").unwrap();
+    thread::sleep(Duration::from_millis(500));
+    state.update(&mut ctx.term, &[]).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+
+    state.update(&mut ctx.term, &keys("<esc>")).unwrap();
+    await_text(&mut ctx, &mut state, "edited.txt");
+}
+
+#[test]
+fn refresh_only_changed_sections() {
+    let mut ctx = TestContext::setup_clone();
+    let mut state = ctx.init_state();
+    commit(ctx.dir.path(), "committed.txt", "");
+    fs::write(ctx.dir.child("edited.txt"), "hello\n").unwrap();
+
+    // Recent commits are only made from refs, and left as they were
+    state
+        .screen_mut()
+        .update_changed(Changes {
+            worktree: true,
+            ..Default::default()
+        })
+        .unwrap();
+    state.update(&mut ctx.term, &keys("jk")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
@@ -0,0 +1,38 @@
+use super::*;
+
+#[test]
+fn show_git_config() {
+    snapshot!(TestContext::setup_clone(), "S");
+}
+
+#[test]
+fn set_git_config() {
+    snapshot!(
+        TestContext::setup_clone(),
+        "Sjjjjjjj<enter><enter>true<enter>"
+    );
+}
+
+#[test]
+fn set_git_config_scope_prompt() {
+    snapshot!(TestContext::setup_clone(), "Sj<enter>");
+}
+
+#[test]
+fn unset_git_config() {
+    snapshot!(TestContext::setup_clone(), "SjKy");
+}
+
+#[test]
+fn unset_worktree_git_config() {
+    let ctx = TestContext::setup_clone();
+    run(
+        ctx.dir.path(),
+        &["git", "config", "extensions.worktreeConfig", "true"],
+    );
+    run(
+        ctx.dir.path(),
+        &["git", "config", "--worktree", "user.name", "Worktree"],
+    );
+    snapshot!(ctx, "SjKy");
+}
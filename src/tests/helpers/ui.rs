@@ -73,12 +73,33 @@ impl TestContext {
     }
 
     pub fn init_state_at_path(&mut self, path: PathBuf) -> State {
+        self.create_state(path, &Args::default(), false)
+    }
+
+    pub fn init_state_with_args(&mut self, args: &Args) -> State {
+        self.create_state(self.dir.path().to_path_buf(), args, false)
+    }
+
+    /// Like Gitu started outside of a repository, which `--workspace` allows.
+    pub fn init_state_without_repo(&mut self, args: &Args) -> State {
+        let mut state =
+            State::create(None, self.size, args, Rc::clone(&self.config), false).unwrap();
+        state.update(&mut self.term, &[Event::FocusGained]).unwrap();
+        state
+    }
+
+    /// Like `init_state`, but commands run in the background like they do outside of tests.
+    pub fn init_async_state(&mut self) -> State {
+        self.create_state(self.dir.path().to_path_buf(), &Args::default(), true)
+    }
+
+    fn create_state(&mut self, path: PathBuf, args: &Args, enable_async_cmds: bool) -> State {
         let mut state = State::create(
-            Rc::new(Repository::open(path).unwrap()),
+            Some(Rc::new(Repository::open(path).unwrap())),
             self.size,
-            &Args::default(),
+            args,
             Rc::clone(&self.config),
-            false,
+            enable_async_cmds,
         )
         .unwrap();
 
@@ -0,0 +1,46 @@
+use super::*;
+use crate::init::{choose, init, Choice};
+use std::path::{Path, PathBuf};
+use temp_dir::TempDir;
+
+#[test]
+fn init_repo() {
+    let dir = TempDir::new().unwrap();
+    init(&dir.path().join("new")).unwrap();
+    assert!(git2::Repository::open(dir.path().join("new")).is_ok());
+}
+
+fn choose_with_keys(ctx: &mut TestContext, recent: &[PathBuf], keys_string: &str) -> Choice {
+    let mut events = keys(keys_string).into_iter();
+    choose(
+        &mut ctx.term,
+        Path::new("/home/user/project"),
+        recent,
+        || events.next().ok_or("Out of keys".into()),
+    )
+    .unwrap()
+}
+
+#[test]
+fn outside_of_repo_init() {
+    let mut ctx = TestContext::setup_init();
+    assert_eq!(choose_with_keys(&mut ctx, &[], "i"), Choice::Init);
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn outside_of_repo_open_recent() {
+    let mut ctx = TestContext::setup_init();
+    let recent = [PathBuf::from("/repos/a"), PathBuf::from("/repos/b")];
+    assert_eq!(
+        choose_with_keys(&mut ctx, &recent, "jj<enter>"),
+        Choice::Open(PathBuf::from("/repos/b"))
+    );
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn outside_of_repo_quit() {
+    let mut ctx = TestContext::setup_init();
+    assert_eq!(choose_with_keys(&mut ctx, &[], "<enter>q"), Choice::Quit);
+}
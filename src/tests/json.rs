@@ -0,0 +1,51 @@
+use super::*;
+use crate::{cli::Commands, json};
+use git2::Repository;
+
+fn print(ctx: &mut TestContext, command: Commands) -> String {
+    let repo = Repository::open(ctx.dir.path()).unwrap();
+    json::diff(ctx.config(), &repo, &command).unwrap()
+}
+
+#[test]
+fn diff_json() {
+    let mut ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "file", "one\ntwo\n");
+    fs::write(ctx.dir.child("file"), "one\n2\n").unwrap();
+
+    let command = Commands::Diff {
+        staged: false,
+        json: true,
+    };
+    insta::assert_snapshot!(print(&mut ctx, command));
+}
+
+#[test]
+fn show_json() {
+    let mut ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "file", "one\n");
+
+    let command = Commands::Show {
+        reference: "HEAD".into(),
+        json: true,
+    };
+    insta::assert_snapshot!(print(&mut ctx, command));
+}
+
+#[test]
+fn status_json() {
+    let mut ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "file", "one\n");
+    fs::write(ctx.dir.child("stashed"), "").unwrap();
+    run(
+        ctx.dir.path(),
+        &["git", "stash", "push", "--include-untracked"],
+    );
+    fs::write(ctx.dir.child("file"), "two\n").unwrap();
+    fs::write(ctx.dir.child("staged"), "").unwrap();
+    run(ctx.dir.path(), &["git", "add", "staged"]);
+    fs::write(ctx.dir.child("untracked"), "").unwrap();
+
+    let repo = Repository::open(ctx.dir.path()).unwrap();
+    insta::assert_snapshot!(json::status(ctx.config(), &repo).unwrap());
+}
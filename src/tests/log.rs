@@ -1,4 +1,8 @@
 use super::*;
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
 
 fn setup() -> TestContext {
     let ctx = TestContext::setup_clone();
@@ -28,6 +32,46 @@ fn limit_2_commits() {
     snapshot!(setup(), "l-n-n2<enter>l");
 }
 
+#[test]
+fn limit_list_more() {
+    snapshot!(setup(), "l-n-n2<enter>l+");
+}
+
+#[test]
+fn limit_list_fewer() {
+    snapshot!(setup(), "l-n-n2<enter>l-");
+}
+
+#[test]
+fn limit_long_history() {
+    let ctx = TestContext::setup_init();
+    let stream = (1..=10_001)
+        .map(|i| {
+            format!(
+                "commit refs/heads/main\ncommitter A <a@b> {} +0000\ndata 1\n{}\n",
+                i,
+                i % 10
+            )
+        })
+        .collect::<String>();
+
+    let mut fast_import = Command::new("git")
+        .args(["fast-import", "--quiet"])
+        .current_dir(ctx.dir.path())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    fast_import
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stream.as_bytes())
+        .unwrap();
+    assert!(fast_import.wait().unwrap().success());
+
+    snapshot!(ctx, "l-n-n2<enter>l");
+}
+
 #[test]
 fn limit_2_commits_other() {
     snapshot!(setup(), "l-n-n2<enter>l");
@@ -68,6 +112,13 @@ fn log_other() {
     snapshot!(setup(), "lljlo<enter>");
 }
 
+#[test]
+fn log_other_pick_tag() {
+    let ctx = setup();
+    run(ctx.dir.path(), &["git", "tag", "v1.0", "HEAD~2"]);
+    snapshot!(ctx, "lov1<tab><enter>");
+}
+
 #[test]
 fn log_other_input() {
     snapshot!(setup(), "lomain~1<enter>");
@@ -77,3 +128,219 @@ fn log_other_input() {
 fn log_other_invalid() {
     snapshot!(setup(), "lo <enter>");
 }
+
+#[test]
+fn range_diff_prompt() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "first-file", "first\n");
+    commit(
+        ctx.dir.path(),
+        "second-file",
+        "one\ntwo\nthree\nfour\nfive\n",
+    );
+    run(ctx.dir.path(), &["git", "push"]);
+    fs::write(
+        ctx.dir.child("second-file"),
+        "one\ntwo\nTHREE\nfour\nfive\n",
+    )
+    .unwrap();
+    run(
+        ctx.dir.path(),
+        &["git", "commit", "--all", "--amend", "--no-edit"],
+    );
+    snapshot!(ctx, "lr");
+}
+
+#[test]
+fn range_diff_upstream() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "first-file", "first\n");
+    commit(
+        ctx.dir.path(),
+        "second-file",
+        "one\ntwo\nthree\nfour\nfive\n",
+    );
+    run(ctx.dir.path(), &["git", "push"]);
+    fs::write(
+        ctx.dir.child("second-file"),
+        "one\ntwo\nTHREE\nfour\nfive\n",
+    )
+    .unwrap();
+    run(
+        ctx.dir.path(),
+        &["git", "commit", "--all", "--amend", "--no-edit"],
+    );
+    snapshot!(ctx, "lr<enter>");
+}
+
+#[test]
+fn range_diff_invalid() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "first-file", "first\n");
+    commit(
+        ctx.dir.path(),
+        "second-file",
+        "one\ntwo\nthree\nfour\nfive\n",
+    );
+    run(ctx.dir.path(), &["git", "push"]);
+    fs::write(
+        ctx.dir.child("second-file"),
+        "one\ntwo\nTHREE\nfour\nfive\n",
+    )
+    .unwrap();
+    run(
+        ctx.dir.path(),
+        &["git", "commit", "--all", "--amend", "--no-edit"],
+    );
+    snapshot!(ctx, "lrnonsense<enter>");
+}
+
+#[test]
+fn search_messages() {
+    snapshot!(setup(), "lll/second<enter>");
+}
+
+#[test]
+fn search_next() {
+    snapshot!(setup(), "lll/commit<enter>nn");
+}
+
+#[test]
+fn search_next_wraps() {
+    snapshot!(setup(), "lll/(first|third)<enter>nn");
+}
+
+#[test]
+fn search_no_match() {
+    snapshot!(setup(), "lll/doesntexist<enter>");
+}
+
+#[test]
+fn search_next_without_search() {
+    snapshot!(setup(), "lln");
+}
+
+#[test]
+fn search_past_limit() {
+    snapshot!(setup(), "l-n-n2<enter>ll/third<enter>");
+}
+
+#[test]
+fn signatures() {
+    let mut ctx = TestContext::setup_clone();
+    let dir = ctx.dir.path();
+    run(
+        dir,
+        &["ssh-keygen", "-q", "-t", "ed25519", "-N", "", "-f", "key"],
+    );
+    let public_key = fs::read_to_string(dir.join("key.pub")).unwrap();
+    fs::write(
+        dir.join("allowed_signers"),
+        format!("ci@example.com {public_key}"),
+    )
+    .unwrap();
+    run(dir, &["git", "config", "gpg.format", "ssh"]);
+    run(dir, &["git", "config", "user.signingkey", "key"]);
+    run(
+        dir,
+        &[
+            "git",
+            "config",
+            "gpg.ssh.allowedSignersFile",
+            "allowed_signers",
+        ],
+    );
+
+    commit(dir, "unsigned", "");
+    run(
+        dir,
+        &["git", "commit", "--allow-empty", "-S", "-m", "signed"],
+    );
+
+    ctx.config().general.log_signatures.enabled = true;
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("ll")).unwrap();
+
+    let buffer = ctx.redact_buffer();
+    assert!(buffer.contains(" ✓ main signed"), "{buffer}");
+    assert!(buffer.contains("   add unsigned"), "{buffer}");
+}
+
+#[test]
+fn branch_point_status() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.base_branch = "main".into();
+    run(ctx.dir.path(), &["git", "checkout", "-b", "feature"]);
+    commit(ctx.dir.path(), "feature-one", "one\n");
+    commit(ctx.dir.path(), "feature-two", "two\n");
+    snapshot!(ctx, "");
+}
+
+#[test]
+fn log_since_base() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.base_branch = "main".into();
+    run(ctx.dir.path(), &["git", "checkout", "-b", "feature"]);
+    commit(ctx.dir.path(), "feature-one", "one\n");
+    commit(ctx.dir.path(), "feature-two", "two\n");
+    snapshot!(ctx, "lb");
+}
+
+#[test]
+fn show_since_base() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.base_branch = "main".into();
+    run(ctx.dir.path(), &["git", "checkout", "-b", "feature"]);
+    commit(ctx.dir.path(), "feature-one", "one\n");
+    commit(ctx.dir.path(), "feature-two", "two\n");
+    snapshot!(ctx, "lB");
+}
+
+#[test]
+fn log_since_base_not_set() {
+    snapshot!(setup(), "lb");
+}
+
+#[test]
+fn log_all() {
+    let ctx = setup();
+    run(
+        ctx.dir.path(),
+        &["git", "checkout", "-b", "other", "HEAD~2"],
+    );
+    commit(ctx.dir.path(), "other commit", "");
+    run(ctx.dir.path(), &["git", "checkout", "main"]);
+    snapshot!(ctx, "l-al");
+}
+
+#[test]
+fn log_first_parent() {
+    let ctx = setup();
+    run(
+        ctx.dir.path(),
+        &["git", "checkout", "-b", "other", "HEAD~2"],
+    );
+    commit(ctx.dir.path(), "other commit", "");
+    run(ctx.dir.path(), &["git", "checkout", "main"]);
+    run(
+        ctx.dir.path(),
+        &["git", "merge", "--no-ff", "-m", "merge other", "other"],
+    );
+    snapshot!(ctx, "l-pl");
+}
+
+#[test]
+fn log_simplify_by_decoration() {
+    let ctx = setup();
+    run(
+        ctx.dir.path(),
+        &["git", "checkout", "-b", "other", "HEAD~2"],
+    );
+    commit(ctx.dir.path(), "other commit", "");
+    run(ctx.dir.path(), &["git", "checkout", "main"]);
+    run(
+        ctx.dir.path(),
+        &["git", "merge", "--no-ff", "-m", "merge other", "other"],
+    );
+    snapshot!(ctx, "l-dl");
+}
@@ -1,23 +1,47 @@
-use std::fs;
+use std::{fs, process::Command};
 
 #[macro_use]
 mod helpers;
 mod arg;
+mod bare;
+mod clone;
+mod cmd_log;
 mod commit;
 mod discard;
 mod editor;
+mod external_diff;
 mod fetch;
+mod file_watcher;
+mod git_config;
+mod init;
+#[cfg(feature = "json")]
+mod json;
 mod log;
+mod mouse;
+mod op_log;
 mod pull;
 mod push;
 mod quit;
 mod rebase;
 mod remote;
+mod repair;
+mod repeat;
 mod reset;
+mod restore;
+#[cfg(feature = "session")]
+mod session;
+mod show;
 mod stage;
 mod stash;
+mod switch_repo;
 mod unstage;
+mod workspace;
 
+use crate::{
+    config::{InitialScreen, StatusSection},
+    items::TargetData,
+    screen::NavMode,
+};
 use helpers::{clone_and_commit, commit, keys, run, TestContext};
 
 #[test]
@@ -70,6 +94,242 @@ fn binary_file() {
     snapshot!(ctx, "jj<tab>");
 }
 
+fn png(width: u32, height: u32) -> Vec<u8> {
+    let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+    png.extend(width.to_be_bytes());
+    png.extend(height.to_be_bytes());
+    png.extend([8, 6, 0, 0, 0]);
+    png
+}
+
+#[test]
+fn binary_image_file() {
+    let ctx = TestContext::setup_init();
+    fs::write(ctx.dir.child("image.png"), png(16, 16)).expect("error writing to file");
+    run(ctx.dir.path(), &["git", "add", "."]);
+    run(ctx.dir.path(), &["git", "commit", "-m", "add image"]);
+    fs::write(ctx.dir.child("image.png"), png(32, 24)).expect("error writing to file");
+    snapshot!(ctx, "jj<tab>");
+}
+
+#[test]
+fn hide_whitespace_hunks() {
+    let ctx = TestContext::setup_init();
+    let lines = (1..=20).map(|i| format!("line {i}\n")).collect::<String>();
+    commit(ctx.dir.path(), "testfile", &lines);
+    let changed = lines
+        .replace("line 2\n", "  line 2\n")
+        .replace("line 19\n", "line nineteen\n");
+    fs::write(ctx.dir.child("testfile"), changed).expect("error writing to file");
+    snapshot!(ctx, "jj<tab>W");
+}
+
+#[test]
+fn show_whitespace_hunks_again() {
+    let ctx = TestContext::setup_init();
+    let lines = (1..=20).map(|i| format!("line {i}\n")).collect::<String>();
+    commit(ctx.dir.path(), "testfile", &lines);
+    let changed = lines
+        .replace("line 2\n", "  line 2\n")
+        .replace("line 19\n", "line nineteen\n");
+    fs::write(ctx.dir.child("testfile"), changed).expect("error writing to file");
+    snapshot!(ctx, "jj<tab>WW");
+}
+
+#[test]
+fn wrap_lines() {
+    let ctx = TestContext::setup_init();
+    commit(ctx.dir.path(), "testfile", "short\nunchanged\n");
+    let long = (1..=40).map(|i| format!("word{i} ")).collect::<String>();
+    fs::write(ctx.dir.child("testfile"), format!("{long}\nunchanged\n"))
+        .expect("error writing to file");
+    snapshot!(ctx, "jj<tab>w");
+}
+
+#[test]
+fn wrap_lines_select_next_line() {
+    let ctx = TestContext::setup_init();
+    commit(ctx.dir.path(), "testfile", "short\nunchanged\n");
+    let long = (1..=40).map(|i| format!("word{i} ")).collect::<String>();
+    fs::write(ctx.dir.child("testfile"), format!("{long}\nunchanged\n"))
+        .expect("error writing to file");
+    snapshot!(ctx, "jj<tab>w<ctrl+j><ctrl+j><ctrl+j>");
+}
+
+#[test]
+fn unwrap_lines_again() {
+    let ctx = TestContext::setup_init();
+    commit(ctx.dir.path(), "testfile", "short\nunchanged\n");
+    let long = (1..=40).map(|i| format!("word{i} ")).collect::<String>();
+    fs::write(ctx.dir.child("testfile"), format!("{long}\nunchanged\n"))
+        .expect("error writing to file");
+    snapshot!(ctx, "jj<tab>ww");
+}
+
+#[test]
+fn blame_hunk() {
+    let ctx = TestContext::setup_init();
+    commit(ctx.dir.path(), "testfile", "one\ntwo\nthree\nfour\nfive\n");
+    fs::write(ctx.dir.child("testfile"), "one\n2\nthree\nfour\nfive\n").unwrap();
+    run(
+        ctx.dir.path(),
+        &[
+            "git",
+            "commit",
+            "-am",
+            "modify two",
+            "--author",
+            "Someone Else <else@example.com>",
+        ],
+    );
+    fs::write(ctx.dir.child("testfile"), "one\n2\nthree\n4\nfive\n").unwrap();
+    snapshot!(ctx, "jj<tab>jB");
+}
+
+#[test]
+fn unblame_hunk_again() {
+    let ctx = TestContext::setup_init();
+    commit(ctx.dir.path(), "testfile", "one\ntwo\nthree\nfour\nfive\n");
+    fs::write(ctx.dir.child("testfile"), "one\n2\nthree\nfour\nfive\n").unwrap();
+    run(
+        ctx.dir.path(),
+        &[
+            "git",
+            "commit",
+            "-am",
+            "modify two",
+            "--author",
+            "Someone Else <else@example.com>",
+        ],
+    );
+    fs::write(ctx.dir.child("testfile"), "one\n2\nthree\n4\nfive\n").unwrap();
+    snapshot!(ctx, "jj<tab>jBB");
+}
+
+#[test]
+fn blame_hunk_with_staged_changes() {
+    let ctx = TestContext::setup_init();
+    commit(ctx.dir.path(), "testfile", "one\ntwo\nthree\n");
+    fs::write(ctx.dir.child("testfile"), "ONE\ntwo\nthree\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "testfile"]);
+    fs::write(ctx.dir.child("testfile"), "ONE\ntwo\nTHREE\n").unwrap();
+    snapshot!(ctx, "jj<tab>jB");
+}
+
+#[test]
+fn blame_hunk_without_newline_at_end() {
+    let ctx = TestContext::setup_init();
+    commit(ctx.dir.path(), "testfile", "one\ntwo");
+    fs::write(ctx.dir.child("testfile"), "one\nTWO").unwrap();
+    snapshot!(ctx, "jj<tab>jB");
+}
+
+#[test]
+fn diff_base() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "committed_file", "committed\n");
+    fs::write(ctx.dir.child("staged_file"), "staged\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "staged_file"]);
+    snapshot!(ctx, "Dorigin/main<enter>");
+}
+
+#[test]
+fn diff_base_unstaged() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "committed_file", "committed\n");
+    fs::write(ctx.dir.child("staged_file"), "staged\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "staged_file"]);
+    fs::write(ctx.dir.child("initial-file"), "changed\n").unwrap();
+    snapshot!(ctx, "Dorigin/main<enter>");
+}
+
+#[test]
+fn diff_base_staged_section_is_read_only() {
+    let mut ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "committed_file", "committed\n");
+    fs::write(ctx.dir.child("staged_file"), "staged\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "staged_file"]);
+    let mut state = ctx.init_state();
+    state
+        .update(&mut ctx.term, &keys("Dorigin/main<enter>ju"))
+        .unwrap();
+
+    let staged = Command::new("git")
+        .args(["diff", "--cached", "--name-only"])
+        .current_dir(ctx.dir.path())
+        .output()
+        .unwrap();
+    assert_eq!(String::from_utf8_lossy(&staged.stdout), "staged_file\n");
+}
+
+#[test]
+fn diff_base_leaves_nothing_to_stage_or_discard() {
+    let mut ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "committed_file", "committed\n");
+    fs::write(ctx.dir.child("staged_file"), "staged\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "staged_file"]);
+    fs::write(ctx.dir.child("initial-file"), "changed\n").unwrap();
+    let mut state = ctx.init_state();
+    state
+        .update(&mut ctx.term, &keys("Dorigin/main<enter>"))
+        .unwrap();
+
+    for _ in 0..20 {
+        let screen = state.screen_mut();
+        let item = screen.get_selected_item();
+        assert!(
+            !matches!(
+                item.target_data,
+                Some(
+                    TargetData::AllStaged
+                        | TargetData::AllUnstaged
+                        | TargetData::Delta(_)
+                        | TargetData::File(_)
+                        | TargetData::Hunk(_)
+                        | TargetData::HunkLine(..)
+                        | TargetData::ModeChange { .. }
+                )
+            ),
+            "{} can be acted on",
+            item.id
+        );
+        screen.select_next(NavMode::IncludeHunkLines);
+    }
+
+    let mut state = ctx.init_state();
+    state
+        .update(&mut ctx.term, &keys("Dorigin/main<enter>jjjjKy"))
+        .unwrap();
+    assert!(ctx.dir.child("committed_file").exists());
+}
+
+#[test]
+fn diff_base_reset_to_head() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "committed_file", "committed\n");
+    fs::write(ctx.dir.child("staged_file"), "staged\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "staged_file"]);
+    snapshot!(ctx, "Dorigin/main<enter>D<enter>");
+}
+
+#[test]
+fn diff_base_invalid() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "committed_file", "committed\n");
+    fs::write(ctx.dir.child("staged_file"), "staged\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "staged_file"]);
+    snapshot!(ctx, "Dnonexistent<enter>");
+}
+
+#[test]
+fn diff_base_unsupported_screen() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "committed_file", "committed\n");
+    fs::write(ctx.dir.child("staged_file"), "staged\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "staged_file"]);
+    snapshot!(ctx, "llD");
+}
+
 #[test]
 fn collapsed_sections_config() {
     let mut ctx = TestContext::setup_clone();
@@ -84,6 +344,140 @@ fn collapsed_sections_config() {
     snapshot!(ctx, "");
 }
 
+#[test]
+fn status_sections_staged_first() {
+    let mut ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "unstaged.txt", "");
+    commit(ctx.dir.path(), "staged.txt", "");
+    fs::write(ctx.dir.child("unstaged.txt"), "changed\n").unwrap();
+    fs::write(ctx.dir.child("staged.txt"), "changed\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "staged.txt"]);
+    fs::write(ctx.dir.child("untracked_file.txt"), "").unwrap();
+    ctx.config().general.status_sections = vec![
+        StatusSection::BranchStatus,
+        StatusSection::StagedChanges,
+        StatusSection::UnstagedChanges,
+        StatusSection::Untracked,
+    ];
+    snapshot!(ctx, "");
+}
+
+#[test]
+fn status_sections_without_branch_status() {
+    let mut ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "unstaged.txt", "");
+    commit(ctx.dir.path(), "staged.txt", "");
+    fs::write(ctx.dir.child("unstaged.txt"), "changed\n").unwrap();
+    fs::write(ctx.dir.child("staged.txt"), "changed\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "staged.txt"]);
+    fs::write(ctx.dir.child("untracked_file.txt"), "").unwrap();
+    ctx.config().general.status_sections =
+        vec![StatusSection::UnstagedChanges, StatusSection::RecentCommits];
+    snapshot!(ctx, "");
+}
+
+#[test]
+fn initial_screen_log() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.initial_screen = InitialScreen::Log;
+    snapshot!(ctx, "");
+}
+
+#[test]
+fn initial_screen_refs() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.initial_screen = InitialScreen::Refs;
+    snapshot!(ctx, "");
+}
+
+#[test]
+fn status_filter_glob() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "lib.rs", "");
+    commit(ctx.dir.path(), "notes.txt", "");
+    fs::write(ctx.dir.child("lib.rs"), "changed\n").unwrap();
+    fs::write(ctx.dir.child("notes.txt"), "changed\n").unwrap();
+    fs::create_dir(ctx.dir.child("gen")).unwrap();
+    fs::write(ctx.dir.child("gen/generated.rs"), "").unwrap();
+    fs::write(ctx.dir.child("gen/generated.txt"), "").unwrap();
+    fs::write(ctx.dir.child("main.rs"), "").unwrap();
+    run(ctx.dir.path(), &["git", "add", "main.rs"]);
+    snapshot!(ctx, "/*.rs<enter>");
+}
+
+#[test]
+fn status_filter_substring() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "lib.rs", "");
+    commit(ctx.dir.path(), "notes.txt", "");
+    fs::write(ctx.dir.child("lib.rs"), "changed\n").unwrap();
+    fs::write(ctx.dir.child("notes.txt"), "changed\n").unwrap();
+    fs::create_dir(ctx.dir.child("gen")).unwrap();
+    fs::write(ctx.dir.child("gen/generated.rs"), "").unwrap();
+    fs::write(ctx.dir.child("gen/generated.txt"), "").unwrap();
+    fs::write(ctx.dir.child("main.rs"), "").unwrap();
+    run(ctx.dir.path(), &["git", "add", "main.rs"]);
+    snapshot!(ctx, "/notes<enter>");
+}
+
+#[test]
+fn status_clear_filter() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "lib.rs", "");
+    commit(ctx.dir.path(), "notes.txt", "");
+    fs::write(ctx.dir.child("lib.rs"), "changed\n").unwrap();
+    fs::write(ctx.dir.child("notes.txt"), "changed\n").unwrap();
+    fs::create_dir(ctx.dir.child("gen")).unwrap();
+    fs::write(ctx.dir.child("gen/generated.rs"), "").unwrap();
+    fs::write(ctx.dir.child("gen/generated.txt"), "").unwrap();
+    fs::write(ctx.dir.child("main.rs"), "").unwrap();
+    run(ctx.dir.path(), &["git", "add", "main.rs"]);
+    snapshot!(ctx, "/*.rs<enter><backspace>");
+}
+
+#[test]
+fn relative_paths() {
+    let mut ctx = TestContext::setup_clone();
+    fs::create_dir(ctx.dir.child("src")).unwrap();
+    commit(ctx.dir.path(), "src/lib.rs", "");
+    fs::write(ctx.dir.child("src/lib.rs"), "changed\n").unwrap();
+    fs::write(ctx.dir.child("untracked_file.txt"), "").unwrap();
+    ctx.config().general.relative_paths.enabled = true;
+    ctx.config().launch_dir = "src".into();
+
+    snapshot!(ctx, "");
+}
+
+#[test]
+fn list_more_recent_commits() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.recent_commits_limit = 2;
+    commit(ctx.dir.path(), "firstfile", "");
+    commit(ctx.dir.path(), "secondfile", "");
+    commit(ctx.dir.path(), "thirdfile", "");
+    snapshot!(ctx, "jj+");
+}
+
+#[test]
+fn list_fewer_recent_commits() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.recent_commits_limit = 2;
+    commit(ctx.dir.path(), "firstfile", "");
+    commit(ctx.dir.path(), "secondfile", "");
+    commit(ctx.dir.path(), "thirdfile", "");
+    snapshot!(ctx, "jj-");
+}
+
+#[test]
+fn list_more_outside_lists() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.recent_commits_limit = 2;
+    commit(ctx.dir.path(), "firstfile", "");
+    commit(ctx.dir.path(), "secondfile", "");
+    commit(ctx.dir.path(), "thirdfile", "");
+    snapshot!(ctx, "+");
+}
+
 #[test]
 fn log() {
     let ctx = TestContext::setup_clone();
@@ -136,6 +530,41 @@ fn merge_conflict() {
     insta::assert_snapshot!(ctx.redact_buffer());
 }
 
+#[test]
+fn mergetool() {
+    let mut ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "new-file", "hello");
+
+    run(ctx.dir.path(), &["git", "checkout", "-b", "other-branch"]);
+    commit(ctx.dir.path(), "new-file", "hey");
+
+    run(ctx.dir.path(), &["git", "checkout", "main"]);
+    commit(ctx.dir.path(), "new-file", "hi");
+
+    run(ctx.dir.path(), &["git", "merge", "other-branch"]);
+
+    // Resolves conflicts by taking their side
+    run(
+        ctx.dir.path(),
+        &[
+            "git",
+            "config",
+            "mergetool.theirs.cmd",
+            "cp \"$REMOTE\" \"$MERGED\"",
+        ],
+    );
+    run(
+        ctx.dir.path(),
+        &["git", "config", "mergetool.theirs.trustExitCode", "true"],
+    );
+    run(
+        ctx.dir.path(),
+        &["git", "config", "mergetool.keepBackup", "false"],
+    );
+    ctx.config().editor.mergetool = "theirs".into();
+    snapshot!(ctx, "jjE");
+}
+
 #[test]
 fn revert_conflict() {
     let mut ctx = TestContext::setup_clone();
@@ -193,7 +622,7 @@ fn hide_untracked() {
     run(ctx.dir.path(), &["touch", "i-am-untracked"]);
 
     let mut state = ctx.init_state();
-    let mut config = state.repo.config().unwrap();
+    let mut config = state.repo().config().unwrap();
     config.set_str("status.showUntrackedFiles", "off").unwrap();
 
     state.update(&mut ctx.term, &keys("g")).unwrap();
@@ -238,11 +667,185 @@ mod show_refs {
         run(ctx.dir.path(), &["git", "tag", "v1.0"]);
         snapshot!(ctx, "Yjjjjjjbb<enter>Y");
     }
+
+    #[test]
+    fn show_refs_collapse_remote() {
+        let ctx = TestContext::setup_clone();
+        snapshot!(ctx, "Yjj<tab>");
+    }
+
+    #[test]
+    fn show_refs_filter() {
+        let ctx = TestContext::setup_clone();
+        run(ctx.dir.path(), &["git", "branch", "feature-a"]);
+        run(ctx.dir.path(), &["git", "branch", "feature-b"]);
+        run(ctx.dir.path(), &["git", "tag", "v1.0"]);
+        snapshot!(ctx, "Y/feat<enter>");
+    }
+
+    #[test]
+    fn show_refs_clear_filter() {
+        let ctx = TestContext::setup_clone();
+        run(ctx.dir.path(), &["git", "branch", "feature-a"]);
+        snapshot!(ctx, "Y/feat<enter>/<enter>");
+    }
+
+    #[test]
+    fn filter_unsupported_screen() {
+        let ctx = TestContext::setup_clone();
+        snapshot!(ctx, "ll/");
+    }
+
+    #[test]
+    fn show_refs_ahead_behind_base() {
+        let mut ctx = TestContext::setup_clone();
+        run(ctx.dir.path(), &["git", "checkout", "-b", "feature"]);
+        commit(ctx.dir.path(), "feature-file", "feature");
+        run(ctx.dir.path(), &["git", "checkout", "main"]);
+        commit(ctx.dir.path(), "main-file-1", "main");
+        commit(ctx.dir.path(), "main-file-2", "main");
+        ctx.config().general.base_branch = "main".into();
+        snapshot!(ctx, "Y");
+    }
+
+    #[test]
+    fn show_refs_sort_by_committer_date() {
+        let ctx = TestContext::setup_clone();
+        run(ctx.dir.path(), &["git", "checkout", "-b", "newer"]);
+        fs::write(ctx.dir.path().join("newer-file"), "").unwrap();
+        run(ctx.dir.path(), &["git", "add", "newer-file"]);
+        Command::new("git")
+            .args(["commit", "-m", "add newer-file"])
+            .env("GIT_COMMITTER_DATE", "Mon Feb 19 14:00 2024 +0100")
+            .current_dir(ctx.dir.path())
+            .output()
+            .unwrap();
+        run(ctx.dir.path(), &["git", "checkout", "main"]);
+        run(ctx.dir.path(), &["git", "branch", "older"]);
+        snapshot!(ctx, "Yo");
+    }
+
+    #[test]
+    fn show_refs_sort_by_ahead() {
+        let ctx = TestContext::setup_clone();
+        run(
+            ctx.dir.path(),
+            &["git", "checkout", "-b", "topic", "--track", "origin/main"],
+        );
+        commit(ctx.dir.path(), "topic-file", "");
+        run(ctx.dir.path(), &["git", "checkout", "main"]);
+        run(ctx.dir.path(), &["git", "branch", "alpha"]);
+        snapshot!(ctx, "Yoo");
+    }
 }
 
 mod checkout {
     use super::*;
 
+    #[test]
+    pub(crate) fn checkout_tag_menu() {
+        let ctx = TestContext::setup_clone();
+        run(ctx.dir.path(), &["git", "tag", "v1.0"]);
+        snapshot!(ctx, "Yjjjjjjb");
+    }
+
+    #[test]
+    pub(crate) fn checkout_tag_detached() {
+        let ctx = TestContext::setup_clone();
+        run(ctx.dir.path(), &["git", "tag", "v1.0"]);
+        snapshot!(ctx, "Yjjjjjjbdq");
+    }
+
+    #[test]
+    pub(crate) fn detached_head_moved() {
+        let ctx = TestContext::setup_clone();
+        run(ctx.dir.path(), &["git", "tag", "v1.0"]);
+        run(ctx.dir.path(), &["git", "checkout", "--detach", "v1.0"]);
+        commit(ctx.dir.path(), "detached-file", "");
+        snapshot!(ctx, "");
+    }
+
+    #[test]
+    pub(crate) fn detached_branch_menu() {
+        let ctx = TestContext::setup_clone();
+        run(ctx.dir.path(), &["git", "checkout", "--detach"]);
+        snapshot!(ctx, "b");
+    }
+
+    #[test]
+    pub(crate) fn detached_create_branch_here() {
+        let ctx = TestContext::setup_clone();
+        run(ctx.dir.path(), &["git", "checkout", "--detach"]);
+        snapshot!(ctx, "bcrescued<enter>");
+    }
+
+    #[test]
+    pub(crate) fn return_to_previous_branch() {
+        let ctx = TestContext::setup_clone();
+        run(ctx.dir.path(), &["git", "checkout", "-b", "feature"]);
+        run(ctx.dir.path(), &["git", "checkout", "--detach", "main"]);
+        snapshot!(ctx, "br");
+    }
+
+    #[test]
+    pub(crate) fn return_to_previous_branch_none() {
+        snapshot!(TestContext::setup_clone(), "br");
+    }
+
+    #[test]
+    pub(crate) fn checkout_previous() {
+        let ctx = TestContext::setup_clone();
+        run(ctx.dir.path(), &["git", "checkout", "-b", "feature"]);
+        run(ctx.dir.path(), &["git", "checkout", "main"]);
+        snapshot!(ctx, "b-");
+    }
+
+    #[test]
+    pub(crate) fn checkout_previous_detached() {
+        let ctx = TestContext::setup_clone();
+        run(ctx.dir.path(), &["git", "checkout", "--detach"]);
+        run(ctx.dir.path(), &["git", "checkout", "main"]);
+        snapshot!(ctx, "b-");
+    }
+
+    #[test]
+    pub(crate) fn checkout_previous_none() {
+        snapshot!(TestContext::setup_clone(), "b-");
+    }
+
+    #[test]
+    pub(crate) fn set_upstream() {
+        let ctx = TestContext::setup_clone();
+        run(ctx.dir.path(), &["git", "checkout", "-b", "feature"]);
+        snapshot!(ctx, "bu<tab><enter>");
+    }
+
+    #[test]
+    pub(crate) fn set_upstream_of_selected_branch() {
+        let mut ctx = TestContext::setup_clone();
+        run(ctx.dir.path(), &["git", "branch", "feature"]);
+        let mut state = ctx.init_state();
+        state
+            .update(&mut ctx.term, &keys("Yjbu<tab><enter>"))
+            .unwrap();
+
+        let repo = git2::Repository::open(ctx.dir.path()).unwrap();
+        let upstream = repo.branch_upstream_name("refs/heads/feature").unwrap();
+        assert_eq!(upstream.as_str(), Some("refs/remotes/origin/main"));
+    }
+
+    #[test]
+    pub(crate) fn unset_upstream() {
+        snapshot!(TestContext::setup_clone(), "bU");
+    }
+
+    #[test]
+    pub(crate) fn create_branch_from_tag() {
+        let ctx = TestContext::setup_clone();
+        run(ctx.dir.path(), &["git", "tag", "v1.0"]);
+        snapshot!(ctx, "Yjjjjjjbnfrom-tag<enter>");
+    }
+
     #[test]
     pub(crate) fn checkout_menu() {
         let ctx = TestContext::setup_clone();
@@ -264,10 +867,67 @@ mod checkout {
         snapshot!(ctx, "Yjjbbhi<enter>");
     }
 
+    #[test]
+    pub(crate) fn checkout_remote_branch_prompt() {
+        let ctx = TestContext::setup_clone();
+        run(
+            ctx.dir.path(),
+            &["git", "update-ref", "refs/remotes/origin/feature", "HEAD"],
+        );
+        snapshot!(ctx, "Yjjjjbb<enter>");
+    }
+
+    #[test]
+    pub(crate) fn checkout_remote_branch_tracking() {
+        let ctx = TestContext::setup_clone();
+        run(
+            ctx.dir.path(),
+            &["git", "update-ref", "refs/remotes/origin/feature", "HEAD"],
+        );
+        snapshot!(ctx, "Yjjjjbb<enter><enter>");
+    }
+
     #[test]
     pub(crate) fn checkout_new_branch() {
         snapshot!(TestContext::setup_clone(), "bcf<esc>bcx<enter>");
     }
+
+    #[test]
+    pub(crate) fn checkout_new_branch_paste() {
+        let mut ctx = TestContext::setup_clone();
+        let mut state = ctx.init_state();
+        state.update(&mut ctx.term, &keys("bc")).unwrap();
+        state
+            .update(
+                &mut ctx.term,
+                &[crossterm::event::Event::Paste("pasted\nname\n".into())],
+            )
+            .unwrap();
+        insta::assert_snapshot!(ctx.redact_buffer());
+    }
+
+    #[test]
+    pub(crate) fn spinoff() {
+        let ctx = TestContext::setup_clone();
+        commit(ctx.dir.path(), "first-file", "");
+        commit(ctx.dir.path(), "second-file", "");
+        snapshot!(ctx, "bsspun<enter>Y");
+    }
+
+    #[test]
+    pub(crate) fn spinoff_from_selected_commit() {
+        let ctx = TestContext::setup_clone();
+        commit(ctx.dir.path(), "first-file", "");
+        commit(ctx.dir.path(), "second-file", "");
+        snapshot!(ctx, "lljbSspun<enter>");
+    }
+
+    #[test]
+    pub(crate) fn spinoff_without_upstream() {
+        let ctx = TestContext::setup_init();
+        commit(ctx.dir.path(), "first-file", "");
+        snapshot!(ctx, "bs");
+    }
 }
 
 #[test]
@@ -284,6 +944,48 @@ fn updated_externally() {
     insta::assert_snapshot!(ctx.redact_buffer());
 }
 
+#[test]
+fn collapsed_hunk_stays_collapsed() {
+    let mut ctx = TestContext::setup_init();
+    let lines = (1..=20).map(|i| format!("line {i}\n")).collect::<String>();
+    commit(ctx.dir.path(), "file", &lines);
+    fs::write(
+        ctx.dir.child("file"),
+        lines
+            .replace("line 1\n", "one\n")
+            .replace("line 20", "twenty"),
+    )
+    .unwrap();
+
+    let mut state = ctx.init_state();
+    state
+        .update(&mut ctx.term, &keys("jj<tab>jj<tab>"))
+        .unwrap();
+
+    fs::write(
+        ctx.dir.child("file"),
+        lines
+            .replace("line 1\n", "ONE\n")
+            .replace("line 20", "twenty"),
+    )
+    .unwrap();
+
+    state.update(&mut ctx.term, &keys("g")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn hard_refresh() {
+    let mut ctx = TestContext::setup_clone();
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("ll")).unwrap();
+
+    commit(ctx.dir.path(), "new-file", "");
+
+    state.update(&mut ctx.term, &keys("G")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
 #[test]
 fn stage_last_hunk_of_first_delta() {
     let ctx = TestContext::setup_clone();
@@ -343,12 +1045,48 @@ fn syntax_highlighted() {
     snapshot!(ctx, "jj<tab>");
 }
 
+#[test]
+fn hunk_header_definitions() {
+    let ctx = TestContext::setup_init();
+    let content = |message| {
+        format!(
+            "struct Greeter;\n\nimpl Greeter {{\n    fn greet(&self) {{\n        let a = 1;\n        let b = 2;\n        let c = 3;\n        let d = 4;\n        println!(\"{}\");\n    }}\n}}\n",
+            message
+        )
+    };
+    commit(ctx.dir.path(), "greeter.rs", &content("Hey"));
+    fs::write(ctx.dir.child("greeter.rs"), content("Bye")).unwrap();
+
+    snapshot!(ctx, "jj<tab>");
+}
+
+#[test]
+fn hunk_breadcrumbs() {
+    let ctx = TestContext::setup_init();
+    let content = |message| {
+        let lines = (0..20)
+            .map(|i| format!("        println!(\"{} {}\");\n", message, i))
+            .collect::<String>();
+        format!(
+            "impl Greeter {{\n    fn greet(&self) {{\n{}    }}\n}}\n",
+            lines
+        )
+    };
+    commit(ctx.dir.path(), "greeter.rs", &content("Hey"));
+    fs::write(ctx.dir.child("greeter.rs"), content("Bye")).unwrap();
+
+    snapshot!(
+        ctx,
+        "jj<tab>j<ctrl+j><ctrl+j><ctrl+j><ctrl+j><ctrl+j><ctrl+j><ctrl+j><ctrl+j><ctrl+j><ctrl+j><ctrl+j><ctrl+j><ctrl+j><ctrl+j><ctrl+j><ctrl+j><ctrl+j><ctrl+j>"
+    );
+}
+
 #[test]
 fn crlf_diff() {
     let mut ctx = TestContext::setup_init();
     let mut state = ctx.init_state();
     state
-        .repo
+        .repo()
         .config()
         .unwrap()
         .set_bool("core.autocrlf", true)
@@ -0,0 +1,107 @@
+use super::*;
+use crossterm::event::{Event, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+
+fn mouse(kind: MouseEventKind, row: u16) -> Event {
+    Event::Mouse(MouseEvent {
+        kind,
+        column: 5,
+        row,
+        modifiers: KeyModifiers::NONE,
+    })
+}
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "file.txt", "one\ntwo\nthree\n");
+    fs::write(ctx.dir.child("file.txt"), "one\n2\n3\n").unwrap();
+    run(ctx.dir.path(), &["touch", "untracked.txt"]);
+    ctx
+}
+
+#[test]
+fn click() {
+    let mut ctx = setup();
+    let mut state = ctx.init_state();
+    state
+        .update(
+            &mut ctx.term,
+            &[mouse(MouseEventKind::Down(MouseButton::Left), 4)],
+        )
+        .unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn click_unselectable() {
+    let mut ctx = setup();
+    let mut state = ctx.init_state();
+    state
+        .update(
+            &mut ctx.term,
+            &[mouse(MouseEventKind::Down(MouseButton::Left), 2)],
+        )
+        .unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn hover() {
+    let mut ctx = setup();
+    let mut state = ctx.init_state();
+    state
+        .update(&mut ctx.term, &[mouse(MouseEventKind::Moved, 4)])
+        .unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn drag_select_hunk_lines() {
+    let mut ctx = setup();
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("jjjj<tab>")).unwrap();
+    state
+        .update(
+            &mut ctx.term,
+            &[
+                mouse(MouseEventKind::Down(MouseButton::Left), 10),
+                mouse(MouseEventKind::Drag(MouseButton::Left), 13),
+                mouse(MouseEventKind::Up(MouseButton::Left), 13),
+            ],
+        )
+        .unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn scroll() {
+    let mut ctx = setup();
+    let mut state = ctx.init_state();
+    state
+        .update(
+            &mut ctx.term,
+            &[
+                mouse(MouseEventKind::ScrollDown, 0),
+                mouse(MouseEventKind::ScrollDown, 0),
+            ],
+        )
+        .unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn click_wrapped_line() {
+    let mut ctx = TestContext::setup_init();
+    commit(ctx.dir.path(), "testfile", "short\n");
+    let long = (1..=40).map(|i| format!("word{i} ")).collect::<String>();
+    fs::write(ctx.dir.child("testfile"), format!("{long}\n")).unwrap();
+
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("jj<tab>w")).unwrap();
+    state
+        .update(
+            &mut ctx.term,
+            &[mouse(MouseEventKind::Down(MouseButton::Left), 8)],
+        )
+        .unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
@@ -0,0 +1,67 @@
+use super::*;
+use crate::{config::DiscardToTrash, op_log};
+
+#[test]
+fn op_log_records_commands() {
+    let mut ctx = TestContext::setup_clone();
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("bcnew<enter>")).unwrap();
+
+    let repo = git2::Repository::open(ctx.dir.path()).unwrap();
+    let entries = op_log::load(&repo);
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].args, "git checkout -b new");
+    assert_eq!(entries[0].exit_code, Some(0));
+    assert_eq!(entries[0].session, state.session_started);
+}
+
+#[test]
+fn op_log_records_in_process_changes() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.discard_to_trash = DiscardToTrash::Backup;
+    fs::write(ctx.dir.child("some-file"), "precious\n").unwrap();
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("jjKy")).unwrap();
+
+    let entries = op_log::load(state.repo());
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].args, "back up some-file in .git/gitu-trash");
+    assert_eq!(entries[0].exit_code, Some(0));
+}
+
+#[test]
+fn op_log_drops_the_oldest_entries() {
+    let mut ctx = TestContext::setup_clone();
+    let old_entry = "2024-02-18T14:00:00+01:00\t2024-02-18T14:01:00+01:00\t120\t0\tgit fetch\n";
+    fs::write(
+        ctx.dir.path().join(".git/gitu-oplog"),
+        old_entry.repeat(op_log::MAX_ENTRIES),
+    )
+    .unwrap();
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("bcnew<enter>")).unwrap();
+
+    let entries = op_log::load(state.repo());
+    assert_eq!(entries.len(), op_log::MAX_ENTRIES);
+    assert_eq!(entries[0].args, "git fetch");
+    assert_eq!(entries.last().unwrap().args, "git checkout -b new");
+}
+
+#[test]
+fn op_log_screen() {
+    let ctx = TestContext::setup_clone();
+    fs::write(
+        ctx.dir.path().join(".git/gitu-oplog"),
+        "2024-02-18T14:00:00+01:00\t2024-02-18T14:01:00+01:00\t120\t0\tgit fetch --all\n\
+         2024-02-18T14:00:00+01:00\t2024-02-18T14:02:00+01:00\t80\t1\tgit push origin main\n\
+         2024-02-19T09:00:00+01:00\t2024-02-19T09:05:30+01:00\t40\t0\tgit commit\n",
+    )
+    .unwrap();
+
+    snapshot!(ctx, "L");
+}
+
+#[test]
+fn op_log_empty() {
+    snapshot!(TestContext::setup_clone(), "L");
+}
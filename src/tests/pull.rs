@@ -77,3 +77,11 @@ fn pull_from_elsewhere_prompt() {
 fn pull_from_elsewhere() {
     snapshot!(TestContext::setup_clone(), "Feorigin<enter>");
 }
+
+#[test]
+fn pull_menu_summary() {
+    let ctx = TestContext::setup_clone();
+    clone_and_commit(&ctx.remote_dir, "remote-file", "hello\n");
+    run(ctx.dir.path(), &["git", "fetch"]);
+    snapshot!(ctx, "F");
+}
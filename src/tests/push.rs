@@ -17,6 +17,12 @@ fn push_menu_existing_push_remote_and_upstream() {
     snapshot!(ctx, "P");
 }
 
+#[test]
+fn push_menu_remembers_args() {
+    let ctx = TestContext::setup_clone();
+    snapshot!(ctx, "P-fqP");
+}
+
 #[test]
 fn push_upstream() {
     let ctx = TestContext::setup_clone();
@@ -24,6 +30,23 @@ fn push_upstream() {
     snapshot!(ctx, "Pu");
 }
 
+#[test]
+fn push_upstream_confirm() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().push.confirm.enabled = true;
+    commit(ctx.dir.path(), "first-file", "");
+    commit(ctx.dir.path(), "second-file", "");
+    snapshot!(ctx, "Pu");
+}
+
+#[test]
+fn push_upstream_confirmed() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().push.confirm.enabled = true;
+    commit(ctx.dir.path(), "new-file", "");
+    snapshot!(ctx, "Puy");
+}
+
 #[test]
 fn push_push_remote() {
     let ctx = TestContext::setup_clone();
@@ -78,6 +101,26 @@ fn force_push() {
     snapshot!(ctx, "P-fu");
 }
 
+#[test]
+fn force_push_after_rewind_prompt() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().push.confirm.enabled = true;
+    commit(ctx.dir.path(), "new-file", "");
+    run(ctx.dir.path(), &["git", "push"]);
+    run(ctx.dir.path(), &["git", "reset", "--hard", "HEAD~1"]);
+    snapshot!(ctx, "P-fu");
+}
+
+#[test]
+fn push_after_rewind_prompt() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().push.confirm.enabled = true;
+    commit(ctx.dir.path(), "new-file", "");
+    run(ctx.dir.path(), &["git", "push"]);
+    run(ctx.dir.path(), &["git", "reset", "--hard", "HEAD~1"]);
+    snapshot!(ctx, "Pu");
+}
+
 #[test]
 fn open_push_menu_after_dash_input() {
     let ctx = TestContext::setup_clone();
@@ -102,3 +145,27 @@ fn push_elsewhere_prompt() {
 fn push_elsewhere() {
     snapshot!(TestContext::setup_clone(), "Peorigin<enter>");
 }
+
+#[test]
+fn push_menu_summary() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "first-file", "one\n");
+    commit(ctx.dir.path(), "second-file", "two\nlines\n");
+    snapshot!(ctx, "P");
+}
+
+#[test]
+fn push_upstream_wait_cmd_script() {
+    let mut ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "new-file", "");
+
+    let mut state = ctx.init_async_state();
+
+    let ("", steps) = crate::key_parser::parse_script("Pu<wait-cmd>").unwrap() else {
+        unreachable!();
+    };
+    crate::handle_initial_send_keys(&steps, &mut state, &mut ctx.term).unwrap();
+
+    assert!(state.pending_cmd.is_none());
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
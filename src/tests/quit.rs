@@ -1,4 +1,10 @@
 use super::*;
+use crate::cmd_log::CmdLogEntry;
+use std::{
+    process::Command,
+    sync::{Arc, RwLock},
+    time::Instant,
+};
 
 #[test]
 pub(crate) fn quit() {
@@ -29,3 +35,38 @@ pub(crate) fn confirm_quit() {
     let state = snapshot!(ctx, "qy");
     assert!(state.quit);
 }
+
+#[test]
+pub(crate) fn confirm_quit_staged_summary() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.confirm_quit.enabled = true;
+    fs::write(ctx.dir.child("Staged.txt"), "hello\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "Staged.txt"]);
+
+    let state = snapshot!(ctx, "q");
+    assert!(!state.quit);
+}
+
+#[test]
+pub(crate) fn quit_while_cmd_running() {
+    let mut ctx = TestContext::setup_clone();
+    let mut state = ctx.init_state();
+    let child = Command::new("sleep").arg("10").spawn().unwrap();
+    let log_entry = Arc::new(RwLock::new(CmdLogEntry::Cmd {
+        args: "git push".into(),
+        out: None,
+    }));
+    state.pending_cmd = Some((child, log_entry, Instant::now()));
+
+    state.update(&mut ctx.term, &keys("q")).unwrap();
+    assert!(!state.quit);
+    insta::assert_snapshot!(ctx.redact_buffer());
+
+    state.update(&mut ctx.term, &keys("y")).unwrap();
+    assert!(!state.quit);
+    insta::assert_snapshot!(ctx.redact_buffer());
+
+    state.update(&mut ctx.term, &keys("y")).unwrap();
+    assert!(state.quit);
+    assert!(state.pending_cmd.is_none());
+}
@@ -23,3 +23,64 @@ fn rebase_elsewhere_prompt() {
 fn rebase_elsewhere() {
     snapshot!(setup(), "remain<enter>");
 }
+
+#[test]
+fn rebase_elsewhere_typed_rev() {
+    let ctx = setup();
+    run(ctx.dir.path(), &["git", "branch", "fix-header"]);
+    snapshot!(ctx, "reHEAD<enter>");
+}
+
+#[test]
+fn rebase_elsewhere_pick_commit() {
+    snapshot!(setup(), "reinitial<tab><enter>");
+}
+
+#[test]
+fn fixups_section() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "file", "a");
+    fs::write(ctx.dir.path().join("file"), "b").unwrap();
+    run(ctx.dir.path(), &["git", "commit", "-am", "fixup! add file"]);
+    snapshot!(ctx, "");
+}
+
+#[test]
+fn autosquash_fixups() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "file", "a");
+    fs::write(ctx.dir.path().join("file"), "b").unwrap();
+    run(ctx.dir.path(), &["git", "commit", "-am", "fixup! add file"]);
+    snapshot!(ctx, "A");
+}
+
+#[test]
+fn autosquash_fixups_none() {
+    snapshot!(setup(), "A");
+}
+
+#[test]
+fn rebase_conflicts_partly_resolved() {
+    let mut ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "first-file", "hello");
+    commit(ctx.dir.path(), "second-file", "hello");
+
+    run(ctx.dir.path(), &["git", "checkout", "-b", "other-branch"]);
+    fs::write(ctx.dir.child("first-file"), "hey").unwrap();
+    fs::write(ctx.dir.child("second-file"), "hey").unwrap();
+    run(ctx.dir.path(), &["git", "commit", "-am", "modify files"]);
+    commit(ctx.dir.path(), "third-file", "hey");
+
+    run(ctx.dir.path(), &["git", "checkout", "main"]);
+    fs::write(ctx.dir.child("first-file"), "hi").unwrap();
+    fs::write(ctx.dir.child("second-file"), "hi").unwrap();
+    run(ctx.dir.path(), &["git", "commit", "-am", "modify files"]);
+
+    run(ctx.dir.path(), &["git", "checkout", "other-branch"]);
+    run(ctx.dir.path(), &["git", "rebase", "main"]);
+    fs::write(ctx.dir.child("first-file"), "hey").unwrap();
+    run(ctx.dir.path(), &["git", "add", "first-file"]);
+
+    ctx.init_state();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
@@ -1,7 +1,7 @@
 use git2::{Buf, Error, Repository};
 
 use crate::git::remote::*;
-use crate::tests::helpers::{run, RepoTestContext};
+use crate::tests::helpers::{keys, run, RepoTestContext, TestContext};
 
 fn get_head_name(repo: &Repository) -> String {
     repo.head().unwrap().name().unwrap().into()
@@ -71,3 +71,24 @@ fn get_upstream_components_of_feature_branch() {
     assert_eq!(remote, ".");
     assert_eq!(branch, "main");
 }
+
+#[test]
+fn remote_menu() {
+    snapshot!(TestContext::setup_clone(), "YjjM");
+}
+
+#[test]
+fn set_remote_url() {
+    snapshot!(
+        TestContext::setup_clone(),
+        "YjjMuhttps://example.com/repo.git<enter><enter>"
+    );
+}
+
+#[test]
+fn set_remote_push_url() {
+    snapshot!(
+        TestContext::setup_clone(),
+        "YjjMuhttps://example.com/repo.git<enter>MUhttps://example.com/push.git<enter><enter>"
+    );
+}
@@ -0,0 +1,87 @@
+use super::*;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    let git_dir = ctx.dir.child(".git");
+    fs::write(git_dir.join("index.lock"), "").unwrap();
+    fs::write(git_dir.join("MERGE_MSG"), "Merge branch 'gone'\n").unwrap();
+    fs::create_dir(git_dir.join("rebase-merge")).unwrap();
+    ctx
+}
+
+#[test]
+fn repair_prompt() {
+    snapshot!(setup(), "!");
+}
+
+#[test]
+fn repair() {
+    let mut ctx = setup();
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("!y")).unwrap();
+
+    let git_dir = ctx.dir.child(".git");
+    assert!(!git_dir.join("index.lock").exists());
+    assert!(!git_dir.join("MERGE_MSG").exists());
+    assert!(!git_dir.join("rebase-merge").exists());
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn repair_nothing() {
+    snapshot!(TestContext::setup_clone(), "!");
+}
+
+#[test]
+fn repair_leaves_stopped_am() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "file", "ours\n");
+    run(
+        ctx.dir.path(),
+        &["git", "checkout", "-b", "theirs", "HEAD~"],
+    );
+    commit(ctx.dir.path(), "file", "theirs\n");
+    run(
+        ctx.dir.path(),
+        &["git", "format-patch", "-1", "-o", ".git/patches"],
+    );
+    run(ctx.dir.path(), &["git", "checkout", "main"]);
+    run(ctx.dir.path(), &["sh", "-c", "git am .git/patches/*.patch"]);
+
+    let git_dir = ctx.dir.child(".git");
+    assert!(git_dir.join("rebase-apply").join("applying").exists());
+    snapshot!(ctx, "!");
+}
+
+#[test]
+fn repair_leaves_message_of_stopped_rebase() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "file", "ours\n");
+    run(
+        ctx.dir.path(),
+        &["git", "checkout", "-b", "theirs", "HEAD~"],
+    );
+    commit(ctx.dir.path(), "file", "theirs\n");
+    run(ctx.dir.path(), &["git", "rebase", "main"]);
+
+    let git_dir = ctx.dir.child(".git");
+    assert!(git_dir.join("MERGE_MSG").exists());
+    snapshot!(ctx, "!");
+}
+
+#[test]
+fn repair_leaves_message_of_squash_merge() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "file", "ours\n");
+    run(
+        ctx.dir.path(),
+        &["git", "checkout", "-b", "theirs", "HEAD~"],
+    );
+    commit(ctx.dir.path(), "file", "theirs\n");
+    run(ctx.dir.path(), &["git", "merge", "--squash", "main"]);
+
+    let git_dir = ctx.dir.child(".git");
+    assert!(git_dir.join("MERGE_MSG").exists());
+    assert!(git_dir.join("SQUASH_MSG").exists());
+    snapshot!(ctx, "!");
+}
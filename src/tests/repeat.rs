@@ -0,0 +1,43 @@
+use super::*;
+
+#[test]
+fn repeat_stage() {
+    let ctx = TestContext::setup_clone();
+    run(ctx.dir.path(), &["touch", "a", "b", "c"]);
+    snapshot!(ctx, "jjs.");
+}
+
+#[test]
+fn repeat_discard_without_confirm() {
+    let ctx = TestContext::setup_clone();
+    run(ctx.dir.path(), &["touch", "a", "b", "c"]);
+    snapshot!(ctx, "jjKy.");
+}
+
+#[test]
+fn repeat_nothing() {
+    let ctx = TestContext::setup_clone();
+    run(ctx.dir.path(), &["touch", "a", "b", "c"]);
+    snapshot!(ctx, "jj.");
+}
+
+#[test]
+fn record_macro() {
+    let ctx = TestContext::setup_clone();
+    run(ctx.dir.path(), &["touch", "a", "b", "c"]);
+    snapshot!(ctx, "jjQKyQ");
+}
+
+#[test]
+fn replay_macro() {
+    let ctx = TestContext::setup_clone();
+    run(ctx.dir.path(), &["touch", "a", "b", "c"]);
+    snapshot!(ctx, "jjQKyQ@");
+}
+
+#[test]
+fn replay_nothing_recorded() {
+    let ctx = TestContext::setup_clone();
+    run(ctx.dir.path(), &["touch", "a", "b", "c"]);
+    snapshot!(ctx, "@");
+}
@@ -28,5 +28,17 @@ pub(crate) fn reset_mixed() {
 
 #[test]
 fn reset_hard() {
-    snapshot!(setup(), "lljXh<enter>q");
+    snapshot!(setup(), "lljXh<enter>yq");
+}
+
+#[test]
+fn reset_hard_preview() {
+    let ctx = setup();
+    fs::write(ctx.dir.child("initial-file"), "uncommitted\n").unwrap();
+    snapshot!(ctx, "lljXh<enter>");
+}
+
+#[test]
+fn reset_hard_nothing_lost_prompt() {
+    snapshot!(TestContext::setup_clone(), "XhHEAD<enter>");
 }
@@ -0,0 +1,31 @@
+use super::*;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    fs::write(ctx.dir.child("initial-file"), "staged\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "initial-file"]);
+    fs::write(ctx.dir.child("initial-file"), "unstaged\n").unwrap();
+    ctx
+}
+
+#[test]
+fn restore_file_prompt() {
+    snapshot!(setup(), "jjR");
+}
+
+#[test]
+fn restore_file() {
+    let mut ctx = setup();
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("jjRy")).unwrap();
+    assert_eq!(
+        fs::read_to_string(ctx.dir.child("initial-file")).unwrap(),
+        "staged\n"
+    );
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn restore_file_staged() {
+    snapshot!(setup(), "jjjjU");
+}
@@ -0,0 +1,74 @@
+use super::*;
+use crate::session;
+use crossterm::event::Event;
+
+#[test]
+fn restore_screens_and_cursor() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.restore_session.enabled = true;
+    commit(ctx.dir.path(), "second.txt", "");
+    commit(ctx.dir.path(), "third.txt", "");
+
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("lljj<enter>")).unwrap();
+    session::save(&state).unwrap();
+
+    let mut state = ctx.init_state();
+    session::restore(&mut state, ctx.size, true).unwrap();
+    state.update(&mut ctx.term, &keys("q")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn restore_collapsed_sections() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.restore_session.enabled = true;
+    fs::write(ctx.dir.child("untracked.txt"), "").unwrap();
+    commit(ctx.dir.path(), "second.txt", "");
+
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("j<tab>")).unwrap();
+    session::save(&state).unwrap();
+
+    let mut state = ctx.init_state();
+    session::restore(&mut state, ctx.size, true).unwrap();
+    state.update(&mut ctx.term, &[Event::FocusGained]).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn restore_gone_commit() {
+    let mut ctx = TestContext::setup_clone();
+    ctx.config().general.restore_session.enabled = true;
+    commit(ctx.dir.path(), "second.txt", "");
+
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("ll<enter>")).unwrap();
+    session::save(&state).unwrap();
+
+    run(ctx.dir.path(), &["git", "reset", "--hard", "HEAD~"]);
+    run(
+        ctx.dir.path(),
+        &["git", "reflog", "expire", "--expire=now", "--all"],
+    );
+    run(ctx.dir.path(), &["git", "gc", "--prune=now", "--quiet"]);
+
+    let mut state = ctx.init_state();
+    session::restore(&mut state, ctx.size, true).unwrap();
+    state.update(&mut ctx.term, &[Event::FocusGained]).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn restore_menu_args() {
+    let mut ctx = TestContext::setup_clone();
+
+    let mut state = ctx.init_state();
+    state.update(&mut ctx.term, &keys("P-f<esc>")).unwrap();
+    session::save(&state).unwrap();
+
+    let mut state = ctx.init_state();
+    session::restore(&mut state, ctx.size, false).unwrap();
+    state.update(&mut ctx.term, &keys("P")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
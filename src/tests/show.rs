@@ -0,0 +1,170 @@
+use super::*;
+
+fn setup() -> TestContext {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "first-file", "first\n");
+    commit(ctx.dir.path(), "second-file", "second\n");
+    ctx
+}
+
+#[test]
+fn show_parent() {
+    snapshot!(setup(), "ll<enter>[");
+}
+
+#[test]
+fn show_parent_and_back_to_child() {
+    snapshot!(setup(), "ll<enter>[[]");
+}
+
+#[test]
+fn show_child_found_from_head() {
+    snapshot!(setup(), "llj<enter>]");
+}
+
+#[test]
+fn show_parent_of_merge() {
+    let ctx = setup();
+    run(ctx.dir.path(), &["git", "checkout", "-b", "other", "HEAD~"]);
+    commit(ctx.dir.path(), "other-file", "other\n");
+    run(ctx.dir.path(), &["git", "checkout", "main"]);
+    run(ctx.dir.path(), &["git", "merge", "--no-edit", "other"]);
+    snapshot!(ctx, "ll<enter>[");
+}
+
+#[test]
+fn show_parent_of_merge_enter() {
+    let ctx = setup();
+    run(ctx.dir.path(), &["git", "checkout", "-b", "other", "HEAD~"]);
+    commit(ctx.dir.path(), "other-file", "other\n");
+    run(ctx.dir.path(), &["git", "checkout", "main"]);
+    run(ctx.dir.path(), &["git", "merge", "--no-edit", "other"]);
+    snapshot!(ctx, "ll<enter>[<enter>");
+}
+
+#[test]
+fn show_parent_outside_show_screen() {
+    snapshot!(setup(), "[");
+}
+
+#[test]
+fn show_folds_unchanged_lines() {
+    let ctx = TestContext::setup_clone();
+    let lines = |first, last| format!("{}\n2\n3\n4\n5\n6\n7\n{}\n", first, last);
+    commit(ctx.dir.path(), "numbers", &lines("1", "8"));
+    commit(ctx.dir.path(), "numbers", &lines("one", "eight"));
+    snapshot!(ctx, "ll<enter>");
+}
+
+#[test]
+fn show_expand_unchanged_lines() {
+    let ctx = TestContext::setup_clone();
+    let lines = |first, last| format!("{}\n2\n3\n4\n5\n6\n7\n{}\n", first, last);
+    commit(ctx.dir.path(), "numbers", &lines("1", "8"));
+    commit(ctx.dir.path(), "numbers", &lines("one", "eight"));
+    snapshot!(ctx, "ll<enter>jjjjj<tab>");
+}
+
+#[test]
+fn show_mark_reviewed() {
+    let ctx = TestContext::setup_clone();
+    fs::write(ctx.dir.child("file-a"), "a\n").unwrap();
+    fs::write(ctx.dir.child("file-b"), "b\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "."]);
+    run(ctx.dir.path(), &["git", "commit", "-m", "add two files"]);
+    snapshot!(ctx, "ll<enter>kv");
+}
+
+#[test]
+fn show_mark_reviewed_persists() {
+    let ctx = TestContext::setup_clone();
+    fs::write(ctx.dir.child("file-a"), "a\n").unwrap();
+    fs::write(ctx.dir.child("file-b"), "b\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "."]);
+    run(ctx.dir.path(), &["git", "commit", "-m", "add two files"]);
+    snapshot!(ctx, "ll<enter>kvq<enter>");
+}
+
+#[test]
+fn show_unmark_reviewed() {
+    let ctx = TestContext::setup_clone();
+    fs::write(ctx.dir.child("file-a"), "a\n").unwrap();
+    fs::write(ctx.dir.child("file-b"), "b\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "."]);
+    run(ctx.dir.path(), &["git", "commit", "-m", "add two files"]);
+    snapshot!(ctx, "ll<enter>kvv");
+}
+
+#[test]
+fn date_format() {
+    let mut ctx = setup();
+    ctx.config().general.date_format = "%d.%m.%Y %H:%M".into();
+    snapshot!(ctx, "ll<enter>");
+}
+
+#[test]
+fn date_in_locale() {
+    let mut ctx = setup();
+    ctx.config().general.locale = "de_DE.UTF-8".into();
+    snapshot!(ctx, "ll<enter>");
+}
+
+#[test]
+fn show_range() {
+    snapshot!(setup(), "ldHEAD~2..HEAD<enter>");
+}
+
+#[test]
+fn show_range_from_selected_commit() {
+    snapshot!(setup(), "lljjld<enter>");
+}
+
+#[test]
+fn show_range_from_merge_base() {
+    let ctx = setup();
+    run(ctx.dir.path(), &["git", "checkout", "-b", "other", "HEAD~"]);
+    commit(ctx.dir.path(), "other-file", "other\n");
+    snapshot!(ctx, "ldmain...other<enter>");
+}
+
+#[test]
+fn show_range_not_a_range() {
+    snapshot!(setup(), "ldHEAD<enter>");
+}
+
+#[test]
+fn show_stat() {
+    let ctx = TestContext::setup_clone();
+    fs::write(ctx.dir.child("a-file"), "one\ntwo\nthree\n").unwrap();
+    fs::write(ctx.dir.child("b-file"), "one\n".repeat(60)).unwrap();
+    run(ctx.dir.path(), &["git", "add", "."]);
+    run(ctx.dir.path(), &["git", "commit", "-m", "add files"]);
+    snapshot!(ctx, "ll<enter><ctrl+u>");
+}
+
+#[test]
+fn show_stat_goto_file() {
+    let ctx = TestContext::setup_clone();
+    fs::write(ctx.dir.child("a-file"), "one\ntwo\nthree\n").unwrap();
+    fs::write(ctx.dir.child("b-file"), "one\n".repeat(60)).unwrap();
+    run(ctx.dir.path(), &["git", "add", "."]);
+    run(ctx.dir.path(), &["git", "commit", "-m", "add files"]);
+    snapshot!(ctx, "ll<enter>kk<enter>");
+}
+
+#[test]
+fn show_merge_conflict_resolution() {
+    let ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "file", "a\nb\nc\n");
+    run(ctx.dir.path(), &["git", "checkout", "-b", "other"]);
+    commit(ctx.dir.path(), "file", "a\nB\nc\n");
+    run(ctx.dir.path(), &["git", "checkout", "main"]);
+    commit(ctx.dir.path(), "file", "a\nbb\nc\n");
+    run(ctx.dir.path(), &["git", "merge", "other"]);
+    fs::write(ctx.dir.child("file"), "a\nresolved\nc\n").unwrap();
+    run(
+        ctx.dir.path(),
+        &["git", "commit", "-am", "Merge branch 'other'"],
+    );
+    snapshot!(ctx, "ll<enter>");
+}
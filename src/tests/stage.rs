@@ -46,6 +46,64 @@ fn stage_added_line() {
     snapshot!(ctx, "jj<tab><ctrl+j><ctrl+j><ctrl+j><ctrl+j>s");
 }
 
+#[test]
+fn stage_edited_hunk() {
+    let ctx = TestContext::setup_init();
+    run(ctx.dir.path(), &["git", "config", "core.editor", "true"]);
+    commit(ctx.dir.path(), "firstfile", "testing\ntesttest\n");
+    fs::write(ctx.dir.child("firstfile"), "weehooo\ntesttest\n").unwrap();
+
+    snapshot!(ctx, "jj<tab>je");
+}
+
+#[test]
+fn stage_interactively() {
+    let ctx = TestContext::setup_init();
+    commit(ctx.dir.path(), "firstfile", "testing\ntesttest\n");
+    commit(ctx.dir.path(), "secondfile", "testing\ntesttest\n");
+    fs::write(ctx.dir.child("firstfile"), "weehooo\ntesttest\n").unwrap();
+    fs::write(ctx.dir.child("secondfile"), "testing\nwoohaaa\n").unwrap();
+    snapshot!(ctx, "a");
+}
+
+#[test]
+fn stage_interactively_skip_then_stage() {
+    let ctx = TestContext::setup_init();
+    commit(ctx.dir.path(), "firstfile", "testing\ntesttest\n");
+    commit(ctx.dir.path(), "secondfile", "testing\ntesttest\n");
+    fs::write(ctx.dir.child("firstfile"), "weehooo\ntesttest\n").unwrap();
+    fs::write(ctx.dir.child("secondfile"), "testing\nwoohaaa\n").unwrap();
+    snapshot!(ctx, "any");
+}
+
+#[test]
+fn stage_interactively_unknown_answer() {
+    let ctx = TestContext::setup_init();
+    commit(ctx.dir.path(), "firstfile", "testing\ntesttest\n");
+    commit(ctx.dir.path(), "secondfile", "testing\ntesttest\n");
+    fs::write(ctx.dir.child("firstfile"), "weehooo\ntesttest\n").unwrap();
+    fs::write(ctx.dir.child("secondfile"), "testing\nwoohaaa\n").unwrap();
+    snapshot!(ctx, "axy");
+}
+
+#[test]
+fn stage_interactively_split() {
+    let ctx = TestContext::setup_init();
+    commit(ctx.dir.path(), "firstfile", "one\ntwo\nthree\nfour\n");
+    fs::write(ctx.dir.child("firstfile"), "ONE\ntwo\nthree\nFOUR\n").unwrap();
+
+    snapshot!(ctx, "asny");
+}
+
+#[test]
+fn stage_interactively_quit() {
+    let ctx = TestContext::setup_init();
+    commit(ctx.dir.path(), "firstfile", "testing\ntesttest\n");
+    fs::write(ctx.dir.child("firstfile"), "weehooo\ntesttest\n").unwrap();
+
+    snapshot!(ctx, "aq");
+}
+
 #[test]
 fn stage_changes_crlf() {
     let ctx = TestContext::setup_init();
@@ -54,3 +112,139 @@ fn stage_changes_crlf() {
 
     snapshot!(ctx, "jj<tab>");
 }
+
+#[test]
+fn mode_change_item() {
+    let ctx = TestContext::setup_init();
+    commit(ctx.dir.path(), "script", "echo hi\n");
+    fs::write(ctx.dir.child("script"), "echo hello\n").unwrap();
+    run(ctx.dir.path(), &["chmod", "+x", "script"]);
+    snapshot!(ctx, "jj<tab>");
+}
+
+#[test]
+fn stage_mode_change() {
+    let ctx = TestContext::setup_init();
+    commit(ctx.dir.path(), "script", "echo hi\n");
+    fs::write(ctx.dir.child("script"), "echo hello\n").unwrap();
+    run(ctx.dir.path(), &["chmod", "+x", "script"]);
+    snapshot!(ctx, "jj<tab>js");
+}
+
+#[test]
+fn stage_hunk_without_mode_change() {
+    let ctx = TestContext::setup_init();
+    commit(ctx.dir.path(), "script", "echo hi\n");
+    fs::write(ctx.dir.child("script"), "echo hello\n").unwrap();
+    run(ctx.dir.path(), &["chmod", "+x", "script"]);
+    snapshot!(ctx, "jj<tab>jjs");
+}
+
+#[test]
+fn symlink_target_changed() {
+    let ctx = TestContext::setup_init();
+    run(ctx.dir.path(), &["ln", "-s", "target", "link"]);
+    run(ctx.dir.path(), &["git", "add", "link"]);
+    run(ctx.dir.path(), &["git", "commit", "-m", "add link"]);
+    run(ctx.dir.path(), &["ln", "-sfn", "other-target", "link"]);
+    snapshot!(ctx, "jj<tab>");
+}
+
+#[test]
+fn stage_symlink_hunk() {
+    let ctx = TestContext::setup_init();
+    run(ctx.dir.path(), &["ln", "-s", "target", "link"]);
+    run(ctx.dir.path(), &["git", "add", "link"]);
+    run(ctx.dir.path(), &["git", "commit", "-m", "add link"]);
+    run(ctx.dir.path(), &["ln", "-sfn", "other-target", "link"]);
+    snapshot!(ctx, "jj<tab>js");
+}
+
+#[test]
+fn typechange_file_to_symlink() {
+    let ctx = TestContext::setup_init();
+    commit(ctx.dir.path(), "file", "content\n");
+    fs::remove_file(ctx.dir.child("file")).unwrap();
+    run(ctx.dir.path(), &["ln", "-s", "target", "file"]);
+    snapshot!(ctx, "jj<tab>");
+}
+
+#[test]
+fn stage_typechange() {
+    let ctx = TestContext::setup_init();
+    commit(ctx.dir.path(), "file", "content\n");
+    fs::remove_file(ctx.dir.child("file")).unwrap();
+    run(ctx.dir.path(), &["ln", "-s", "target", "file"]);
+    snapshot!(ctx, "jj<tab>s");
+}
+
+#[test]
+fn legacy_encoding() {
+    let ctx = TestContext::setup_init();
+    fs::write(ctx.dir.child("latin1.txt"), b"caf\xe9\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "."]);
+    run(ctx.dir.path(), &["git", "commit", "-m", "add latin1.txt"]);
+    fs::write(ctx.dir.child("latin1.txt"), b"caf\xe9\nna\xefve\n").unwrap();
+    snapshot!(ctx, "jj<tab>");
+}
+
+#[test]
+fn stage_legacy_encoding_hunk() {
+    let ctx = TestContext::setup_init();
+    fs::write(ctx.dir.child("latin1.txt"), b"caf\xe9\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "."]);
+    run(ctx.dir.path(), &["git", "commit", "-m", "add latin1.txt"]);
+    fs::write(ctx.dir.child("latin1.txt"), b"caf\xe9\nna\xefve\n").unwrap();
+    snapshot!(ctx, "jj<tab>js");
+}
+
+#[test]
+fn untracked_dir_collapsed() {
+    let ctx = TestContext::setup_init();
+    fs::create_dir_all(ctx.dir.child("new-dir/nested")).unwrap();
+    run(
+        ctx.dir.path(),
+        &["touch", "new-dir/file-a", "new-dir/nested/file-b"],
+    );
+    run(ctx.dir.path(), &["touch", "top-level-file"]);
+    snapshot!(ctx, "");
+}
+
+#[test]
+fn untracked_dir_expanded() {
+    let ctx = TestContext::setup_init();
+    fs::create_dir_all(ctx.dir.child("new-dir/nested")).unwrap();
+    run(
+        ctx.dir.path(),
+        &["touch", "new-dir/file-a", "new-dir/nested/file-b"],
+    );
+    run(ctx.dir.path(), &["touch", "top-level-file"]);
+    snapshot!(ctx, "jj<tab>");
+}
+
+#[test]
+fn stage_untracked_dir() {
+    let ctx = TestContext::setup_init();
+    fs::create_dir_all(ctx.dir.child("new-dir/nested")).unwrap();
+    run(
+        ctx.dir.path(),
+        &["touch", "new-dir/file-a", "new-dir/nested/file-b"],
+    );
+    run(ctx.dir.path(), &["touch", "top-level-file"]);
+    snapshot!(ctx, "jjs");
+}
+
+#[test]
+fn stage_hunk_staged_elsewhere() {
+    let mut ctx = TestContext::setup_clone();
+    commit(ctx.dir.path(), "file", "one\ntwo\nthree\n");
+    fs::write(ctx.dir.child("file"), "one\n2\nthree\n").unwrap();
+
+    let mut state = ctx.init_state();
+    fs::write(ctx.dir.child("file"), "1\ntwo\nthree\n").unwrap();
+    run(ctx.dir.path(), &["git", "add", "file"]);
+    fs::write(ctx.dir.child("file"), "one\n2\nthree\n").unwrap();
+
+    state.update(&mut ctx.term, &keys("jj<tab>js")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
@@ -23,6 +23,20 @@ pub(crate) fn stash() {
     snapshot!(setup(), "zztest<enter>");
 }
 
+#[test]
+pub(crate) fn stash_all() {
+    let mut ctx = setup();
+    fs::write(ctx.dir.child(".gitignore"), "ignored-file\n").unwrap();
+    fs::write(ctx.dir.child("ignored-file"), "").unwrap();
+
+    let mut state = ctx.init_state();
+    state
+        .update(&mut ctx.term, &keys("z-aztest<enter>"))
+        .unwrap();
+    assert!(!ctx.dir.child("ignored-file").exists());
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
 #[test]
 pub(crate) fn stash_index_prompt() {
     snapshot!(setup(), "zi");
@@ -65,6 +79,17 @@ pub(crate) fn stash_keeping_index() {
     snapshot!(setup(), "zxtest<enter>");
 }
 
+#[test]
+pub(crate) fn show_stash_staged_unstaged_untracked() {
+    let ctx = setup();
+    fs::write(ctx.dir.child("initial-file"), "changed\n").unwrap();
+    run(
+        ctx.dir.path(),
+        &["git", "stash", "push", "--include-untracked"],
+    );
+    snapshot!(ctx, "jj<enter>");
+}
+
 fn setup_two_stashes() -> TestContext {
     let ctx = setup();
     run(
@@ -130,3 +155,31 @@ pub(crate) fn stash_drop() {
 pub(crate) fn stash_drop_default() {
     snapshot!(setup_two_stashes(), "zk<enter>");
 }
+
+#[test]
+pub(crate) fn stash_pop_conflict_preview() {
+    let ctx = setup_two_stashes();
+    commit(ctx.dir.path(), "file-one", "conflicting\n");
+    snapshot!(ctx, "zp1<enter>");
+}
+
+#[test]
+pub(crate) fn stash_pop_conflict_abort() {
+    let ctx = setup_two_stashes();
+    commit(ctx.dir.path(), "file-one", "conflicting\n");
+    snapshot!(ctx, "zp1<enter>n");
+}
+
+#[test]
+pub(crate) fn stash_apply_overwrite_preview() {
+    let ctx = setup_two_stashes();
+    fs::write(ctx.dir.child("file-one"), "local\n").unwrap();
+    snapshot!(ctx, "za1<enter>");
+}
+
+#[test]
+pub(crate) fn stash_pop_untracked_overwrite_preview() {
+    let ctx = setup_two_stashes();
+    fs::write(ctx.dir.child("file-two"), "local\n").unwrap();
+    snapshot!(ctx, "zp<enter>");
+}
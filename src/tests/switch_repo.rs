@@ -0,0 +1,32 @@
+use super::*;
+use crate::state::State;
+
+fn setup() -> (TestContext, State) {
+    let mut ctx = TestContext::setup_clone();
+    let other = ctx.dir.child("other");
+    fs::create_dir(&other).unwrap();
+    run(&other, &["git", "init", "--initial-branch=other-main"]);
+    commit(&other, "other-file", "");
+
+    let mut state = ctx.init_state();
+    state.recent_repos = vec![ctx.dir.path().to_path_buf(), other];
+    (ctx, state)
+}
+
+#[test]
+fn switch_repo() {
+    let (mut ctx, mut state) = setup();
+    state.update(&mut ctx.term, &keys("O<tab><enter>")).unwrap();
+
+    assert_eq!(state.recent_repos[0], ctx.dir.child("other"));
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn switch_repo_not_found() {
+    let (mut ctx, mut state) = setup();
+    state
+        .update(&mut ctx.term, &keys("O/nonexistent<enter>"))
+        .unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
@@ -25,3 +25,29 @@ fn unstage_added_line() {
     run(ctx.dir.path(), &["git", "add", "."]);
     snapshot!(ctx, "jj<tab><ctrl+j><ctrl+j><ctrl+j><ctrl+j>u");
 }
+
+#[test]
+fn unstage_deleted_file_hunk() {
+    let ctx = TestContext::setup_init();
+    commit(ctx.dir.path(), "firstfile", "testing\ntesttest\n");
+    run(ctx.dir.path(), &["git", "rm", "firstfile"]);
+    snapshot!(ctx, "jj<tab>ju");
+}
+
+#[test]
+fn unstage_deleted_file_line() {
+    let ctx = TestContext::setup_init();
+    commit(ctx.dir.path(), "firstfile", "testing\ntesttest\n");
+    run(ctx.dir.path(), &["git", "rm", "firstfile"]);
+    snapshot!(ctx, "jj<tab><ctrl+j><ctrl+j>u");
+}
+
+#[test]
+fn unstage_mode_change() {
+    let ctx = TestContext::setup_init();
+    commit(ctx.dir.path(), "script", "echo hi\n");
+    fs::write(ctx.dir.child("script"), "echo hello\n").unwrap();
+    run(ctx.dir.path(), &["chmod", "+x", "script"]);
+    run(ctx.dir.path(), &["git", "add", "."]);
+    snapshot!(ctx, "jj<tab>ju");
+}
@@ -0,0 +1,68 @@
+use super::*;
+use crate::cli::Args;
+
+fn setup() -> (TestContext, Args) {
+    let ctx = TestContext::setup_clone();
+    let workspace = ctx.dir.child("workspace");
+    fs::create_dir(&workspace).unwrap();
+
+    let upstream = workspace.join("upstream");
+    fs::create_dir(&upstream).unwrap();
+    run(&upstream, &["git", "init", "--initial-branch=main"]);
+    commit(&upstream, "upstream-file", "");
+    fs::write(upstream.join("untracked-file"), "").unwrap();
+
+    run(&workspace, &["git", "clone", "upstream", "clone"]);
+    let clone = workspace.join("clone");
+    commit(&clone, "clone-file", "");
+
+    fs::create_dir(workspace.join("not-a-repo")).unwrap();
+
+    let args = Args {
+        workspace: Some(vec![workspace]),
+        ..Default::default()
+    };
+
+    (ctx, args)
+}
+
+#[test]
+fn workspace() {
+    let (mut ctx, args) = setup();
+    ctx.init_state_with_args(&args);
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn workspace_open_repo() {
+    let (mut ctx, args) = setup();
+    let mut state = ctx.init_state_with_args(&args);
+    state.update(&mut ctx.term, &keys("j<enter>")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn workspace_back_from_repo() {
+    let (mut ctx, args) = setup();
+    let mut state = ctx.init_state_with_args(&args);
+    state.update(&mut ctx.term, &keys("j<enter>q")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn workspace_disables_repo_ops() {
+    let (mut ctx, args) = setup();
+    let mut state = ctx.init_state_with_args(&args);
+    state.update(&mut ctx.term, &keys("cc")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
+
+#[test]
+fn workspace_without_repo() {
+    let (mut ctx, args) = setup();
+    let mut state = ctx.init_state_without_repo(&args);
+    state.update(&mut ctx.term, &keys("s")).unwrap();
+    assert!(state.repo.is_none());
+    state.update(&mut ctx.term, &keys("j<enter>")).unwrap();
+    insta::assert_snapshot!(ctx.redact_buffer());
+}
@@ -6,8 +6,15 @@ use ratatui::Frame;
 use tui_prompts::State as _;
 use tui_prompts::TextPrompt;
 
+mod key_hints;
 mod menu;
 
+/// Max amount of lines of a confirmation preview shown at once.
+const MAX_PREVIEW_LINES: usize = 10;
+
+/// Max amount of lines of command output shown below the screen, the rest is in the command log.
+const MAX_LOG_LINES: usize = 10;
+
 pub(crate) struct SizedWidget<W> {
     height: u16,
     widget: W,
@@ -29,11 +36,24 @@ impl<W: StatefulWidget> StatefulWidget for SizedWidget<W> {
 
 pub(crate) fn ui(frame: &mut Frame, state: &mut State) {
     let maybe_log = if !state.current_cmd_log.is_empty() {
-        let text: Text = state.current_cmd_log.format_log(&state.config);
+        let mut lines = state.current_cmd_log.format_log(&state.config).lines;
+
+        // The end of the output is kept, as that's where errors usually are
+        if lines.len() > MAX_LOG_LINES {
+            let hidden = lines.len() + 1 - MAX_LOG_LINES;
+            lines.drain(..hidden);
+            lines.insert(
+                0,
+                Line::styled(
+                    format!("… {} more lines in the command log", hidden),
+                    Style::new().dim(),
+                ),
+            );
+        }
 
         Some(SizedWidget {
-            widget: Paragraph::new(text.clone()).block(popup_block()),
-            height: 1 + text.lines.len() as u16,
+            height: 1 + lines.len() as u16,
+            widget: Paragraph::new(lines).block(popup_block()),
         })
     } else {
         None
@@ -44,6 +64,48 @@ pub(crate) fn ui(frame: &mut Frame, state: &mut State) {
         widget: TextPrompt::new(prompt_data.prompt_text.clone()).with_block(popup_block()),
     });
 
+    // Once nothing matches, only the input as typed is left
+    let maybe_picker = state.prompt.picker.as_ref().and_then(|picker| {
+        let style = &state.config.style;
+        let lines = picker
+            .visible()
+            .map(|(candidate, selected)| {
+                if selected {
+                    Line::from(vec![
+                        Span::styled(style.cursor.symbol.to_string(), &style.cursor),
+                        Span::styled(candidate.to_string(), &style.selection_line),
+                    ])
+                } else {
+                    Line::raw(format!(" {}", candidate))
+                }
+            })
+            .collect::<Vec<_>>();
+
+        (!lines.is_empty()).then(|| SizedWidget {
+            height: 1 + lines.len() as u16,
+            widget: Paragraph::new(lines).block(popup_block()),
+        })
+    });
+
+    let maybe_preview = state.prompt.preview.as_ref().map(|preview| {
+        let mut lines = preview
+            .iter()
+            .take(MAX_PREVIEW_LINES)
+            .cloned()
+            .collect::<Vec<_>>();
+        if preview.len() > MAX_PREVIEW_LINES {
+            lines.push(Line::styled(
+                format!("… {} more lines", preview.len() - MAX_PREVIEW_LINES),
+                Style::new().dim(),
+            ));
+        }
+
+        SizedWidget {
+            height: 1 + lines.len() as u16,
+            widget: Paragraph::new(lines).block(popup_block()),
+        }
+    });
+
     let maybe_menu = state.pending_menu.as_ref().and_then(|menu| {
         if menu.is_hidden {
             None
@@ -58,11 +120,29 @@ pub(crate) fn ui(frame: &mut Frame, state: &mut State) {
         }
     });
 
+    let maybe_key_hints = key_hints::key_hints(state);
+
+    let maybe_breadcrumbs = state
+        .config
+        .general
+        .breadcrumbs
+        .enabled
+        .then(|| state.screens.last().unwrap().breadcrumbs())
+        .flatten()
+        .map(|line| SizedWidget {
+            height: 2,
+            widget: Paragraph::new(line).block(popup_block()),
+        });
+
     let layout = Layout::new(
         Direction::Vertical,
         [
             Constraint::Min(1),
+            widget_height(&maybe_breadcrumbs),
+            widget_height(&maybe_preview),
+            widget_height(&maybe_picker),
             widget_height(&maybe_prompt),
+            widget_height(&maybe_key_hints),
             widget_height(&maybe_menu),
             widget_height(&maybe_log),
         ],
@@ -71,11 +151,15 @@ pub(crate) fn ui(frame: &mut Frame, state: &mut State) {
 
     frame.render_widget(state.screens.last().unwrap(), layout[0]);
 
-    maybe_render(maybe_menu, frame, layout[2]);
-    maybe_render(maybe_log, frame, layout[3]);
+    maybe_render(maybe_breadcrumbs, frame, layout[1]);
+    maybe_render(maybe_preview, frame, layout[2]);
+    maybe_render(maybe_picker, frame, layout[3]);
+    maybe_render(maybe_key_hints, frame, layout[5]);
+    maybe_render(maybe_menu, frame, layout[6]);
+    maybe_render(maybe_log, frame, layout[7]);
 
     if let Some(prompt) = maybe_prompt {
-        frame.render_stateful_widget(prompt, layout[1], &mut state.prompt.state);
+        frame.render_stateful_widget(prompt, layout[4], &mut state.prompt.state);
         let (cx, cy) = state.prompt.state.cursor();
         frame.set_cursor_position((cx, cy));
     }
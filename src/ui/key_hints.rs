@@ -0,0 +1,42 @@
+use super::{popup_block, SizedWidget};
+use crate::{ops::Op, state::State};
+use ratatui::{
+    style::Style,
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+
+/// What the keys pressed so far can go on with, like which-key does in Emacs.
+pub(crate) fn key_hints(state: &State) -> Option<SizedWidget<Paragraph<'static>>> {
+    if !state.key_hints_visible() {
+        return None;
+    }
+
+    let style = &state.config.style;
+    let menu = state.bindings_menu();
+    let lines = state
+        .bindings
+        .match_bindings(&menu, &state.pending_keys)
+        .map(|binding| {
+            let display = match &binding.op {
+                Op::ToggleArg(name) => state
+                    .pending_menu
+                    .as_ref()
+                    .and_then(|pending| pending.args.get(name.as_str()))
+                    .map(|arg| format!("{} ({})", arg.display, arg.get_cli_token()))
+                    .unwrap_or_else(|| name.clone()),
+                op => op.clone().implementation().display(state),
+            };
+
+            Line::from(vec![
+                Span::styled(binding.raw.clone(), &style.hotkey),
+                Span::styled(format!(" {}", display), Style::new()),
+            ])
+        })
+        .collect::<Vec<_>>();
+
+    Some(SizedWidget {
+        height: 1 + lines.len() as u16,
+        widget: Paragraph::new(lines).block(popup_block()),
+    })
+}
@@ -76,6 +76,10 @@ impl<'a> MenuWidget<'a> {
         }
 
         let mut right_column = vec![];
+        if let Some(summary) = &pending.summary {
+            right_column.push(Line::raw(summary.as_str()));
+        }
+
         if let Some(target_data) = &item.target_data {
             let target_binds = bindings
                 .list(&pending.menu)